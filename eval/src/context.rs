@@ -2,12 +2,18 @@ use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use parser::ast::Expression;
 
-use crate::{errors::RuntimeErrorKind, value::JmlValue};
+use crate::{
+    errors::RuntimeErrorKind,
+    logging::{LogSink, Logger},
+    value::JmlValue,
+};
 
 #[derive(Debug, Default, Clone)]
 pub struct Context<'source> {
     bindings: HashMap<String, RefCell<Binding<'source>>>,
     parent: Option<Rc<Context<'source>>>,
+    strict: bool,
+    logger: Logger<'source>,
 }
 
 // planning for lazy evaluation here
@@ -35,10 +41,50 @@ impl<'source> Context<'source> {
     pub fn new_with_parent(parent: Rc<Context<'source>>) -> Self {
         Context {
             bindings: HashMap::new(),
+            strict: parent.strict,
+            logger: parent.logger.clone(),
             parent: Some(parent),
         }
     }
 
+    /// In strict mode a missing key or an out-of-range index is a runtime
+    /// error instead of `null`. Scopes created from this context inherit it.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Sends what the script logs to `sink` instead of `tracing`. Scopes
+    /// created from this context inherit it.
+    pub fn set_log_sink(&mut self, sink: impl LogSink + 'static) {
+        self.logger.sink = Rc::new(sink);
+    }
+
+    /// Names the script in its log records, usually after its file.
+    pub fn set_file_name(&mut self, name: impl Into<String>) {
+        self.logger.file = Some(name.into().into());
+    }
+
+    /// The source log records compute their line and column from. The
+    /// `eval_*_source` functions set it.
+    pub fn set_source(&mut self, source: &'source str) {
+        self.logger.sources = Rc::new(vec![(0, source)]);
+    }
+
+    /// Adds a source whose spans start at `offset`, for hosts that parse
+    /// several texts as parts of one, as the REPL does with its entries.
+    /// Sources have to be added in order of their offsets.
+    pub fn add_source(&mut self, offset: usize, source: &'source str) {
+        Rc::make_mut(&mut self.logger.sources).push((offset, source));
+    }
+
+    pub(crate) fn logger(&self) -> &Logger<'source> {
+        &self.logger
+    }
+
     pub fn bind_with_expr<N>(&mut self, name: N, expr: Expression<'source>)
     where
         N: Into<String>,
@@ -58,6 +104,11 @@ impl<'source> Context<'source> {
             .or_insert(RefCell::new(Binding::new_with_value(value)));
     }
 
+    /// Names bound directly in this context, excluding its parents.
+    pub fn names(&self) -> Vec<&str> {
+        self.bindings.keys().map(String::as_str).collect()
+    }
+
     pub fn lookup_variable<N>(&self, name: N) -> Result<Binding<'source>, RuntimeErrorKind>
     where
         N: AsRef<str>,
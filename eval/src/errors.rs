@@ -14,7 +14,6 @@ pub enum EvalError {
 
 #[derive(Error, Diagnostic, Debug)]
 #[error("type error")]
-
 pub struct TypeError {
     #[label("Error occurred here")]
     pub span: SourceSpan,
@@ -64,6 +63,15 @@ pub enum TypeErrorKind {
         help("Ensure the operator '{operator}' is used with compatible type.")
     )]
     InvalidUnaryOperator { operator: String, right: JmlType },
+
+    #[error("Slice {bound} must be an Int or null, found {found}")]
+    #[diagnostic(
+        code(type_error::invalid_slice_bound),
+        help(
+            "Slice bounds are integers that count from the end when negative, as in `items[1:-1]`."
+        )
+    )]
+    InvalidSliceBound { bound: String, found: JmlType },
 }
 
 #[derive(Error, Diagnostic, Debug)]
@@ -101,6 +109,59 @@ pub enum RuntimeErrorKind {
     )]
     Overflow,
 
+    #[error("{operation} is not a finite number")]
+    #[diagnostic(
+        code(eval::not_finite),
+        help("Check that the argument is within the domain of the function.")
+    )]
+    NotFinite { operation: String },
+
+    #[error("Key '{key}' not found, available keys: {available}")]
+    #[diagnostic(code(eval::missing_key))]
+    MissingKey {
+        key: String,
+        available: String,
+        #[help]
+        suggestion: Option<String>,
+    },
+
+    #[error("Index {index} is out of bounds for length {length}")]
+    #[diagnostic(code(eval::index_out_of_bounds))]
+    IndexOutOfBounds { index: i64, length: usize },
+
+    #[error("Slice step cannot be zero")]
+    #[diagnostic(
+        code(eval::slice_step_zero),
+        help("Use a positive step to go forwards or a negative one to go backwards.")
+    )]
+    SliceStepZero,
+
+    #[error("Invalid query '{path}' at offset {offset}: {reason}")]
+    #[diagnostic(
+        code(eval::invalid_query),
+        help("Queries are JSONPath expressions such as `$.items[*].id`, `$..id` or `$.items[?(@.price < 10)]`.")
+    )]
+    InvalidQuery {
+        path: String,
+        offset: usize,
+        reason: String,
+    },
+
+    #[error("Invalid {encoding}: {reason}")]
+    #[diagnostic(
+        code(eval::invalid_encoding),
+        help("Decoded text must also be valid UTF-8, since strings can't hold arbitrary bytes.")
+    )]
+    InvalidEncoding { encoding: String, reason: String },
+
+    #[error("Invalid argument '{name}': {reason}")]
+    #[diagnostic(code(eval::invalid_argument))]
+    InvalidArgument { name: String, reason: String },
+
+    #[error("Assertion failed: {message}")]
+    #[diagnostic(code(eval::assertion_failed))]
+    AssertionFailed { message: String },
+
     #[error("{message}")]
     #[diagnostic(code(runtime_error::generic_runtime_error))]
     GenericError { message: String },
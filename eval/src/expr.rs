@@ -1,4 +1,5 @@
 use binary_op::eval_binary_op;
+use comprehension::{eval_list_comprehension, eval_object_comprehension};
 use if_expr::eval_if_expr;
 use lambda::{eval_lambda_application, eval_lambda_defenition};
 use list_constructor::eval_list;
@@ -8,12 +9,13 @@ use unary_op::eval_unary_op;
 
 use crate::{
     context::Context,
-    errors::{EvalError, RuntimeError, TypeError, TypeErrorKind},
+    errors::{EvalError, RuntimeError, RuntimeErrorKind, TypeError, TypeErrorKind},
     jml_type::JmlType,
     value::JmlValue,
 };
 
 pub mod binary_op;
+pub mod comprehension;
 pub mod if_expr;
 pub mod lambda;
 pub mod list_constructor;
@@ -34,11 +36,20 @@ pub fn eval_expr<'source>(
         parser::ast::ExpressionKind::String(v) => Ok(JmlValue::string(v)),
         parser::ast::ExpressionKind::Object(data) => eval_object(data, ctx),
         parser::ast::ExpressionKind::List(elems) => eval_list(elems, ctx),
+        parser::ast::ExpressionKind::ListComprehension { element, clauses } => {
+            eval_list_comprehension(*element, clauses, ctx)
+        }
+        parser::ast::ExpressionKind::ObjectComprehension {
+            key,
+            value,
+            clauses,
+        } => eval_object_comprehension(*key, *value, clauses, ctx),
         parser::ast::ExpressionKind::Variable(ident) => eval_variable(span, ident, ctx),
-        parser::ast::ExpressionKind::IndexAccess { target, index } => {
-            eval_index_access(*target, *index, ctx)
+        node @ (parser::ast::ExpressionKind::IndexAccess { .. }
+        | parser::ast::ExpressionKind::Slice { .. }
+        | parser::ast::ExpressionKind::Selector { .. }) => {
+            Ok(eval_chain(Expression { l, r, node }, ctx)?.unwrap_or(JmlValue::Null))
         }
-        parser::ast::ExpressionKind::Selector { target, key } => eval_selector(*target, key, ctx),
         parser::ast::ExpressionKind::UnaryOp { op, expr } => eval_unary_op(span, op, *expr, ctx),
         parser::ast::ExpressionKind::BinaryOp { op, lhs, rhs } => {
             eval_binary_op(span, op, *lhs, *rhs, ctx)
@@ -79,19 +90,60 @@ where
     }
 }
 
-fn eval_selector<'source, I>(
+/// Evaluates a link of an access chain like `a?.b.c[0]`, or `None` once an
+/// optional access has met `null`, which skips the rest of the chain.
+fn eval_chain<'source>(
+    expression: Expression<'source>,
+    ctx: &mut Context<'source>,
+) -> Result<Option<JmlValue<'source>>, EvalError> {
+    let Expression { l, r, node } = expression;
+    let span = (l, r - l);
+    match node {
+        parser::ast::ExpressionKind::IndexAccess {
+            target,
+            index,
+            optional,
+        } => eval_index_access(span, *target, *index, optional, ctx),
+        parser::ast::ExpressionKind::Slice {
+            target,
+            start,
+            end,
+            step,
+            optional,
+        } => eval_slice(span, *target, [start, end, step], optional, ctx),
+        parser::ast::ExpressionKind::Selector {
+            target,
+            key,
+            optional,
+        } => eval_selector(span, *target, key, optional, ctx),
+        node => eval_expr(Expression { l, r, node }, ctx).map(Some),
+    }
+}
+
+fn eval_selector<'source, S, I>(
+    span: S,
     target: Expression<'source>,
     key: I,
+    optional: bool,
     ctx: &mut Context<'source>,
-) -> Result<JmlValue<'source>, EvalError>
+) -> Result<Option<JmlValue<'source>>, EvalError>
 where
+    S: Into<miette::SourceSpan>,
     I: AsRef<str>,
 {
     let target_l = target.l;
     let target_r = target.r;
-    let val = eval_expr(target, ctx)?;
+    let Some(val) = eval_chain(target, ctx)? else {
+        return Ok(None);
+    };
     match val {
-        JmlValue::Object(ob) => Ok(ob.access_by_key(key.as_ref())),
+        // `?.` stays lenient about missing keys even in strict mode.
+        JmlValue::Object(ob) if ctx.is_strict() && !optional => ob
+            .try_access_by_key(key)
+            .map(Some)
+            .map_err(|kind| strict_error(span, kind)),
+        JmlValue::Object(ob) => Ok(Some(ob.access_by_key(key.as_ref()))),
+        JmlValue::Null if optional => Ok(None),
         _ => {
             let type_error = TypeError {
                 span: (target_l, target_r - target_l).into(),
@@ -105,39 +157,67 @@ where
     }
 }
 
-fn eval_index_access<'source>(
+fn eval_index_access<'source, S>(
+    span: S,
     target: Expression<'source>,
     index: Expression<'source>,
+    optional: bool,
     ctx: &mut Context<'source>,
-) -> Result<JmlValue<'source>, EvalError> {
+) -> Result<Option<JmlValue<'source>>, EvalError>
+where
+    S: Into<miette::SourceSpan>,
+{
+    let strict = ctx.is_strict() && !optional;
     let index_l = index.l;
     let index_r = index.r;
 
     let target_l = target.l;
     let target_r = target.r;
-    let target_val = eval_expr(target, ctx)?;
-    match &target_val {
+    let Some(target_val) = eval_chain(target, ctx)? else {
+        return Ok(None);
+    };
+    let value = match &target_val {
         JmlValue::List(v) => {
             let index: i64 = eval_expr(index, ctx)?.try_into().map_err(|e| TypeError {
                 span: (index_l, index_r - index_l).into(),
                 kind: e,
             })?;
-            Ok(v.access_by_index(index as usize))
+            if strict {
+                return v
+                    .try_access_by_index(index)
+                    .map(Some)
+                    .map_err(|kind| strict_error(span, kind));
+            }
+            v.access_by_index(index)
         }
         JmlValue::String(v) => {
             let index: i64 = eval_expr(index, ctx)?.try_into().map_err(|e| TypeError {
                 span: (index_l, index_r - index_l).into(),
                 kind: e,
             })?;
-            Ok(v.get_by_index(index as usize))
+            if strict {
+                return v
+                    .try_get_by_index(index)
+                    .map(Some)
+                    .map_err(|kind| strict_error(span, kind));
+            }
+            v.get_by_index(index)
         }
         JmlValue::Object(v) => {
             let key: String = eval_expr(index, ctx)?.try_into().map_err(|e| TypeError {
                 span: (index_l, index_r - index_l).into(),
                 kind: e,
             })?;
-            Ok(v.access_by_key(key))
+            if strict {
+                return v
+                    .try_access_by_key(key)
+                    .map(Some)
+                    .map_err(|kind| strict_error(span, kind));
+            }
+            v.access_by_key(key)
         }
+        // The index is not evaluated when there is nothing to index.
+        JmlValue::Null if optional => return Ok(None),
         _ => {
             let type_error = TypeError {
                 span: (target_l, target_r - target_l).into(),
@@ -148,5 +228,92 @@ fn eval_index_access<'source>(
             };
             Err(type_error)?
         }
+    };
+    Ok(Some(value))
+}
+
+fn eval_slice<'source, S>(
+    span: S,
+    target: Expression<'source>,
+    bounds: [Option<Box<Expression<'source>>>; 3],
+    optional: bool,
+    ctx: &mut Context<'source>,
+) -> Result<Option<JmlValue<'source>>, EvalError>
+where
+    S: Into<miette::SourceSpan>,
+{
+    let target_l = target.l;
+    let target_r = target.r;
+    let Some(target_val) = eval_chain(target, ctx)? else {
+        return Ok(None);
+    };
+
+    if !matches!(target_val, JmlValue::List(_) | JmlValue::String(_)) {
+        // Like `?[`, the bounds are not evaluated when there is nothing to slice.
+        if optional && target_val == JmlValue::Null {
+            return Ok(None);
+        }
+        return Err(TypeError {
+            span: (target_l, target_r - target_l).into(),
+            kind: TypeErrorKind::MismatchedTypes {
+                expected: vec![JmlType::List, JmlType::String],
+                found: target_val.type_of(),
+            },
+        }
+        .into());
+    }
+
+    let [start, end, step] = bounds;
+    let start = eval_slice_bound(start, "start", ctx)?;
+    let end = eval_slice_bound(end, "end", ctx)?;
+    let step = eval_slice_bound(step, "step", ctx)?.unwrap_or(1);
+
+    let sliced = match target_val {
+        JmlValue::List(v) => v.slice(start, end, step).map(JmlValue::from),
+        JmlValue::String(v) => v.slice(start, end, step).map(JmlValue::from),
+        _ => unreachable!("checked above"),
+    };
+    sliced.map(Some).map_err(|kind| {
+        RuntimeError {
+            span: span.into(),
+            kind,
+        }
+        .into()
+    })
+}
+
+/// A left out or `null` bound falls back to its default.
+fn eval_slice_bound<'source>(
+    bound: Option<Box<Expression<'source>>>,
+    name: &str,
+    ctx: &mut Context<'source>,
+) -> Result<Option<i64>, EvalError> {
+    let Some(bound) = bound else {
+        return Ok(None);
+    };
+    let bound_l = bound.l;
+    let bound_r = bound.r;
+
+    match eval_expr(*bound, ctx)? {
+        JmlValue::Null => Ok(None),
+        JmlValue::Int(v) => Ok(Some(v.0)),
+        other => Err(TypeError {
+            span: (bound_l, bound_r - bound_l).into(),
+            kind: TypeErrorKind::InvalidSliceBound {
+                bound: name.to_owned(),
+                found: other.type_of(),
+            },
+        })?,
+    }
+}
+
+fn strict_error<S>(span: S, kind: RuntimeErrorKind) -> EvalError
+where
+    S: Into<miette::SourceSpan>,
+{
+    RuntimeError {
+        span: span.into(),
+        kind,
     }
+    .into()
 }
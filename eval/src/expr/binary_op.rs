@@ -1,11 +1,14 @@
+use std::cmp::Ordering;
+
 use anyhow::Error;
+use indexmap::IndexMap;
 use miette::Result;
 use parser::ast::{BinaryOp, Expression};
 
 use crate::{
     context::Context,
     errors::{EvalError, RuntimeError, RuntimeErrorKind, TypeError, TypeErrorKind},
-    value::{list::JmlList, object::JmlObject, JmlValue},
+    value::{compare_int_float, list::JmlList, object::JmlObject, JmlValue},
 };
 
 use super::eval_expr;
@@ -18,6 +21,15 @@ pub fn eval_binary_op<'source>(
     ctx: &mut Context<'source>,
 ) -> Result<JmlValue<'source>, EvalError> {
     let lhs = eval_expr(lhs, ctx)?;
+
+    // `??` only evaluates its right-hand side when the left one is null.
+    if op == BinaryOp::Coalesce {
+        return match lhs {
+            JmlValue::Null => eval_expr(rhs, ctx),
+            lhs => Ok(lhs),
+        };
+    }
+
     let rhs = eval_expr(rhs, ctx)?;
 
     match op {
@@ -51,27 +63,9 @@ pub fn eval_binary_op<'source>(
             }
             .into()
         }),
-        BinaryOp::Sum => add(lhs, rhs).map_err(|e| {
-            TypeError {
-                span: span.into(),
-                kind: e,
-            }
-            .into()
-        }),
-        BinaryOp::Sub => subtract(lhs, rhs).map_err(|e| {
-            TypeError {
-                span: span.into(),
-                kind: e,
-            }
-            .into()
-        }),
-        BinaryOp::Mul => multiply(lhs, rhs).map_err(|e| {
-            TypeError {
-                span: span.into(),
-                kind: e,
-            }
-            .into()
-        }),
+        BinaryOp::Sum => add(lhs, rhs).map_err(|e| map_anyhow(e, span)),
+        BinaryOp::Sub => subtract(lhs, rhs).map_err(|e| map_anyhow(e, span)),
+        BinaryOp::Mul => multiply(lhs, rhs).map_err(|e| map_anyhow(e, span)),
         BinaryOp::Div => divide(lhs, rhs).map_err(|e| map_anyhow(e, span)),
         BinaryOp::Pow => pow(lhs, rhs).map_err(|e| map_anyhow(e, span)),
         BinaryOp::Mod => mod_op(lhs, rhs).map_err(|e| map_anyhow(e, span)),
@@ -96,6 +90,7 @@ pub fn eval_binary_op<'source>(
             }
             .into()
         }),
+        BinaryOp::Coalesce => unreachable!("handled above"),
     }
 }
 
@@ -121,7 +116,7 @@ fn map_anyhow(e: Error, span: impl Into<miette::SourceSpan>) -> EvalError {
 
 macro_rules! ord_op {
     ($func_name:ident, $operator:tt, $op_str:expr) => {
-        fn $func_name<'a>(lhs: JmlValue, rhs: JmlValue) -> Result<JmlValue<'a>, TypeErrorKind> {
+        fn $func_name<'a, 'source>(lhs: JmlValue<'source>, rhs: JmlValue<'source>) -> Result<JmlValue<'a>, TypeErrorKind> {
 
             if !&lhs.is_ord() {
                 Err(TypeErrorKind::NotOrderedType {found: lhs.type_of()})?
@@ -132,12 +127,13 @@ macro_rules! ord_op {
             }
 
             match (&lhs, &rhs) {
-                (JmlValue::Float(lhs), JmlValue::Float(rhs)) => Ok(JmlValue::bool(lhs.0 $operator rhs.0)),
-                (JmlValue::Float(lhs), JmlValue::Int(rhs)) => Ok(JmlValue::bool(lhs.0 $operator rhs.0 as f64)),
-                (JmlValue::Int(lhs), JmlValue::Float(rhs)) => Ok(JmlValue::bool((lhs.0 as f64) $operator rhs.0 )),
-                (JmlValue::Int(lhs), JmlValue::Int(rhs)) =>Ok(JmlValue::bool(lhs.0 $operator rhs.0)),
-                (JmlValue::Bool(lhs), JmlValue::Bool(rhs)) => Ok(JmlValue::bool(lhs.0 $operator rhs.0)),
-                (JmlValue::String(lhs), JmlValue::String(rhs)) => Ok(JmlValue::bool(lhs.0 $operator rhs.0)),
+                _ if (lhs.type_of().is_number() && rhs.type_of().is_number())
+                    || lhs.type_of() == rhs.type_of() =>
+                {
+                    Ok(JmlValue::bool(
+                        compare_by_value(&lhs, &rhs).is_some_and(|ordering| ordering $operator Ordering::Equal),
+                    ))
+                }
                 _ => Err(TypeErrorKind::InvalidBinaryOperator {
                     operator: $op_str.to_string(),
                     left: lhs.type_of(),
@@ -153,6 +149,64 @@ ord_op!(greater_equal, >=, ">=");
 ord_op!(less, <, "<");
 ord_op!(less_equal, <=, "<=");
 
+/// Orders two values for `<` and the other comparison operators. Numbers
+/// compare by value at any depth, so `[1] < [1.0]` is false like `1 < 1.0`,
+/// and NaN is neither below nor above anything. Otherwise values follow the
+/// total order: `null` equals `null`, and elements of different types in lists
+/// and objects are ordered by type.
+fn compare_by_value<'source>(lhs: &JmlValue<'source>, rhs: &JmlValue<'source>) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (JmlValue::Int(lhs), JmlValue::Int(rhs)) => Some(lhs.value().cmp(&rhs.value())),
+        (JmlValue::Float(lhs), JmlValue::Float(rhs)) => lhs.value().partial_cmp(&rhs.value()),
+        (JmlValue::Int(lhs), JmlValue::Float(rhs)) => {
+            (!rhs.value().is_nan()).then(|| compare_int_float(lhs.value(), rhs.value()))
+        }
+        (JmlValue::Float(lhs), JmlValue::Int(rhs)) => {
+            (!lhs.value().is_nan()).then(|| compare_int_float(rhs.value(), lhs.value()).reverse())
+        }
+        (JmlValue::List(lhs), JmlValue::List(rhs)) => {
+            for (lhs, rhs) in lhs.iter().zip(rhs.iter()) {
+                match compare_by_value(lhs, rhs)? {
+                    Ordering::Equal => {}
+                    ordering => return Some(ordering),
+                }
+            }
+            Some(lhs.len().cmp(&rhs.len()))
+        }
+        (JmlValue::Object(lhs), JmlValue::Object(rhs)) => {
+            let (lhs, rhs) = (lhs.sorted_entries(), rhs.sorted_entries());
+            for ((lhs_key, lhs), (rhs_key, rhs)) in lhs.iter().zip(rhs.iter()) {
+                let ordering = match lhs_key.cmp(rhs_key) {
+                    Ordering::Equal => compare_by_value(lhs, rhs)?,
+                    ordering => ordering,
+                };
+                if ordering != Ordering::Equal {
+                    return Some(ordering);
+                }
+            }
+            Some(lhs.len().cmp(&rhs.len()))
+        }
+        _ => Some(lhs.cmp(rhs)),
+    }
+}
+
+/// Orders two values by the total order of values, for sorting. Only
+/// lambdas can't be sorted.
+pub(crate) fn compare<'source>(
+    lhs: &JmlValue<'source>,
+    rhs: &JmlValue<'source>,
+) -> Result<Ordering, TypeErrorKind> {
+    for value in [lhs, rhs] {
+        if !value.is_ord() {
+            Err(TypeErrorKind::NotOrderedType {
+                found: value.type_of(),
+            })?
+        }
+    }
+
+    Ok(lhs.cmp(rhs))
+}
+
 macro_rules! logical_op {
     ($func_name:ident, $operator:tt, $op_str:expr) => {
         fn $func_name<'a>(lhs: JmlValue, rhs: JmlValue) -> Result<JmlValue<'a>, TypeErrorKind> {
@@ -181,7 +235,10 @@ macro_rules! arithmetic_op {
                 (JmlValue::Float(lhs), JmlValue::Float(rhs)) => Ok(JmlValue::float(lhs.0 / rhs.0)),
                 (JmlValue::Float(lhs), JmlValue::Int(rhs)) => Ok(JmlValue::float(lhs.0 / rhs.0 as f64)),
                 (JmlValue::Int(lhs), JmlValue::Float(rhs)) => Ok(JmlValue::float(lhs.0 as f64 / rhs.0 )),
-                (JmlValue::Int(lhs), JmlValue::Int(rhs)) =>Ok(JmlValue::int(lhs.0 / rhs.0)),
+                (JmlValue::Int(lhs), JmlValue::Int(rhs)) => match lhs.0.checked_div(rhs.0) {
+                    Some(result) => Ok(JmlValue::int(result)),
+                    None => Err(RuntimeErrorKind::Overflow)?,
+                },
                 _ => Err(TypeErrorKind::InvalidBinaryOperator {
                     operator: "/".to_owned(),
                     left: lhs.type_of(),
@@ -201,7 +258,8 @@ macro_rules! arithmetic_op {
                 (JmlValue::Float(lhs), JmlValue::Float(rhs)) => Ok(JmlValue::float(lhs.0 % rhs.0)),
                 (JmlValue::Float(lhs), JmlValue::Int(rhs)) => Ok(JmlValue::float(lhs.0 % rhs.0 as f64)),
                 (JmlValue::Int(lhs), JmlValue::Float(rhs)) => Ok(JmlValue::float(lhs.0 as f64 % rhs.0 )),
-                (JmlValue::Int(lhs), JmlValue::Int(rhs)) =>Ok(JmlValue::int(lhs.0 % rhs.0)),
+                // Only `i64::MIN % -1` wraps, and its remainder is 0 all the same.
+                (JmlValue::Int(lhs), JmlValue::Int(rhs)) => Ok(JmlValue::int(lhs.0.wrapping_rem(rhs.0))),
                 _ => Err(TypeErrorKind::InvalidBinaryOperator {
                     operator: "%".to_owned(),
                     left: lhs.type_of(),
@@ -232,26 +290,29 @@ macro_rules! arithmetic_op {
             }
         }
     };
-    ($func_name:ident, $operator:tt, $op_str:expr) => {
-        fn $func_name<'a>(lhs: JmlValue, rhs: JmlValue) -> Result<JmlValue<'a>, TypeErrorKind> {
+    ($func_name:ident, $operator:tt, $checked:ident, $op_str:expr) => {
+        fn $func_name<'a>(lhs: JmlValue, rhs: JmlValue) -> anyhow::Result<JmlValue<'a>> {
             match (&lhs, &rhs) {
                 (JmlValue::Float(lhs), JmlValue::Float(rhs)) => Ok(JmlValue::float(lhs.0 $operator rhs.0)),
                 (JmlValue::Float(lhs), JmlValue::Int(rhs)) => Ok(JmlValue::float(lhs.0 $operator rhs.0 as f64)),
                 (JmlValue::Int(lhs), JmlValue::Float(rhs)) => Ok(JmlValue::float(lhs.0 as f64 $operator rhs.0 )),
-                (JmlValue::Int(lhs), JmlValue::Int(rhs)) =>Ok(JmlValue::int(lhs.0 $operator rhs.0)),
+                (JmlValue::Int(lhs), JmlValue::Int(rhs)) => match lhs.0.$checked(rhs.0) {
+                    Some(result) => Ok(JmlValue::int(result)),
+                    None => Err(RuntimeErrorKind::Overflow)?,
+                },
                 _ => Err(TypeErrorKind::InvalidBinaryOperator {
                     operator: $op_str.to_string(),
                     left: lhs.type_of(),
                     right: rhs.type_of(),
-                }),
+                })?,
             }
         }
     };
 }
 
-arithmetic_op!(add, +, "+");
-arithmetic_op!(subtract, -, "-");
-arithmetic_op!(multiply, *, "*");
+arithmetic_op!(add, +, checked_add, "+");
+arithmetic_op!(subtract, -, checked_sub, "-");
+arithmetic_op!(multiply, *, checked_mul, "*");
 arithmetic_op!(mod_op, %);
 arithmetic_op!(divide, /);
 arithmetic_op!(pow, ^);
@@ -267,17 +328,17 @@ fn concat<'source>(
         }
         // Concatenation for lists
         (JmlValue::List(JmlList(lhs)), JmlValue::List(JmlList(rhs))) => {
-            let mut combined_list = lhs.clone();
-            combined_list.extend(rhs.clone());
-            Ok(JmlValue::List(JmlList(combined_list)))
+            let mut combined_list = Vec::clone(lhs);
+            combined_list.extend(rhs.iter().cloned());
+            Ok(JmlValue::list(combined_list))
         }
         // Concatenation for objects (merge key-value pairs)
         (JmlValue::Object(JmlObject(lhs)), JmlValue::Object(JmlObject(rhs))) => {
-            let mut combined_object = lhs.clone();
-            for (key, value) in rhs {
+            let mut combined_object = IndexMap::clone(lhs);
+            for (key, value) in rhs.iter() {
                 combined_object.insert(key.clone(), value.clone());
             }
-            Ok(JmlValue::Object(JmlObject(combined_object)))
+            Ok(JmlValue::object(combined_object))
         }
         // If types are not compatible for concatenation, return a type error
         _ => Err(TypeErrorKind::InvalidBinaryOperator {
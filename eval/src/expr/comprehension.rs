@@ -0,0 +1,103 @@
+use std::rc::Rc;
+
+use indexmap::IndexMap;
+use parser::ast::{ComprehensionClause, Expression, Key};
+
+use crate::{
+    context::Context,
+    errors::{EvalError, TypeError, TypeErrorKind},
+    jml_type::JmlType,
+    value::JmlValue,
+};
+
+use super::{
+    eval_expr,
+    object_constructor::{eval_condition, eval_key},
+};
+
+pub(crate) fn eval_list_comprehension<'source>(
+    element: Expression<'source>,
+    clauses: Vec<ComprehensionClause<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let mut list = vec![];
+
+    for_each_binding(&clauses, ctx, &mut |scope| {
+        list.push(eval_expr(element.clone(), scope)?);
+        Ok(())
+    })?;
+
+    Ok(JmlValue::list(list))
+}
+
+pub(crate) fn eval_object_comprehension<'source>(
+    key: Key<'source>,
+    value: Expression<'source>,
+    clauses: Vec<ComprehensionClause<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let mut object = IndexMap::new();
+
+    for_each_binding(&clauses, ctx, &mut |scope| {
+        let key = eval_key(key.clone(), scope)?;
+        object.insert(key, eval_expr(value.clone(), scope)?);
+        Ok(())
+    })?;
+
+    Ok(JmlValue::object(object))
+}
+
+/// Calls `emit` once for every combination of the `for` bindings that passes
+/// the `if` clauses. All of them share one scope, whose bindings are replaced
+/// as the loops advance.
+fn for_each_binding<'source>(
+    clauses: &[ComprehensionClause<'source>],
+    ctx: &mut Context<'source>,
+    emit: &mut dyn FnMut(&mut Context<'source>) -> Result<(), EvalError>,
+) -> Result<(), EvalError> {
+    let mut scope = Context::new_with_parent(Rc::new(ctx.clone()));
+    run_clauses(clauses, &mut scope, emit)
+}
+
+fn run_clauses<'source>(
+    clauses: &[ComprehensionClause<'source>],
+    scope: &mut Context<'source>,
+    emit: &mut dyn FnMut(&mut Context<'source>) -> Result<(), EvalError>,
+) -> Result<(), EvalError> {
+    let Some((clause, rest)) = clauses.split_first() else {
+        return emit(scope);
+    };
+
+    match clause {
+        ComprehensionClause::For { binding, iterable } => {
+            let (l, r) = (iterable.l, iterable.r);
+            let items = match eval_expr(iterable.clone(), scope)? {
+                JmlValue::List(items) => items,
+                other => {
+                    let type_error_kind = TypeErrorKind::MismatchedTypes {
+                        expected: vec![JmlType::List],
+                        found: other.type_of(),
+                    };
+
+                    return Err(TypeError {
+                        kind: type_error_kind,
+                        span: (l, r - l).into(),
+                    }
+                    .into());
+                }
+            };
+
+            for item in items.iter() {
+                scope.bind_with_value(binding.node, item.clone());
+                run_clauses(rest, scope, emit)?;
+            }
+            Ok(())
+        }
+        ComprehensionClause::If(condition) => {
+            if eval_condition(condition.clone(), scope)? {
+                run_clauses(rest, scope, emit)?;
+            }
+            Ok(())
+        }
+    }
+}
@@ -5,6 +5,7 @@ use parser::ast::{Expression, Identifier};
 use crate::{
     context::Context,
     errors::{EvalError, TypeError, TypeErrorKind},
+    jml_type::JmlType,
     value::{
         lambda::{self, JmlLambda},
         JmlValue,
@@ -53,18 +54,18 @@ where
                 let result = match body {
                     lambda::LambdaBody::Common(body) => {
                         let mut local_context = Context::new_with_parent(Rc::new(ctx.clone()));
-                        for (param, arg) in params.into_iter().zip(args.into_iter()) {
+                        for (param, arg) in params.into_iter().zip(args) {
                             local_context.bind_with_value(param.to_owned(), eval_expr(arg, ctx)?);
                         }
 
                         eval_expr(body, &mut local_context)?
                     }
-                    lambda::LambdaBody::Native(fun) => {
+                    lambda::LambdaBody::Native { function, .. } => {
                         let mut evaluated_args = vec![];
                         for arg in args.into_iter() {
                             evaluated_args.push(eval_expr(arg, ctx)?);
                         }
-                        fun(span.into(), evaluated_args, ctx)?
+                        function(span.into(), evaluated_args, ctx)?
                     }
                 };
 
@@ -101,18 +102,27 @@ where
                 let result = match body {
                     lambda::LambdaBody::Common(body) => {
                         let mut local_context = Context::new_with_parent(Rc::new(ctx.clone()));
-                        for (param, arg) in params.into_iter().zip(args.into_iter()) {
+                        for (param, arg) in params.into_iter().zip(args) {
                             local_context.bind_with_value(param.to_owned(), arg);
                         }
 
                         eval_expr(body, &mut local_context)?
                     }
-                    lambda::LambdaBody::Native(fun) => fun(span.into(), args, ctx)?,
+                    lambda::LambdaBody::Native { function, .. } => {
+                        function(span.into(), args, ctx)?
+                    }
                 };
 
                 Ok(result)
             }
         }
-        _ => todo!(),
+        other => Err(TypeError {
+            kind: TypeErrorKind::MismatchedTypes {
+                expected: vec![JmlType::Lambda { arity: args.len() }],
+                found: other.type_of(),
+            },
+            span: span.into(),
+        }
+        .into()),
     }
 }
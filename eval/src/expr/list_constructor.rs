@@ -1,6 +1,11 @@
-use parser::ast::Expression;
+use parser::ast::ListElement;
 
-use crate::{context::Context, errors::EvalError, value::JmlValue};
+use crate::{
+    context::Context,
+    errors::{EvalError, TypeError, TypeErrorKind},
+    jml_type::JmlType,
+    value::JmlValue,
+};
 
 use super::eval_expr;
 
@@ -9,13 +14,35 @@ pub(crate) fn eval_list<'source, I>(
     ctx: &mut Context<'source>,
 ) -> Result<JmlValue<'source>, EvalError>
 where
-    I: IntoIterator<Item = Expression<'source>>,
+    I: IntoIterator<Item = ListElement<'source>>,
 {
     let mut list: Vec<JmlValue> = vec![];
 
-    for expr in elems {
-        let val = eval_expr(expr, ctx)?;
-        list.push(val);
+    for elem in elems {
+        match elem {
+            ListElement::Item(expr) => {
+                let val = eval_expr(expr, ctx)?;
+                list.push(val);
+            }
+            ListElement::Spread(expr) => {
+                let (l, r) = (expr.l, expr.r);
+                match eval_expr(expr, ctx)? {
+                    JmlValue::List(elements) => list.extend(elements.iter().cloned()),
+                    other => {
+                        let type_error_kind = TypeErrorKind::MismatchedTypes {
+                            expected: vec![JmlType::List],
+                            found: other.type_of(),
+                        };
+
+                        return Err(TypeError {
+                            kind: type_error_kind,
+                            span: (l, r - l).into(),
+                        }
+                        .into());
+                    }
+                }
+            }
+        }
     }
 
     Ok(JmlValue::list(list))
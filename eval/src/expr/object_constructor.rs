@@ -1,5 +1,5 @@
 use indexmap::IndexMap;
-use parser::ast::{Expression, Key};
+use parser::ast::{Expression, Key, ObjectMember};
 
 use crate::{
     context::Context,
@@ -11,38 +11,45 @@ use crate::{
 use super::eval_expr;
 
 pub(crate) fn eval_object<'source>(
-    data: Vec<(Key<'source>, Expression<'source>)>,
+    members: Vec<ObjectMember<'source>>,
     ctx: &mut Context<'source>,
 ) -> Result<JmlValue<'source>, EvalError> {
     let mut result_map: IndexMap<String, JmlValue<'source>> = IndexMap::new();
 
-    for (k, expr) in data {
-        let evaluated_value = eval_expr(expr, ctx)?;
-        match k {
-            Key::Ident(key) => {
-                result_map.insert(key.node.to_string(), evaluated_value);
-            }
-            Key::Expression(expr) => {
-                let key = eval_expr(expr.clone(), ctx)?;
-                match key {
-                    JmlValue::String(JmlString(str)) => {
-                        result_map.insert(str, evaluated_value);
-                    }
-                    JmlValue::Int(JmlInt(i)) => {
-                        result_map.insert(i.to_string(), evaluated_value);
+    for member in members {
+        match member {
+            ObjectMember::Entry {
+                key,
+                value,
+                condition,
+            } => {
+                if let Some(condition) = condition {
+                    if !eval_condition(condition, ctx)? {
+                        continue;
                     }
-                    JmlValue::Float(JmlFloat(f)) => {
-                        result_map.insert(f.to_string(), evaluated_value);
+                }
+
+                let key = eval_key(key, ctx)?;
+                let evaluated_value = eval_expr(value, ctx)?;
+                result_map.insert(key, evaluated_value);
+            }
+            ObjectMember::Spread(expr) => {
+                let (l, r) = (expr.l, expr.r);
+                match eval_expr(expr, ctx)? {
+                    JmlValue::Object(object) => {
+                        for (key, value) in object.iter() {
+                            result_map.insert(key.clone(), value.clone());
+                        }
                     }
-                    _ => {
+                    other => {
                         let type_error_kind = TypeErrorKind::MismatchedTypes {
-                            expected: vec![JmlType::String],
-                            found: key.type_of(),
+                            expected: vec![JmlType::Object],
+                            found: other.type_of(),
                         };
 
                         return Err(TypeError {
                             kind: type_error_kind,
-                            span: (expr.l, expr.r - expr.l).into(),
+                            span: (l, r - l).into(),
                         }
                         .into());
                     }
@@ -53,3 +60,59 @@ pub(crate) fn eval_object<'source>(
 
     Ok(JmlValue::Object(result_map.into()))
 }
+
+pub(crate) fn eval_key<'source>(
+    key: Key<'source>,
+    ctx: &mut Context<'source>,
+) -> Result<String, EvalError> {
+    match key {
+        Key::Ident(key) => Ok(key.node.to_string()),
+        Key::Expression(expr) => {
+            let (l, r) = (expr.l, expr.r);
+            let key = eval_expr(expr, ctx)?;
+            key_from_value(key).map_err(|kind| {
+                TypeError {
+                    kind,
+                    span: (l, r - l).into(),
+                }
+                .into()
+            })
+        }
+    }
+}
+
+/// The object key a computed value stands for. Numbers are written out, as
+/// JSON keys are always strings.
+pub(crate) fn key_from_value(value: JmlValue) -> Result<String, TypeErrorKind> {
+    match value {
+        JmlValue::String(JmlString(str)) => Ok(str),
+        JmlValue::Int(JmlInt(i)) => Ok(i.to_string()),
+        JmlValue::Float(JmlFloat(f)) => Ok(f.to_string()),
+        _ => Err(TypeErrorKind::MismatchedTypes {
+            expected: vec![JmlType::String],
+            found: value.type_of(),
+        }),
+    }
+}
+
+/// Evaluates the `if` of a conditional entry or comprehension, which has to
+/// be a bool.
+pub(crate) fn eval_condition<'source>(
+    condition: Expression<'source>,
+    ctx: &mut Context<'source>,
+) -> Result<bool, EvalError> {
+    let (l, r) = (condition.l, condition.r);
+    let cond = eval_expr(condition, ctx)?;
+    if !cond.is_bool() {
+        let type_error = TypeError {
+            span: (l, r - l).into(),
+            kind: TypeErrorKind::MismatchedTypes {
+                expected: vec![JmlType::Bool],
+                found: cond.type_of(),
+            },
+        };
+        Err(type_error)?
+    }
+
+    Ok(cond.is_truthy())
+}
@@ -3,7 +3,7 @@ use parser::ast::{Expression, UnaryOp};
 
 use crate::{
     context::Context,
-    errors::{EvalError, TypeError, TypeErrorKind},
+    errors::{EvalError, RuntimeError, RuntimeErrorKind, TypeError, TypeErrorKind},
     value::JmlValue,
 };
 
@@ -22,7 +22,13 @@ where
     match op {
         UnaryOp::Minus => match rhs {
             JmlValue::Float(f) => Ok(f.negative().into()),
-            JmlValue::Int(i) => Ok(i.negative().into()),
+            JmlValue::Int(i) => match i.checked_negative() {
+                Some(negated) => Ok(negated.into()),
+                None => Err(RuntimeError {
+                    span: span.into(),
+                    kind: RuntimeErrorKind::Overflow,
+                })?,
+            },
             _ => {
                 let type_erro_kind = TypeErrorKind::InvalidUnaryOperator {
                     operator: "-".to_owned(),
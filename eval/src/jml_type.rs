@@ -18,10 +18,7 @@ impl JmlType {
     }
 
     pub fn is_ord(self) -> bool {
-        !matches!(
-            self,
-            JmlType::Lambda { .. } | JmlType::Object | JmlType::List
-        )
+        !matches!(self, JmlType::Lambda { .. })
     }
 
     pub fn is_number(self) -> bool {
@@ -31,6 +28,20 @@ impl JmlType {
     pub fn is_bool(self) -> bool {
         matches!(self, JmlType::Bool)
     }
+
+    /// The name `type_of` returns, which leaves out a lambda's arity.
+    pub fn name(self) -> &'static str {
+        match self {
+            JmlType::Null => "Null",
+            JmlType::String => "String",
+            JmlType::Bool => "Bool",
+            JmlType::Int => "Int",
+            JmlType::Float => "Float",
+            JmlType::List => "List",
+            JmlType::Object => "Object",
+            JmlType::Lambda { .. } => "Lambda",
+        }
+    }
 }
 
 impl fmt::Display for JmlType {
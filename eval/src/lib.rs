@@ -1,6 +1,6 @@
 use context::Context;
 use expr::eval_expr;
-use parser::ast::Jml;
+use parser::ast::{Expression, Jml, Statement};
 use stdlib::define_std_lib;
 use stmt::eval_stmt;
 use value::JmlValue;
@@ -9,18 +9,17 @@ pub mod context;
 pub mod errors;
 pub mod expr;
 pub mod jml_type;
+pub mod logging;
 pub mod stdlib;
 pub mod stmt;
+mod suggest;
 pub mod value;
 
 pub fn eval_with_ctx<'source>(
     jml: Jml<'source>,
     ctx: &mut Context<'source>,
 ) -> miette::Result<JmlValue<'source>> {
-    define_std_lib(ctx);
-    for stmt in jml.header.into_iter() {
-        eval_stmt(stmt, ctx)?;
-    }
+    eval_header_with_ctx(jml.header, ctx)?;
     eval_expr(jml.body, ctx).map_err(|e| e.into())
 }
 
@@ -30,6 +29,7 @@ pub fn eval_with_source<'source>(
 ) -> miette::Result<JmlValue<'source>> {
     let mut ctx = context::Context::new();
     define_std_lib(&mut ctx);
+    ctx.set_source(source);
     eval_with_ctx(jml, &mut ctx).map_err(|e| e.with_source_code(source))
 }
 
@@ -39,5 +39,29 @@ pub fn eval_with_ctx_source<'source>(
     ctx: &mut Context<'source>,
 ) -> miette::Result<JmlValue<'source>> {
     define_std_lib(ctx);
+    ctx.set_source(source);
     eval_with_ctx(jml, ctx).map_err(|e| e.with_source_code(source))
 }
+
+/// Binds the standard library and every header statement into `ctx`, so the
+/// body can later be evaluated against it any number of times.
+pub fn eval_header_with_ctx<'source>(
+    header: Vec<Statement<'source>>,
+    ctx: &mut Context<'source>,
+) -> miette::Result<()> {
+    define_std_lib(ctx);
+    for stmt in header.into_iter() {
+        eval_stmt(stmt, ctx)?;
+    }
+    Ok(())
+}
+
+/// Evaluates a single expression against an already prepared context.
+pub fn eval_expr_with_ctx_source<'source>(
+    expression: Expression<'source>,
+    source: &'static str,
+    ctx: &mut Context<'source>,
+) -> miette::Result<JmlValue<'source>> {
+    ctx.set_source(source);
+    eval_expr(expression, ctx).map_err(|e| miette::Report::from(e).with_source_code(source))
+}
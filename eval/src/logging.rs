@@ -0,0 +1,165 @@
+//! Log records from the `log`, `debug` and `trace` natives and the sinks they
+//! go to. Unless the host sets its own [`LogSink`] with
+//! [`Context::set_log_sink`](crate::context::Context::set_log_sink), records
+//! are emitted as `tracing` events with the `jml::script` target.
+
+use std::{fmt, rc::Rc};
+
+use crate::value::JmlValue;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A 1-based line and column in the script source.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug)]
+pub struct LogRecord<'a, 'source> {
+    pub level: LogLevel,
+    pub message: &'a str,
+    /// The value the native was given, which it also returns.
+    pub value: &'a JmlValue<'source>,
+    pub span: miette::SourceSpan,
+    /// Known once the host has named the script with
+    /// [`Context::set_file_name`](crate::context::Context::set_file_name).
+    pub file: Option<&'a str>,
+    /// Known when the script was evaluated together with its source.
+    pub position: Option<Position>,
+}
+
+/// Receives every record a script logs. Closures taking a [`LogRecord`] are
+/// sinks too.
+pub trait LogSink {
+    fn log(&self, record: &LogRecord);
+}
+
+impl<F> LogSink for F
+where
+    F: Fn(&LogRecord),
+{
+    fn log(&self, record: &LogRecord) {
+        self(record)
+    }
+}
+
+/// Emits records as `tracing` events, with the value as JSON in the `value`
+/// field.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TracingSink;
+
+impl LogSink for TracingSink {
+    fn log(&self, record: &LogRecord) {
+        let value =
+            serde_json::to_string(record.value).unwrap_or_else(|_| record.value.to_string());
+        let file = record.file.unwrap_or("<script>");
+        let (line, column) = record
+            .position
+            .map_or((0, 0), |position| (position.line, position.column));
+
+        macro_rules! emit {
+            ($level:expr) => {
+                tracing::event!(
+                    target: "jml::script",
+                    $level,
+                    file,
+                    line,
+                    column,
+                    value = %value,
+                    "{}",
+                    record.message
+                )
+            };
+        }
+
+        match record.level {
+            LogLevel::Trace => emit!(tracing::Level::TRACE),
+            LogLevel::Debug => emit!(tracing::Level::DEBUG),
+            LogLevel::Info => emit!(tracing::Level::INFO),
+        }
+    }
+}
+
+/// Where the records of a context go, and what is known about the script
+/// they come from. Scopes created from a context share its logger.
+#[derive(Clone)]
+pub(crate) struct Logger<'source> {
+    pub(crate) sink: Rc<dyn LogSink>,
+    pub(crate) file: Option<Rc<str>>,
+    /// The texts spans point into, each with the offset it starts at.
+    pub(crate) sources: Rc<Vec<(usize, &'source str)>>,
+}
+
+impl Default for Logger<'_> {
+    fn default() -> Self {
+        Logger {
+            sink: Rc::new(TracingSink),
+            file: None,
+            sources: Rc::default(),
+        }
+    }
+}
+
+impl fmt::Debug for Logger<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Logger")
+            .field("file", &self.file)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Logger<'_> {
+    pub(crate) fn log(
+        &self,
+        level: LogLevel,
+        message: &str,
+        value: &JmlValue,
+        span: miette::SourceSpan,
+    ) {
+        self.sink.log(&LogRecord {
+            level,
+            message,
+            value,
+            span,
+            file: self.file.as_deref(),
+            position: self.position(span.offset()),
+        });
+    }
+}
+
+impl Logger<'_> {
+    /// The position in the source the offset falls in, unless that source is
+    /// unknown or the offset isn't on one of its characters.
+    fn position(&self, offset: usize) -> Option<Position> {
+        let (start, source) = self
+            .sources
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= offset)?;
+        let before = source.get(..offset - start)?;
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+
+        Some(Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        })
+    }
+}
@@ -0,0 +1,212 @@
+//! Encodings and content hashes of strings. Strings are encoded and hashed as
+//! their UTF-8 bytes, and decoding has to produce valid UTF-8 again.
+
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use md5::Md5;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    context::Context,
+    errors::{EvalError, RuntimeError, RuntimeErrorKind},
+    value::JmlValue,
+};
+
+use super::expect_string;
+
+/// Standard base64 that writes padding but doesn't require it when decoding.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Everything but the characters RFC 3986 leaves unreserved.
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+pub fn base64_encode<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let text = expect_string(span, &args[0])?;
+    Ok(JmlValue::string(BASE64.encode(text)))
+}
+
+/// Decodes base64 of UTF-8 text. JML strings can't hold arbitrary bytes, so
+/// base64 of binary data, like an image, is an error rather than a value.
+pub fn base64_decode<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let text = expect_string(span, &args[0])?;
+    let bytes = BASE64
+        .decode(text)
+        .map_err(|error| invalid(span, "base64", error.to_string()))?;
+
+    utf8(span, "base64", bytes)
+}
+
+/// Percent-encodes everything but letters, digits and `-_.~`, so the result
+/// is safe in any part of a URL, including query string keys and values.
+pub fn url_encode<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let text = expect_string(span, &args[0])?;
+    Ok(JmlValue::string(
+        utf8_percent_encode(text, URL_COMPONENT).to_string(),
+    ))
+}
+
+/// Decodes `%XX` escapes. A `+` stays a `+` rather than becoming a space.
+pub fn url_decode<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let text = expect_string(span, &args[0])?;
+
+    let bytes = text.as_bytes();
+    if let Some(position) = (0..bytes.len()).find(|&i| {
+        bytes[i] == b'%'
+            && !bytes
+                .get(i + 1..i + 3)
+                .is_some_and(|escape| escape.iter().all(u8::is_ascii_hexdigit))
+    }) {
+        return Err(invalid(
+            span,
+            "URL encoding",
+            format!(
+                "'%' at offset {} isn't followed by two hex digits",
+                position
+            ),
+        ));
+    }
+
+    utf8(span, "URL encoding", percent_decode_str(text).collect())
+}
+
+/// Lowercase hex of the string's bytes.
+pub fn hex_encode<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let text = expect_string(span, &args[0])?;
+    Ok(JmlValue::string(hex::encode(text)))
+}
+
+/// Accepts upper- and lowercase hex digits. Like `base64_decode`, the bytes
+/// have to be UTF-8 text.
+pub fn hex_decode<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let text = expect_string(span, &args[0])?;
+    let bytes = hex::decode(text).map_err(|error| invalid(span, "hex", error.to_string()))?;
+
+    utf8(span, "hex", bytes)
+}
+
+pub fn sha256<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    digest::<Sha256>(span, &args[0])
+}
+
+pub fn sha1<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    digest::<Sha1>(span, &args[0])
+}
+
+/// Only for interoperating with legacy systems, such as ETags. MD5 isn't
+/// collision resistant.
+pub fn md5<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    digest::<Md5>(span, &args[0])
+}
+
+/// The name-based UUID of `name` within `namespace`, which is either a UUID
+/// or one of the RFC 9562 namespaces `"dns"`, `"url"`, `"oid"` and `"x500"`.
+pub fn uuid_v5<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let namespace = expect_string(span, &args[0])?;
+    let name = expect_string(span, &args[1])?;
+
+    let namespace = match namespace {
+        "dns" => Uuid::NAMESPACE_DNS,
+        "url" => Uuid::NAMESPACE_URL,
+        "oid" => Uuid::NAMESPACE_OID,
+        "x500" => Uuid::NAMESPACE_X500,
+        other => Uuid::parse_str(other).map_err(|error| -> EvalError {
+            RuntimeError {
+                kind: RuntimeErrorKind::InvalidArgument {
+                    name: "namespace".to_owned(),
+                    reason: format!(
+                        "expected a UUID or one of \"dns\", \"url\", \"oid\" or \"x500\": {}",
+                        error
+                    ),
+                },
+                span,
+            }
+            .into()
+        })?,
+    };
+
+    Ok(JmlValue::string(
+        Uuid::new_v5(&namespace, name.as_bytes()).to_string(),
+    ))
+}
+
+fn digest<'source, D: Digest>(
+    span: miette::SourceSpan,
+    value: &JmlValue,
+) -> Result<JmlValue<'source>, EvalError> {
+    let text = expect_string(span, value)?;
+    Ok(JmlValue::string(hex::encode(D::digest(text))))
+}
+
+fn utf8<'source>(
+    span: miette::SourceSpan,
+    encoding: &str,
+    bytes: Vec<u8>,
+) -> Result<JmlValue<'source>, EvalError> {
+    String::from_utf8(bytes)
+        .map(JmlValue::string)
+        .map_err(|error| invalid(span, encoding, format!("decoded bytes {}", error)))
+}
+
+fn invalid(span: miette::SourceSpan, encoding: &str, reason: String) -> EvalError {
+    RuntimeError {
+        kind: RuntimeErrorKind::InvalidEncoding {
+            encoding: encoding.to_owned(),
+            reason,
+        },
+        span,
+    }
+    .into()
+}
@@ -0,0 +1,97 @@
+//! Raising and recovering from errors in scripts: `error` and `assert` fail
+//! with the script's own message at the call, and `try` turns a failure back
+//! into a value.
+
+use indexmap::indexmap;
+use miette::Diagnostic;
+
+use crate::{
+    context::Context,
+    errors::{EvalError, RuntimeError, RuntimeErrorKind},
+    expr::lambda::eval_lambda_application_with_evaluated_args,
+    jml_type::JmlType,
+    value::JmlValue,
+};
+
+use super::{expect_string, mismatch};
+
+pub fn error<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let message = expect_string(span, &args[0])?;
+
+    Err(RuntimeError {
+        kind: RuntimeErrorKind::GenericError {
+            message: message.to_owned(),
+        },
+        span,
+    }
+    .into())
+}
+
+/// Fails with `message` unless `condition` is true, and is `null` otherwise,
+/// so that `assert(condition, message) ?? value` checks before yielding
+/// `value`.
+pub fn assert<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let message = expect_string(span, &args[1])?;
+
+    match &args[0] {
+        JmlValue::Bool(condition) if condition.0 => Ok(JmlValue::null()),
+        JmlValue::Bool(_) => Err(RuntimeError {
+            kind: RuntimeErrorKind::AssertionFailed {
+                message: message.to_owned(),
+            },
+            span,
+        }
+        .into()),
+        other => Err(mismatch(span, vec![JmlType::Bool], other)),
+    }
+}
+
+/// Calls the lambda `body` without arguments and returns its result. If it
+/// fails, returns `fallback`, or, when `fallback` is a lambda, calls it with
+/// a `{ message, code }` object describing the error.
+pub fn try_<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let [body, fallback] = &args[..] else {
+        unreachable!("try is defined with two parameters")
+    };
+
+    if !matches!(body, JmlValue::Lambda(_)) {
+        return Err(mismatch(span, vec![JmlType::Lambda { arity: 0 }], body));
+    }
+
+    match eval_lambda_application_with_evaluated_args(span, body.clone(), vec![], ctx) {
+        Ok(value) => Ok(value),
+        Err(error) => match fallback {
+            JmlValue::Lambda(_) => eval_lambda_application_with_evaluated_args(
+                span,
+                fallback.clone(),
+                vec![describe(&error)],
+                ctx,
+            ),
+            value => Ok(value.clone()),
+        },
+    }
+}
+
+fn describe<'source>(error: &EvalError) -> JmlValue<'source> {
+    let (message, code) = match error {
+        EvalError::TypeError(error) => (error.kind.to_string(), error.kind.code()),
+        EvalError::RuntimeError(error) => (error.kind.to_string(), error.kind.code()),
+    };
+
+    JmlValue::object(indexmap! {
+        "message".to_owned() => JmlValue::string(message),
+        "code".to_owned() => code.map_or(JmlValue::null(), |code| JmlValue::string(code.to_string())),
+    })
+}
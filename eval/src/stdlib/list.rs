@@ -1,35 +1,25 @@
+use std::{cmp::Ordering, collections::HashSet};
+
 use crate::{
     context::Context,
-    errors::{EvalError, TypeError, TypeErrorKind},
-    expr::lambda::eval_lambda_application_with_evaluated_args,
+    errors::{EvalError, RuntimeError, RuntimeErrorKind, TypeError, TypeErrorKind},
+    expr::{binary_op::compare, lambda::eval_lambda_application_with_evaluated_args},
     jml_type::JmlType,
-    value::{bool::JmlBool, list::JmlList, JmlValue},
+    value::{bool::JmlBool, JmlValue},
 };
 
+use super::{expect_int, expect_list, mismatch};
+
 pub fn map<'source>(
     span: miette::SourceSpan,
     args: Vec<JmlValue<'source>>,
     ctx: &mut Context<'source>,
 ) -> Result<JmlValue<'source>, EvalError> {
-    let list = match &args[0] {
-        JmlValue::List(JmlList(l)) => l,
-        _ => {
-            let type_error_kind = TypeErrorKind::MismatchedTypes {
-                expected: vec![JmlType::List],
-                found: args[0].type_of(),
-            };
-
-            return Err(TypeError {
-                kind: type_error_kind,
-                span,
-            }
-            .into());
-        }
-    };
+    let list = expect_list(span, &args[0])?;
 
     let mut mapped_list: Vec<JmlValue> = vec![];
 
-    for elem in list {
+    for elem in list.iter() {
         mapped_list.push(eval_lambda_application_with_evaluated_args(
             span,
             args[1].clone(),
@@ -46,25 +36,11 @@ pub fn filter<'source>(
     args: Vec<JmlValue<'source>>,
     ctx: &mut Context<'source>,
 ) -> Result<JmlValue<'source>, EvalError> {
-    let list = match &args[0] {
-        JmlValue::List(JmlList(l)) => l,
-        _ => {
-            let type_error_kind = TypeErrorKind::MismatchedTypes {
-                expected: vec![JmlType::List],
-                found: args[0].type_of(),
-            };
-
-            return Err(TypeError {
-                kind: type_error_kind,
-                span,
-            }
-            .into());
-        }
-    };
+    let list = expect_list(span, &args[0])?;
 
     let mut filtered_list: Vec<JmlValue> = vec![];
 
-    for elem in list {
+    for elem in list.iter() {
         let result = eval_lambda_application_with_evaluated_args(
             span,
             args[1].clone(),
@@ -85,25 +61,11 @@ pub fn reduce<'source>(
     args: Vec<JmlValue<'source>>,
     ctx: &mut Context<'source>,
 ) -> Result<JmlValue<'source>, EvalError> {
-    let list = match &args[0] {
-        JmlValue::List(JmlList(l)) => l,
-        _ => {
-            let type_error_kind = TypeErrorKind::MismatchedTypes {
-                expected: vec![JmlType::List],
-                found: args[0].type_of(),
-            };
-
-            return Err(TypeError {
-                kind: type_error_kind,
-                span,
-            }
-            .into());
-        }
-    };
+    let list = expect_list(span, &args[0])?;
 
     let mut accumulator = args[1].clone();
 
-    for elem in list {
+    for elem in list.iter() {
         accumulator = eval_lambda_application_with_evaluated_args(
             span,
             args[2].clone(),
@@ -114,3 +76,496 @@ pub fn reduce<'source>(
 
     Ok(accumulator)
 }
+
+/// Number of elements of a list, characters of a string or entries of an object.
+pub fn len<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let length = match &args[0] {
+        JmlValue::List(list) => list.len(),
+        JmlValue::String(string) => string.0.chars().count(),
+        JmlValue::Object(object) => object.len(),
+        other => {
+            return Err(mismatch(
+                span,
+                vec![JmlType::List, JmlType::String, JmlType::Object],
+                other,
+            ))
+        }
+    };
+
+    Ok(JmlValue::int(length as i64))
+}
+
+/// Sorts in ascending order by the total order of values. The sort is stable.
+pub fn sort<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let mut elements = expect_list(span, &args[0])?.0.to_vec();
+
+    expect_ord(span, elements.iter())?;
+    elements.sort();
+
+    Ok(JmlValue::list(elements))
+}
+
+/// Sorts by the key the lambda returns for each element, which is computed
+/// once per element. Elements with equal keys keep their order.
+pub fn sort_by<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    let mut keyed = vec![];
+    for elem in list.iter() {
+        let key = apply(span, &args[1], elem.clone(), ctx)?;
+        keyed.push((key, elem.clone()));
+    }
+
+    expect_ord(span, keyed.iter().map(|(key, _)| key))?;
+    keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(JmlValue::list(
+        keyed.into_iter().map(|(_, elem)| elem).collect::<Vec<_>>(),
+    ))
+}
+
+pub fn reverse<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    Ok(JmlValue::list(
+        list.iter().rev().cloned().collect::<Vec<_>>(),
+    ))
+}
+
+/// Drops every element equal to an earlier one.
+pub fn unique<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    let mut seen = HashSet::new();
+    let unique: Vec<JmlValue> = list
+        .iter()
+        .filter(|elem| seen.insert(*elem))
+        .cloned()
+        .collect();
+
+    Ok(JmlValue::list(unique))
+}
+
+/// Splices the elements of nested lists into the list, one level deep.
+pub fn flatten<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    let mut flattened = vec![];
+    for elem in list.iter() {
+        match elem {
+            JmlValue::List(inner) => flattened.extend(inner.iter().cloned()),
+            other => flattened.push(other.clone()),
+        }
+    }
+
+    Ok(JmlValue::list(flattened))
+}
+
+/// Maps every element to a list and concatenates the results.
+pub fn flat_map<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    let mut flattened = vec![];
+    for elem in list.iter() {
+        let mapped = apply(span, &args[1], elem.clone(), ctx)?;
+        flattened.extend(expect_list(span, &mapped)?.iter().cloned());
+    }
+
+    Ok(JmlValue::list(flattened))
+}
+
+/// Pairs up the elements of two lists, stopping at the end of the shorter one.
+pub fn zip<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let left = expect_list(span, &args[0])?;
+    let right = expect_list(span, &args[1])?;
+
+    Ok(JmlValue::list(
+        left.iter()
+            .zip(right.iter())
+            .map(|(l, r)| JmlValue::list(vec![l.clone(), r.clone()]))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Pairs every element with its index, as `[index, element]`.
+pub fn enumerate<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    Ok(JmlValue::list(
+        list.iter()
+            .enumerate()
+            .map(|(i, elem)| JmlValue::list(vec![JmlValue::int(i as i64), elem.clone()]))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// The first `count` elements, or all of them when there are fewer.
+pub fn take<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+    let count = expect_count(span, "count", &args[1])?;
+
+    Ok(JmlValue::list(
+        list.iter().take(count).cloned().collect::<Vec<_>>(),
+    ))
+}
+
+/// Every element after the first `count`.
+pub fn drop<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+    let count = expect_count(span, "count", &args[1])?;
+
+    Ok(JmlValue::list(
+        list.iter().skip(count).cloned().collect::<Vec<_>>(),
+    ))
+}
+
+/// The first element the lambda returns `true` for, or `null`.
+pub fn find<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    for elem in list.iter() {
+        if test(span, &args[1], elem.clone(), ctx)? {
+            return Ok(elem.clone());
+        }
+    }
+
+    Ok(JmlValue::null())
+}
+
+pub fn any<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    for elem in list.iter() {
+        if test(span, &args[1], elem.clone(), ctx)? {
+            return Ok(JmlValue::bool(true));
+        }
+    }
+
+    Ok(JmlValue::bool(false))
+}
+
+pub fn all<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    for elem in list.iter() {
+        if !test(span, &args[1], elem.clone(), ctx)? {
+            return Ok(JmlValue::bool(false));
+        }
+    }
+
+    Ok(JmlValue::bool(true))
+}
+
+pub fn contains<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    Ok(JmlValue::bool(list.0.contains(&args[1])))
+}
+
+/// Index of the first element equal to the value, or `null`.
+pub fn index_of<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    Ok(list
+        .iter()
+        .position(|elem| *elem == args[1])
+        .map_or(JmlValue::null(), |i| JmlValue::int(i as i64)))
+}
+
+/// The most elements `range` builds, so that a typo in a bound fails instead
+/// of exhausting memory.
+const MAX_RANGE_LEN: i64 = 10_000_000;
+
+/// The integers from `start` up to, but not including, `end`.
+pub fn range<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let start = expect_int(span, &args[0])?;
+    let end = expect_int(span, &args[1])?;
+
+    if end.checked_sub(start).is_none_or(|len| len > MAX_RANGE_LEN) {
+        return Err(RuntimeError {
+            kind: RuntimeErrorKind::InvalidArgument {
+                name: "end".to_owned(),
+                reason: format!("a range can have at most {} elements", MAX_RANGE_LEN),
+            },
+            span,
+        }
+        .into());
+    }
+
+    Ok(JmlValue::list(
+        (start..end).map(JmlValue::int).collect::<Vec<_>>(),
+    ))
+}
+
+/// Splits the list into lists of `size` elements, the last of which may be
+/// shorter.
+pub fn chunk<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+    let size = expect_count(span, "size", &args[1])?;
+    if size == 0 {
+        return Err(RuntimeError {
+            kind: RuntimeErrorKind::InvalidArgument {
+                name: "size".to_owned(),
+                reason: "chunks must have at least one element".to_owned(),
+            },
+            span,
+        }
+        .into());
+    }
+
+    Ok(JmlValue::list(
+        list.0
+            .chunks(size)
+            .map(|chunk| JmlValue::list(chunk.to_vec()))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Splits the list into the elements the lambda returns `true` for and the
+/// rest, as `[matching, rest]`.
+pub fn partition<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    let (mut matching, mut rest) = (vec![], vec![]);
+    for elem in list.iter() {
+        if test(span, &args[1], elem.clone(), ctx)? {
+            matching.push(elem.clone());
+        } else {
+            rest.push(elem.clone());
+        }
+    }
+
+    Ok(JmlValue::list(vec![
+        JmlValue::list(matching),
+        JmlValue::list(rest),
+    ]))
+}
+
+/// The smallest element, or `null` for an empty list.
+pub fn min<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    extreme(span, &args[0], Ordering::Less)
+}
+
+/// The largest element, or `null` for an empty list.
+pub fn max<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    extreme(span, &args[0], Ordering::Greater)
+}
+
+/// Sum of a list of numbers, which stays an Int unless a Float is involved.
+pub fn sum<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    let mut total = JmlValue::int(0);
+    for elem in list.iter() {
+        total = match (&total, elem) {
+            (JmlValue::Int(total), JmlValue::Int(value)) => match total.0.checked_add(value.0) {
+                Some(total) => JmlValue::int(total),
+                None => {
+                    return Err(RuntimeError {
+                        kind: RuntimeErrorKind::Overflow,
+                        span,
+                    }
+                    .into())
+                }
+            },
+            (total, value) => JmlValue::float(as_float(span, total)? + as_float(span, value)?),
+        };
+    }
+
+    Ok(total)
+}
+
+/// Mean of a list of numbers as a Float, or `null` for an empty list.
+pub fn avg<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+    if list.is_empty() {
+        return Ok(JmlValue::null());
+    }
+
+    let mut total = 0.0;
+    for elem in list.iter() {
+        total += as_float(span, elem)?;
+    }
+
+    Ok(JmlValue::float(total / list.len() as f64))
+}
+
+fn extreme<'source>(
+    span: miette::SourceSpan,
+    list: &JmlValue<'source>,
+    wanted: Ordering,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, list)?;
+
+    let mut best: Option<&JmlValue> = None;
+    for elem in list.iter() {
+        best = match best {
+            Some(current) => {
+                let ordering = compare(elem, current).map_err(|kind| TypeError { kind, span })?;
+                Some(if ordering == wanted { elem } else { current })
+            }
+            None => Some(elem),
+        };
+    }
+
+    Ok(best.cloned().unwrap_or_default())
+}
+
+/// Checks that every value can be sorted before the sort, which then uses the
+/// total order of values.
+fn expect_ord<'a, 'source: 'a>(
+    span: miette::SourceSpan,
+    mut values: impl Iterator<Item = &'a JmlValue<'source>>,
+) -> Result<(), EvalError> {
+    match values.find(|value| !value.is_ord()) {
+        Some(value) => Err(TypeError {
+            kind: TypeErrorKind::NotOrderedType {
+                found: value.type_of(),
+            },
+            span,
+        }
+        .into()),
+        None => Ok(()),
+    }
+}
+
+fn apply<'source>(
+    span: miette::SourceSpan,
+    lambda: &JmlValue<'source>,
+    elem: JmlValue<'source>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    eval_lambda_application_with_evaluated_args(span, lambda.clone(), vec![elem], ctx)
+}
+
+/// Applies a predicate, which has to return a bool.
+fn test<'source>(
+    span: miette::SourceSpan,
+    lambda: &JmlValue<'source>,
+    elem: JmlValue<'source>,
+    ctx: &mut Context<'source>,
+) -> Result<bool, EvalError> {
+    match apply(span, lambda, elem, ctx)? {
+        JmlValue::Bool(JmlBool(result)) => Ok(result),
+        other => Err(mismatch(span, vec![JmlType::Bool], &other)),
+    }
+}
+
+/// An Int that counts elements, so it can't be negative.
+fn expect_count(
+    span: miette::SourceSpan,
+    name: &str,
+    value: &JmlValue,
+) -> Result<usize, EvalError> {
+    let count = expect_int(span, value)?;
+    usize::try_from(count).map_err(|_| {
+        RuntimeError {
+            kind: RuntimeErrorKind::InvalidArgument {
+                name: name.to_owned(),
+                reason: format!("expected a count of zero or more, found {}", count),
+            },
+            span,
+        }
+        .into()
+    })
+}
+
+fn as_float(span: miette::SourceSpan, value: &JmlValue) -> Result<f64, EvalError> {
+    match value {
+        JmlValue::Int(int) => Ok(int.0 as f64),
+        JmlValue::Float(float) => Ok(float.0),
+        other => Err(mismatch(span, vec![JmlType::Int, JmlType::Float], other)),
+    }
+}
@@ -0,0 +1,400 @@
+//! Numeric functions and conversions. As with the arithmetic operators, Ints
+//! are promoted to Floats when a result can't be whole: `abs`, `floor`, `ceil`
+//! and `round` keep Ints as Ints, and the other functions return Floats. A NaN
+//! or infinite result is an error rather than a value.
+
+use std::cmp::Ordering;
+
+use crate::{
+    context::Context,
+    errors::{EvalError, RuntimeError, RuntimeErrorKind},
+    jml_type::JmlType,
+    value::{compare_int_float, JmlValue},
+};
+
+use super::{expect_int, expect_string, mismatch};
+
+pub fn abs<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    match &args[0] {
+        JmlValue::Int(int) => int
+            .0
+            .checked_abs()
+            .map(JmlValue::int)
+            .ok_or_else(|| runtime(span, RuntimeErrorKind::Overflow)),
+        JmlValue::Float(float) => Ok(JmlValue::float(float.0.abs())),
+        other => Err(not_a_number(span, other)),
+    }
+}
+
+pub fn floor<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    whole(span, &args[0], f64::floor)
+}
+
+pub fn ceil<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    whole(span, &args[0], f64::ceil)
+}
+
+/// Rounds half away from zero to `digits` decimal places. Negative `digits`
+/// round to tens, hundreds and so on, which also works for Ints.
+pub fn round<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let digits = expect_int(span, &args[1])?;
+
+    match &args[0] {
+        JmlValue::Int(int) if digits >= 0 => Ok(JmlValue::int(int.0)),
+        JmlValue::Int(int) => {
+            let Some(factor) = u32::try_from(-digits)
+                .ok()
+                .and_then(|exponent| 10i64.checked_pow(exponent))
+            else {
+                return Ok(JmlValue::int(0));
+            };
+
+            let remainder = int.0 % factor;
+            let rounded = if remainder.abs() * 2 >= factor {
+                (int.0 - remainder).checked_add(int.0.signum() * factor)
+            } else {
+                Some(int.0 - remainder)
+            };
+
+            rounded
+                .map(JmlValue::int)
+                .ok_or_else(|| runtime(span, RuntimeErrorKind::Overflow))
+        }
+        JmlValue::Float(float) => {
+            let exponent = digits.clamp(-400, 400) as i32;
+            let factor = 10f64.powi(exponent.abs());
+            let rounded = if exponent >= 0 {
+                let scaled = float.0 * factor;
+                // Past the precision of a float there is nothing left to round.
+                if scaled.is_finite() {
+                    scaled.round() / factor
+                } else {
+                    float.0
+                }
+            } else if factor.is_finite() {
+                (float.0 / factor).round() * factor
+            } else {
+                0.0
+            };
+
+            finite(span, || format!("round({}, {})", float, digits), rounded)
+        }
+        other => Err(not_a_number(span, other)),
+    }
+}
+
+pub fn sqrt<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "sqrt", &args[0], f64::sqrt)
+}
+
+pub fn exp<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "exp", &args[0], f64::exp)
+}
+
+pub fn ln<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "ln", &args[0], f64::ln)
+}
+
+pub fn log2<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "log2", &args[0], f64::log2)
+}
+
+pub fn log10<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "log10", &args[0], f64::log10)
+}
+
+pub fn sin<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "sin", &args[0], f64::sin)
+}
+
+pub fn cos<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "cos", &args[0], f64::cos)
+}
+
+pub fn tan<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "tan", &args[0], f64::tan)
+}
+
+pub fn asin<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "asin", &args[0], f64::asin)
+}
+
+pub fn acos<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "acos", &args[0], f64::acos)
+}
+
+pub fn atan<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    unary(span, "atan", &args[0], f64::atan)
+}
+
+/// The angle of the point `(x, y)`, in radians between -π and π.
+pub fn atan2<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let y = expect_number(span, &args[0])?;
+    let x = expect_number(span, &args[1])?;
+
+    finite(span, || format!("atan2({}, {})", y, x), y.atan2(x))
+}
+
+/// `value` if it lies between `low` and `high`, and the nearest bound
+/// otherwise. The bounds are compared by value, so a value equal to a bound is
+/// returned as it is, whether it is an Int or a Float.
+pub fn clamp<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    for (name, arg) in ["number", "low", "high"].into_iter().zip(&args) {
+        if expect_number(span, arg)?.is_nan() {
+            return Err(runtime(
+                span,
+                RuntimeErrorKind::InvalidArgument {
+                    name: name.to_owned(),
+                    reason: "NaN can't be clamped".to_owned(),
+                },
+            ));
+        }
+    }
+    let [value, low, high] = &args[..] else {
+        unreachable!("clamp is defined with three parameters")
+    };
+
+    if compare_numbers(low, high) == Ordering::Greater {
+        return Err(runtime(
+            span,
+            RuntimeErrorKind::InvalidArgument {
+                name: "low".to_owned(),
+                reason: format!("{} is greater than high {}", low, high),
+            },
+        ));
+    }
+
+    Ok(if compare_numbers(value, low) == Ordering::Less {
+        low.clone()
+    } else if compare_numbers(value, high) == Ordering::Greater {
+        high.clone()
+    } else {
+        value.clone()
+    })
+}
+
+/// Compares two numbers by value, unlike the order of values, which puts an
+/// Int before a Float of the same value. Neither number may be NaN.
+fn compare_numbers(a: &JmlValue, b: &JmlValue) -> Ordering {
+    match (a, b) {
+        (JmlValue::Int(a), JmlValue::Int(b)) => a.0.cmp(&b.0),
+        (JmlValue::Int(a), JmlValue::Float(b)) => compare_int_float(a.0, b.0),
+        (JmlValue::Float(a), JmlValue::Int(b)) => compare_int_float(b.0, a.0).reverse(),
+        (JmlValue::Float(a), JmlValue::Float(b)) => {
+            a.0.partial_cmp(&b.0).expect("NaN was rejected")
+        }
+        _ => unreachable!("only numbers are compared"),
+    }
+}
+
+/// Converts a number or a numeric string to an Int, truncating towards zero.
+pub fn to_int<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let number = match &args[0] {
+        JmlValue::String(text) => number_from_str(span, &text.0)?,
+        JmlValue::Int(_) | JmlValue::Float(_) => args[0].clone(),
+        other => return Err(convertible_mismatch(span, other)),
+    };
+
+    match number {
+        JmlValue::Float(float) => {
+            let truncated = float.0.trunc();
+            if !truncated.is_finite() {
+                return Err(runtime(
+                    span,
+                    RuntimeErrorKind::NotFinite {
+                        operation: format!("to_int({})", float),
+                    },
+                ));
+            }
+
+            // i64::MAX isn't a float, so the upper bound is exclusive.
+            if (i64::MIN as f64..i64::MAX as f64).contains(&truncated) {
+                Ok(JmlValue::int(truncated as i64))
+            } else {
+                Err(runtime(span, RuntimeErrorKind::Overflow))
+            }
+        }
+        int => Ok(int),
+    }
+}
+
+/// Converts a number or a numeric string to a Float.
+pub fn to_float<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let number = match &args[0] {
+        JmlValue::String(text) => number_from_str(span, &text.0)?,
+        JmlValue::Int(_) | JmlValue::Float(_) => args[0].clone(),
+        other => return Err(convertible_mismatch(span, other)),
+    };
+
+    Ok(JmlValue::float(expect_number(span, &number)?))
+}
+
+/// Parses a JSON number, as an Int when it has neither a fraction nor an
+/// exponent and fits, and as a Float otherwise.
+pub fn parse_number<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    number_from_str(span, expect_string(span, &args[0])?)
+}
+
+pub(crate) fn number_from_str<'source>(
+    span: miette::SourceSpan,
+    text: &str,
+) -> Result<JmlValue<'source>, EvalError> {
+    let number: serde_json::Number = serde_json::from_str(text).map_err(|_| {
+        runtime(
+            span,
+            RuntimeErrorKind::InvalidArgument {
+                name: "text".to_owned(),
+                reason: format!("'{}' is not a number", text),
+            },
+        )
+    })?;
+
+    Ok(match number.as_i64() {
+        Some(int) => JmlValue::int(int),
+        None => JmlValue::float(number.as_f64().expect("JSON numbers are finite")),
+    })
+}
+
+/// Rounds a Float to a whole Float with `round`, leaving Ints as they are.
+fn whole<'source>(
+    span: miette::SourceSpan,
+    value: &JmlValue<'source>,
+    round: fn(f64) -> f64,
+) -> Result<JmlValue<'source>, EvalError> {
+    match value {
+        JmlValue::Int(int) => Ok(JmlValue::int(int.0)),
+        JmlValue::Float(float) => Ok(JmlValue::float(round(float.0))),
+        other => Err(not_a_number(span, other)),
+    }
+}
+
+fn unary<'source>(
+    span: miette::SourceSpan,
+    name: &str,
+    value: &JmlValue<'source>,
+    function: fn(f64) -> f64,
+) -> Result<JmlValue<'source>, EvalError> {
+    let number = expect_number(span, value)?;
+    finite(span, || format!("{}({})", name, value), function(number))
+}
+
+fn finite<'source>(
+    span: miette::SourceSpan,
+    operation: impl FnOnce() -> String,
+    result: f64,
+) -> Result<JmlValue<'source>, EvalError> {
+    if result.is_finite() {
+        Ok(JmlValue::float(result))
+    } else {
+        Err(runtime(
+            span,
+            RuntimeErrorKind::NotFinite {
+                operation: operation(),
+            },
+        ))
+    }
+}
+
+fn expect_number(span: miette::SourceSpan, value: &JmlValue) -> Result<f64, EvalError> {
+    match value {
+        JmlValue::Int(int) => Ok(int.0 as f64),
+        JmlValue::Float(float) => Ok(float.0),
+        other => Err(not_a_number(span, other)),
+    }
+}
+
+fn not_a_number(span: miette::SourceSpan, found: &JmlValue) -> EvalError {
+    mismatch(span, vec![JmlType::Int, JmlType::Float], found)
+}
+
+fn convertible_mismatch(span: miette::SourceSpan, found: &JmlValue) -> EvalError {
+    mismatch(
+        span,
+        vec![JmlType::Int, JmlType::Float, JmlType::String],
+        found,
+    )
+}
+
+fn runtime(span: miette::SourceSpan, kind: RuntimeErrorKind) -> EvalError {
+    RuntimeError { kind, span }.into()
+}
@@ -1,68 +1,298 @@
 use std::rc::Rc;
 
-use list::{filter, map, reduce};
-use object::pluck;
+use encoding::{
+    base64_decode, base64_encode, hex_decode, hex_encode, md5, sha1, sha256, url_decode,
+    url_encode, uuid_v5,
+};
+use error::{assert, error, try_};
+use list::{
+    all, any, avg, chunk, contains, drop, enumerate, filter, find, flat_map, flatten, index_of,
+    len, map, max, min, partition, range, reduce, reverse, sort, sort_by, sum, take, unique, zip,
+};
+use math::{
+    abs, acos, asin, atan, atan2, ceil, clamp, cos, exp, floor, ln, log10, log2, parse_number,
+    round, sin, sqrt, tan, to_float, to_int,
+};
+use object::{
+    deep_merge, entries, filter_entries, from_entries, has_key, invert, keys, map_keys, map_values,
+    omit, pick, pluck, rename_keys, values,
+};
+use path::{delete_in, get_in, set_in, update_in};
+use query::query;
+use relational::{count_by, group_by, index_by, join};
+use time::{
+    add_time, compare_time, format_time, from_epoch, from_epoch_millis, parse_time, sub_time,
+    time_diff, time_offset, to_epoch, to_epoch_millis, to_offset, truncate_time,
+};
+use types::{
+    is_bool, is_float, is_int, is_lambda, is_list, is_null, is_number, is_object, is_string,
+    parse_json, to_bool, to_json, to_number, to_string, type_of,
+};
 
 use crate::{
-    context::Context,
-    errors::EvalError,
+    context::{Binding, Context},
+    errors::{EvalError, TypeError, TypeErrorKind},
+    jml_type::JmlType,
+    logging::LogLevel,
     value::{
         lambda::{JmlLambda, LambdaBody},
+        list::JmlList,
+        object::JmlObject,
         JmlValue,
     },
 };
 
+pub mod encoding;
+pub mod error;
 pub mod list;
+pub mod math;
 pub mod object;
+pub mod path;
+pub mod query;
+pub mod relational;
+pub mod time;
+pub mod types;
 
-pub(crate) fn define_std_lib(ctx: &mut Context) {
-    ctx.bind_with_value(
-        "log",
-        JmlLambda {
-            params: vec!["msg", "to_log"],
-            body: LambdaBody::Native(Rc::new(log)),
-        },
+pub fn define_std_lib(ctx: &mut Context) {
+    define(ctx, "log", vec!["message", "value"], |span, args, ctx| {
+        log_at(LogLevel::Info, span, args, ctx)
+    });
+    define(ctx, "debug", vec!["message", "value"], |span, args, ctx| {
+        log_at(LogLevel::Debug, span, args, ctx)
+    });
+    define(ctx, "trace", vec!["message", "value"], |span, args, ctx| {
+        log_at(LogLevel::Trace, span, args, ctx)
+    });
+    define(ctx, "map", vec!["list", "lambda"], map);
+    define(ctx, "filter", vec!["list", "lambda"], filter);
+    define(ctx, "reduce", vec!["list", "acc", "lambda"], reduce);
+    define(ctx, "len", vec!["value"], len);
+    define(ctx, "sort", vec!["list"], sort);
+    define(ctx, "sort_by", vec!["list", "lambda"], sort_by);
+    define(ctx, "reverse", vec!["list"], reverse);
+    define(ctx, "unique", vec!["list"], unique);
+    define(ctx, "flatten", vec!["list"], flatten);
+    define(ctx, "flat_map", vec!["list", "lambda"], flat_map);
+    define(ctx, "zip", vec!["left", "right"], zip);
+    define(ctx, "enumerate", vec!["list"], enumerate);
+    define(ctx, "take", vec!["list", "count"], take);
+    define(ctx, "drop", vec!["list", "count"], drop);
+    define(ctx, "find", vec!["list", "lambda"], find);
+    define(ctx, "any", vec!["list", "lambda"], any);
+    define(ctx, "all", vec!["list", "lambda"], all);
+    define(ctx, "contains", vec!["list", "value"], contains);
+    define(ctx, "index_of", vec!["list", "value"], index_of);
+    define(ctx, "range", vec!["start", "end"], range);
+    define(ctx, "chunk", vec!["list", "size"], chunk);
+    define(ctx, "partition", vec!["list", "lambda"], partition);
+    define(ctx, "min", vec!["list"], min);
+    define(ctx, "max", vec!["list"], max);
+    define(ctx, "sum", vec!["list"], sum);
+    define(ctx, "avg", vec!["list"], avg);
+    define(ctx, "pluck", vec!["object"], pluck);
+    define(ctx, "keys", vec!["object"], keys);
+    define(ctx, "values", vec!["object"], values);
+    define(ctx, "entries", vec!["object"], entries);
+    define(ctx, "from_entries", vec!["entries"], from_entries);
+    define(ctx, "has_key", vec!["object", "key"], has_key);
+    define(ctx, "pick", vec!["object", "keys"], pick);
+    define(ctx, "omit", vec!["object", "keys"], omit);
+    define(ctx, "map_values", vec!["object", "lambda"], map_values);
+    define(ctx, "map_keys", vec!["object", "lambda"], map_keys);
+    define(
+        ctx,
+        "filter_entries",
+        vec!["object", "lambda"],
+        filter_entries,
     );
-
-    ctx.bind_with_value(
-        "map",
-        JmlLambda {
-            params: vec!["list", "lambda"],
-            body: LambdaBody::Native(Rc::new(map)),
-        },
+    define(ctx, "rename_keys", vec!["object", "names"], rename_keys);
+    define(ctx, "deep_merge", vec!["left", "right"], deep_merge);
+    define(ctx, "invert", vec!["object"], invert);
+    define(ctx, "group_by", vec!["list", "lambda"], group_by);
+    define(ctx, "index_by", vec!["list", "lambda"], index_by);
+    define(ctx, "count_by", vec!["list", "lambda"], count_by);
+    define(
+        ctx,
+        "join",
+        vec!["left", "right", "left_key", "right_key", "kind"],
+        join,
     );
+    define(ctx, "query", vec!["value", "path"], query);
+    define(ctx, "get_in", vec!["value", "path", "default"], get_in);
+    define(ctx, "set_in", vec!["value", "path", "new"], set_in);
+    define(ctx, "update_in", vec!["value", "path", "lambda"], update_in);
+    define(ctx, "delete_in", vec!["value", "path"], delete_in);
+    define(ctx, "abs", vec!["number"], abs);
+    define(ctx, "floor", vec!["number"], floor);
+    define(ctx, "ceil", vec!["number"], ceil);
+    define(ctx, "round", vec!["number", "digits"], round);
+    define(ctx, "sqrt", vec!["number"], sqrt);
+    define(ctx, "exp", vec!["number"], exp);
+    define(ctx, "ln", vec!["number"], ln);
+    define(ctx, "log2", vec!["number"], log2);
+    define(ctx, "log10", vec!["number"], log10);
+    define(ctx, "sin", vec!["number"], sin);
+    define(ctx, "cos", vec!["number"], cos);
+    define(ctx, "tan", vec!["number"], tan);
+    define(ctx, "asin", vec!["number"], asin);
+    define(ctx, "acos", vec!["number"], acos);
+    define(ctx, "atan", vec!["number"], atan);
+    define(ctx, "atan2", vec!["y", "x"], atan2);
+    define(ctx, "clamp", vec!["number", "low", "high"], clamp);
+    define(ctx, "to_int", vec!["value"], to_int);
+    define(ctx, "to_float", vec!["value"], to_float);
+    define(ctx, "parse_number", vec!["text"], parse_number);
+    define(ctx, "type_of", vec!["value"], type_of);
+    define(ctx, "is_null", vec!["value"], is_null);
+    define(ctx, "is_bool", vec!["value"], is_bool);
+    define(ctx, "is_int", vec!["value"], is_int);
+    define(ctx, "is_float", vec!["value"], is_float);
+    define(ctx, "is_number", vec!["value"], is_number);
+    define(ctx, "is_string", vec!["value"], is_string);
+    define(ctx, "is_list", vec!["value"], is_list);
+    define(ctx, "is_object", vec!["value"], is_object);
+    define(ctx, "is_lambda", vec!["value"], is_lambda);
+    define(ctx, "to_bool", vec!["value"], to_bool);
+    define(ctx, "to_number", vec!["value"], to_number);
+    define(ctx, "to_string", vec!["value"], to_string);
+    define(ctx, "to_json", vec!["value"], to_json);
+    define(ctx, "parse_json", vec!["text"], parse_json);
+    define(ctx, "base64_encode", vec!["text"], base64_encode);
+    define(ctx, "base64_decode", vec!["text"], base64_decode);
+    define(ctx, "url_encode", vec!["text"], url_encode);
+    define(ctx, "url_decode", vec!["text"], url_decode);
+    define(ctx, "hex_encode", vec!["text"], hex_encode);
+    define(ctx, "hex_decode", vec!["text"], hex_decode);
+    define(ctx, "sha256", vec!["text"], sha256);
+    define(ctx, "sha1", vec!["text"], sha1);
+    define(ctx, "md5", vec!["text"], md5);
+    define(ctx, "uuid_v5", vec!["namespace", "name"], uuid_v5);
+    define(ctx, "error", vec!["message"], error);
+    define(ctx, "assert", vec!["condition", "message"], assert);
+    define(ctx, "try", vec!["body", "fallback"], try_);
+    define(ctx, "parse_time", vec!["text", "format"], parse_time);
+    define(ctx, "format_time", vec!["time", "format"], format_time);
+    define(ctx, "to_epoch", vec!["time"], to_epoch);
+    define(ctx, "to_epoch_millis", vec!["time"], to_epoch_millis);
+    define(ctx, "from_epoch", vec!["seconds"], from_epoch);
+    define(ctx, "from_epoch_millis", vec!["millis"], from_epoch_millis);
+    define(ctx, "add_time", vec!["time", "duration"], add_time);
+    define(ctx, "sub_time", vec!["time", "duration"], sub_time);
+    define(ctx, "truncate_time", vec!["time", "unit"], truncate_time);
+    define(ctx, "time_diff", vec!["start", "end", "unit"], time_diff);
+    define(ctx, "compare_time", vec!["left", "right"], compare_time);
+    define(ctx, "to_offset", vec!["time", "offset"], to_offset);
+    define(ctx, "time_offset", vec!["time"], time_offset);
+}
 
+fn define<'source, F>(ctx: &mut Context<'source>, name: &str, params: Vec<&'source str>, native: F)
+where
+    F: Fn(
+            miette::SourceSpan,
+            Vec<JmlValue<'source>>,
+            &mut Context<'source>,
+        ) -> Result<JmlValue<'source>, EvalError>
+        + 'source,
+{
     ctx.bind_with_value(
-        "filter",
+        name,
         JmlLambda {
-            params: vec!["list", "lambda"],
-            body: LambdaBody::Native(Rc::new(filter)),
+            params,
+            body: LambdaBody::Native {
+                name: name.into(),
+                function: Rc::new(native),
+            },
         },
     );
+}
 
-    ctx.bind_with_value(
-        "reduce",
-        JmlLambda {
-            params: vec!["list", "acc", "lambda"],
-            body: LambdaBody::Native(Rc::new(reduce)),
-        },
-    );
+/// Names and parameters of every native bound by [`define_std_lib`], sorted by name.
+pub fn std_lib_signatures() -> Vec<(String, Vec<String>)> {
+    let mut ctx = Context::new();
+    define_std_lib(&mut ctx);
 
-    ctx.bind_with_value(
-        "pluck",
-        JmlLambda {
-            params: vec!["object"],
-            body: LambdaBody::Native(Rc::new(pluck)),
-        },
-    );
+    let mut signatures: Vec<_> = ctx
+        .names()
+        .into_iter()
+        .filter_map(|name| match ctx.lookup_variable(name) {
+            Ok(Binding::Value(JmlValue::Lambda(lambda))) => Some((
+                name.to_owned(),
+                lambda.params().iter().map(|p| p.to_string()).collect(),
+            )),
+            _ => None,
+        })
+        .collect();
+
+    signatures.sort();
+    signatures
 }
 
-fn log<'source>(
-    _: miette::SourceSpan,
+/// Logs `message` with `value` at `level` and returns `value`, so a call can
+/// wrap any expression.
+fn log_at<'source>(
+    level: LogLevel,
+    span: miette::SourceSpan,
     args: Vec<JmlValue<'source>>,
-    _: &mut Context<'source>,
+    ctx: &mut Context<'source>,
 ) -> Result<JmlValue<'source>, EvalError> {
-    let value = args[1].clone();
-    println!("{} : {}", args[0], args[1]);
+    let [message, value] = <[_; 2]>::try_from(args).expect("loggers take two parameters");
+    let message = match &message {
+        JmlValue::String(string) => string.0.clone(),
+        other => other.to_string(),
+    };
+
+    ctx.logger().log(level, &message, &value, span);
     Ok(value)
 }
+
+pub(crate) fn expect_list<'a, 'source>(
+    span: miette::SourceSpan,
+    value: &'a JmlValue<'source>,
+) -> Result<&'a JmlList<'source>, EvalError> {
+    match value {
+        JmlValue::List(list) => Ok(list),
+        other => Err(mismatch(span, vec![JmlType::List], other)),
+    }
+}
+
+pub(crate) fn expect_object<'a, 'source>(
+    span: miette::SourceSpan,
+    value: &'a JmlValue<'source>,
+) -> Result<&'a JmlObject<'source>, EvalError> {
+    match value {
+        JmlValue::Object(object) => Ok(object),
+        other => Err(mismatch(span, vec![JmlType::Object], other)),
+    }
+}
+
+pub(crate) fn expect_string<'a>(
+    span: miette::SourceSpan,
+    value: &'a JmlValue,
+) -> Result<&'a str, EvalError> {
+    match value {
+        JmlValue::String(string) => Ok(&string.0),
+        other => Err(mismatch(span, vec![JmlType::String], other)),
+    }
+}
+
+pub(crate) fn expect_int(span: miette::SourceSpan, value: &JmlValue) -> Result<i64, EvalError> {
+    match value {
+        JmlValue::Int(int) => Ok(int.0),
+        other => Err(mismatch(span, vec![JmlType::Int], other)),
+    }
+}
+
+pub(crate) fn mismatch(
+    span: miette::SourceSpan,
+    expected: Vec<JmlType>,
+    found: &JmlValue,
+) -> EvalError {
+    TypeError {
+        kind: TypeErrorKind::MismatchedTypes {
+            expected,
+            found: found.type_of(),
+        },
+        span,
+    }
+    .into()
+}
@@ -1,31 +1,22 @@
 use crate::{
     context::Context,
-    errors::{EvalError, TypeError, TypeErrorKind},
+    errors::{EvalError, RuntimeError, RuntimeErrorKind, TypeError},
+    expr::{
+        lambda::eval_lambda_application_with_evaluated_args, object_constructor::key_from_value,
+    },
     jml_type::JmlType,
-    value::{list::JmlList, object::JmlObject, JmlValue},
+    value::{bool::JmlBool, JmlValue},
 };
-use indexmap::indexmap;
+use indexmap::{indexmap, IndexMap};
+
+use super::{expect_list, expect_object, expect_string, mismatch};
 
 pub fn pluck<'source>(
     span: miette::SourceSpan,
     args: Vec<JmlValue<'source>>,
     _: &mut Context<'source>,
 ) -> Result<JmlValue<'source>, EvalError> {
-    let obj = match &args[0] {
-        JmlValue::Object(JmlObject(o)) => o,
-        _ => {
-            let type_error_kind = TypeErrorKind::MismatchedTypes {
-                expected: vec![JmlType::Object],
-                found: args[0].type_of(),
-            };
-
-            return Err(TypeError {
-                kind: type_error_kind,
-                span,
-            }
-            .into());
-        }
-    };
+    let obj = expect_object(span, &args[0])?;
 
     let keys = obj.keys();
 
@@ -33,12 +24,290 @@ pub fn pluck<'source>(
 
     for key in keys {
         let value = obj.get(key).unwrap();
-        let pair = JmlValue::object(JmlObject(indexmap! {
+        let pair = JmlValue::object(indexmap! {
             "key".to_string() => JmlValue::string(key),
             "value".to_string() => value.clone(),
-        }));
+        });
         plucked_values.push(pair);
     }
 
-    Ok(JmlValue::List(JmlList(plucked_values)))
+    Ok(JmlValue::list(plucked_values))
+}
+
+pub fn keys<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+
+    Ok(JmlValue::list(
+        obj.keys().map(JmlValue::string).collect::<Vec<_>>(),
+    ))
+}
+
+pub fn values<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+
+    Ok(JmlValue::list(obj.values().cloned().collect::<Vec<_>>()))
+}
+
+/// The entries as `[key, value]` pairs.
+pub fn entries<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+
+    Ok(JmlValue::list(
+        obj.iter()
+            .map(|(key, value)| JmlValue::list(vec![JmlValue::string(key), value.clone()]))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+/// Builds an object from `[key, value]` pairs or from the `{ key, value }`
+/// objects `pluck` returns. A repeated key keeps its first position and its
+/// last value.
+pub fn from_entries<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    let mut obj = IndexMap::new();
+    for entry in list.iter() {
+        let (key, value) = match entry {
+            JmlValue::List(pair) if pair.len() == 2 => (&pair.0[0], &pair.0[1]),
+            JmlValue::Object(pair) if pair.len() == 2 && pair.contains_key("key") => {
+                match (pair.get("key"), pair.get("value")) {
+                    (Some(key), Some(value)) => (key, value),
+                    _ => return Err(malformed_entry(span, entry)),
+                }
+            }
+            JmlValue::List(_) | JmlValue::Object(_) => return Err(malformed_entry(span, entry)),
+            _ => return Err(mismatch(span, vec![JmlType::List, JmlType::Object], entry)),
+        };
+        obj.insert(to_key(span, key.clone())?, value.clone());
+    }
+
+    Ok(JmlValue::object(obj))
+}
+
+pub fn has_key<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+    let key = expect_string(span, &args[1])?;
+
+    Ok(JmlValue::bool(obj.contains_key(key)))
+}
+
+/// Only the entries with the given keys, in the object's order.
+pub fn pick<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+    let keys = key_list(span, &args[1])?;
+
+    Ok(JmlValue::object(
+        obj.iter()
+            .filter(|(key, _)| keys.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<IndexMap<_, _>>(),
+    ))
+}
+
+/// Every entry except those with the given keys.
+pub fn omit<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+    let keys = key_list(span, &args[1])?;
+
+    Ok(JmlValue::object(
+        obj.iter()
+            .filter(|(key, _)| !keys.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<IndexMap<_, _>>(),
+    ))
+}
+
+pub fn map_values<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+
+    let mut mapped = IndexMap::new();
+    for (key, value) in obj.iter() {
+        let value = eval_lambda_application_with_evaluated_args(
+            span,
+            args[1].clone(),
+            vec![value.clone()],
+            ctx,
+        )?;
+        mapped.insert(key.clone(), value);
+    }
+
+    Ok(JmlValue::object(mapped))
+}
+
+/// Replaces every key with the one the lambda returns for it. When two keys
+/// map to the same one, the later value wins.
+pub fn map_keys<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+
+    let mut mapped = IndexMap::new();
+    for (key, value) in obj.iter() {
+        let key = eval_lambda_application_with_evaluated_args(
+            span,
+            args[1].clone(),
+            vec![JmlValue::string(key)],
+            ctx,
+        )?;
+        mapped.insert(to_key(span, key)?, value.clone());
+    }
+
+    Ok(JmlValue::object(mapped))
+}
+
+/// Keeps the entries for which the lambda, called with the key and the
+/// value, returns `true`.
+pub fn filter_entries<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+
+    let mut filtered = IndexMap::new();
+    for (key, value) in obj.iter() {
+        let keep = eval_lambda_application_with_evaluated_args(
+            span,
+            args[1].clone(),
+            vec![JmlValue::string(key), value.clone()],
+            ctx,
+        )?;
+        match keep {
+            JmlValue::Bool(JmlBool(true)) => {
+                filtered.insert(key.clone(), value.clone());
+            }
+            JmlValue::Bool(JmlBool(false)) => {}
+            other => return Err(mismatch(span, vec![JmlType::Bool], &other)),
+        }
+    }
+
+    Ok(JmlValue::object(filtered))
+}
+
+/// Renames keys according to an object of `old: new` names, keeping every
+/// entry in its position.
+pub fn rename_keys<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+    let names = expect_object(span, &args[1])?;
+
+    let mut renamed = IndexMap::new();
+    for (key, value) in obj.iter() {
+        let key = match names.get(key) {
+            Some(name) => expect_string(span, name)?.to_owned(),
+            None => key.clone(),
+        };
+        renamed.insert(key, value.clone());
+    }
+
+    Ok(JmlValue::object(renamed))
+}
+
+/// Merges the right object into the left one, merging nested objects
+/// instead of replacing them. Any other value on the right wins.
+pub fn deep_merge<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    expect_object(span, &args[0])?;
+    expect_object(span, &args[1])?;
+
+    Ok(merge(&args[0], &args[1]))
+}
+
+/// Swaps keys and values. The values have to be usable as keys, and when
+/// several entries share a value the last key wins.
+pub fn invert<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let obj = expect_object(span, &args[0])?;
+
+    let mut inverted = IndexMap::new();
+    for (key, value) in obj.iter() {
+        inverted.insert(to_key(span, value.clone())?, JmlValue::string(key));
+    }
+
+    Ok(JmlValue::object(inverted))
+}
+
+fn merge<'source>(left: &JmlValue<'source>, right: &JmlValue<'source>) -> JmlValue<'source> {
+    match (left, right) {
+        (JmlValue::Object(left), JmlValue::Object(right)) => {
+            let mut merged = IndexMap::clone(&left.0);
+            for (key, value) in right.iter() {
+                let value = match merged.get(key) {
+                    Some(existing) => merge(existing, value),
+                    None => value.clone(),
+                };
+                merged.insert(key.clone(), value);
+            }
+            JmlValue::object(merged)
+        }
+        (_, right) => right.clone(),
+    }
+}
+
+fn key_list<'a>(span: miette::SourceSpan, value: &'a JmlValue) -> Result<Vec<&'a str>, EvalError> {
+    expect_list(span, value)?
+        .iter()
+        .map(|key| expect_string(span, key))
+        .collect()
+}
+
+fn malformed_entry(span: miette::SourceSpan, entry: &JmlValue) -> EvalError {
+    RuntimeError {
+        kind: RuntimeErrorKind::InvalidArgument {
+            name: "entries".to_owned(),
+            reason: format!(
+                "expected a [key, value] pair or a {{ key, value }} object, found {}",
+                entry
+            ),
+        },
+        span,
+    }
+    .into()
+}
+
+fn to_key(span: miette::SourceSpan, value: JmlValue) -> Result<String, EvalError> {
+    key_from_value(value).map_err(|kind| TypeError { kind, span }.into())
 }
@@ -0,0 +1,189 @@
+//! `get_in`, `set_in`, `update_in` and `delete_in`, which work on the value at
+//! a path of object keys and list indices, such as `["items", 0, "price"]`.
+//!
+//! Objects and lists share their contents between copies, so the updates only
+//! copy the levels along the path and leave the rest of the document shared.
+
+use std::rc::Rc;
+
+use crate::{
+    context::Context,
+    errors::{EvalError, RuntimeError, RuntimeErrorKind, TypeError, TypeErrorKind},
+    expr::lambda::eval_lambda_application_with_evaluated_args,
+    jml_type::JmlType,
+    value::{list::resolve_index, JmlValue},
+};
+
+enum Step<'a> {
+    Key(&'a str),
+    Index(i64),
+}
+
+pub fn get_in<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let steps = steps(span, &args[1])?;
+
+    Ok(get(&args[0], &steps).unwrap_or(&args[2]).clone())
+}
+
+pub fn set_in<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let [mut value, path, new] = <[JmlValue; 3]>::try_from(args).expect("arity is checked");
+    let steps = steps(span, &path)?;
+
+    set(span, &mut value, &steps, new)?;
+    Ok(value)
+}
+
+pub fn update_in<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let [mut value, path, lambda] = <[JmlValue; 3]>::try_from(args).expect("arity is checked");
+    let steps = steps(span, &path)?;
+
+    let current = get(&value, &steps).cloned().unwrap_or_default();
+    let new = eval_lambda_application_with_evaluated_args(span, lambda, vec![current], ctx)?;
+
+    set(span, &mut value, &steps, new)?;
+    Ok(value)
+}
+
+pub fn delete_in<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let [mut value, path] = <[JmlValue; 2]>::try_from(args).expect("arity is checked");
+    let steps = steps(span, &path)?;
+
+    if steps.is_empty() {
+        return Ok(JmlValue::null());
+    }
+    delete(&mut value, &steps);
+    Ok(value)
+}
+
+fn steps<'a>(span: miette::SourceSpan, path: &'a JmlValue) -> Result<Vec<Step<'a>>, EvalError> {
+    let mismatch = |expected, found: &JmlValue| TypeError {
+        kind: TypeErrorKind::MismatchedTypes {
+            expected,
+            found: found.type_of(),
+        },
+        span,
+    };
+
+    let JmlValue::List(path) = path else {
+        return Err(mismatch(vec![JmlType::List], path).into());
+    };
+
+    path.iter()
+        .map(|step| match step {
+            JmlValue::String(key) => Ok(Step::Key(&key.0)),
+            JmlValue::Int(index) => Ok(Step::Index(index.0)),
+            _ => Err(mismatch(vec![JmlType::String, JmlType::Int], step).into()),
+        })
+        .collect()
+}
+
+/// The value at the end of the path, if every step exists.
+fn get<'a, 'source>(value: &'a JmlValue<'source>, steps: &[Step]) -> Option<&'a JmlValue<'source>> {
+    steps
+        .iter()
+        .try_fold(value, |value, step| match (step, value) {
+            (Step::Key(key), JmlValue::Object(object)) => object.get(key),
+            (Step::Index(index), JmlValue::List(list)) => {
+                resolve_index(*index, list.len()).map(|position| &list.0[position])
+            }
+            _ => None,
+        })
+}
+
+/// Missing keys are added, creating objects on the way where the value is
+/// `null` or missing. Indices have to exist.
+fn set<'source>(
+    span: miette::SourceSpan,
+    value: &mut JmlValue<'source>,
+    steps: &[Step],
+    new: JmlValue<'source>,
+) -> Result<(), EvalError> {
+    let Some((step, rest)) = steps.split_first() else {
+        *value = new;
+        return Ok(());
+    };
+
+    if let (Step::Key(_), JmlValue::Null) = (step, &value) {
+        *value = JmlValue::object(indexmap::IndexMap::new());
+    }
+
+    match (step, value) {
+        (Step::Key(key), JmlValue::Object(object)) => {
+            let child = Rc::make_mut(&mut object.0)
+                .entry(key.to_string())
+                .or_default();
+            set(span, child, rest, new)
+        }
+        (Step::Index(index), JmlValue::List(list)) => {
+            let Some(position) = resolve_index(*index, list.len()) else {
+                return Err(RuntimeError {
+                    kind: RuntimeErrorKind::IndexOutOfBounds {
+                        index: *index,
+                        length: list.len(),
+                    },
+                    span,
+                }
+                .into());
+            };
+            set(span, &mut Rc::make_mut(&mut list.0)[position], rest, new)
+        }
+        (step, value) => Err(TypeError {
+            kind: TypeErrorKind::MismatchedTypes {
+                expected: vec![match step {
+                    Step::Key(_) => JmlType::Object,
+                    Step::Index(_) => JmlType::List,
+                }],
+                found: value.type_of(),
+            },
+            span,
+        }
+        .into()),
+    }
+}
+
+/// Removes the value at the end of the path. A path that doesn't exist leaves
+/// the value unchanged.
+fn delete(value: &mut JmlValue, steps: &[Step]) {
+    let Some((step, rest)) = steps.split_first() else {
+        return;
+    };
+
+    match (step, value) {
+        (Step::Key(key), JmlValue::Object(object)) if object.contains_key(key) => {
+            let entries = Rc::make_mut(&mut object.0);
+            match entries.get_mut(*key) {
+                Some(child) if !rest.is_empty() => delete(child, rest),
+                _ => {
+                    entries.shift_remove(*key);
+                }
+            }
+        }
+        (Step::Index(index), JmlValue::List(list)) => {
+            if let Some(position) = resolve_index(*index, list.len()) {
+                let elements = Rc::make_mut(&mut list.0);
+                if rest.is_empty() {
+                    elements.remove(position);
+                } else {
+                    delete(&mut elements[position], rest);
+                }
+            }
+        }
+        _ => {}
+    }
+}
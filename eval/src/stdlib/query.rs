@@ -0,0 +1,598 @@
+//! `query(value, path)`, which selects nodes from a value with a JSONPath
+//! expression such as `$.items[*].children[?(@.active)].id` or `$..id`.
+
+use std::{cmp::Ordering, iter::Peekable, str::CharIndices};
+
+use crate::{
+    context::Context,
+    errors::{EvalError, RuntimeError, RuntimeErrorKind, TypeError, TypeErrorKind},
+    jml_type::JmlType,
+    value::{list::slice_positions, JmlValue},
+};
+
+pub fn query<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let JmlValue::String(path) = &args[1] else {
+        return Err(TypeError {
+            kind: TypeErrorKind::MismatchedTypes {
+                expected: vec![JmlType::String],
+                found: args[1].type_of(),
+            },
+            span,
+        }
+        .into());
+    };
+
+    let segments = Parser::new(&path.0)
+        .parse()
+        .map_err(|kind| RuntimeError { span, kind })?;
+
+    let root = &args[0];
+    let nodes = select(&segments, root, root);
+
+    Ok(JmlValue::list(
+        nodes.into_iter().cloned().collect::<Vec<_>>(),
+    ))
+}
+
+#[derive(Debug)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Filter(Filter),
+}
+
+#[derive(Debug)]
+enum Filter {
+    Or(Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    /// A path on its own tests whether it selects anything.
+    Exists(Path),
+    Compare(Operand, Comparison, Operand),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug)]
+enum Operand {
+    Path(Path),
+    Literal(Literal),
+}
+
+/// A path inside a filter, starting at the current node `@` or at the root `$`.
+#[derive(Debug)]
+struct Path {
+    relative: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug)]
+enum Literal {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+fn select<'a, 'source>(
+    segments: &[Segment],
+    root: &'a JmlValue<'source>,
+    current: &'a JmlValue<'source>,
+) -> Vec<&'a JmlValue<'source>> {
+    let mut nodes = vec![current];
+
+    for segment in segments {
+        nodes = match segment {
+            Segment::Child(selectors) => nodes
+                .into_iter()
+                .flat_map(|node| apply(selectors, root, node))
+                .collect(),
+            Segment::Descendant(selectors) => nodes
+                .into_iter()
+                .flat_map(descendants)
+                .flat_map(|node| apply(selectors, root, node))
+                .collect(),
+        };
+    }
+
+    nodes
+}
+
+/// `node` followed by everything below it, in document order.
+fn descendants<'a, 'source>(node: &'a JmlValue<'source>) -> Vec<&'a JmlValue<'source>> {
+    let mut nodes = vec![node];
+    for child in children(node) {
+        nodes.extend(descendants(child));
+    }
+    nodes
+}
+
+fn children<'a, 'source>(node: &'a JmlValue<'source>) -> Vec<&'a JmlValue<'source>> {
+    match node {
+        JmlValue::List(list) => list.iter().collect(),
+        JmlValue::Object(object) => object.values().collect(),
+        _ => vec![],
+    }
+}
+
+fn apply<'a, 'source>(
+    selectors: &[Selector],
+    root: &'a JmlValue<'source>,
+    node: &'a JmlValue<'source>,
+) -> Vec<&'a JmlValue<'source>> {
+    let mut selected = vec![];
+
+    for selector in selectors {
+        match (selector, node) {
+            (Selector::Name(name), JmlValue::Object(object)) => selected.extend(object.get(name)),
+            (Selector::Wildcard, _) => selected.extend(children(node)),
+            (Selector::Index(index), JmlValue::List(list)) => {
+                let position = if *index < 0 {
+                    index.checked_add(list.len() as i64)
+                } else {
+                    Some(*index)
+                };
+                selected.extend(
+                    position
+                        .and_then(|position| usize::try_from(position).ok())
+                        .and_then(|position| list.0.get(position)),
+                );
+            }
+            (Selector::Slice(start, end, step), JmlValue::List(list)) => {
+                if let Ok(positions) = slice_positions(list.len(), *start, *end, step.unwrap_or(1))
+                {
+                    selected.extend(positions.map(|position| &list.0[position]));
+                }
+            }
+            (Selector::Filter(filter), _) => selected.extend(
+                children(node)
+                    .into_iter()
+                    .filter(|child| matches(filter, root, child)),
+            ),
+            _ => {}
+        }
+    }
+
+    selected
+}
+
+fn matches<'source>(
+    filter: &Filter,
+    root: &JmlValue<'source>,
+    current: &JmlValue<'source>,
+) -> bool {
+    match filter {
+        Filter::Or(lhs, rhs) => matches(lhs, root, current) || matches(rhs, root, current),
+        Filter::And(lhs, rhs) => matches(lhs, root, current) && matches(rhs, root, current),
+        Filter::Not(filter) => !matches(filter, root, current),
+        Filter::Exists(path) => !select_path(path, root, current).is_empty(),
+        Filter::Compare(lhs, comparison, rhs) => {
+            let lhs = operand_value(lhs, root, current);
+            let rhs = operand_value(rhs, root, current);
+            compare(lhs.as_ref(), *comparison, rhs.as_ref())
+        }
+    }
+}
+
+fn select_path<'a, 'source>(
+    path: &Path,
+    root: &'a JmlValue<'source>,
+    current: &'a JmlValue<'source>,
+) -> Vec<&'a JmlValue<'source>> {
+    let start = if path.relative { current } else { root };
+    select(&path.segments, root, start)
+}
+
+/// The value an operand compares as. A path only has one if it selects exactly
+/// one node.
+fn operand_value<'source>(
+    operand: &Operand,
+    root: &JmlValue<'source>,
+    current: &JmlValue<'source>,
+) -> Option<JmlValue<'source>> {
+    match operand {
+        Operand::Path(path) => match select_path(path, root, current).as_slice() {
+            [node] => Some((*node).clone()),
+            _ => None,
+        },
+        Operand::Literal(Literal::Null) => Some(JmlValue::null()),
+        Operand::Literal(Literal::Bool(v)) => Some(JmlValue::bool(*v)),
+        Operand::Literal(Literal::Int(v)) => Some(JmlValue::int(*v)),
+        Operand::Literal(Literal::Float(v)) => Some(JmlValue::float(*v)),
+        Operand::Literal(Literal::String(v)) => Some(JmlValue::string(v.as_str())),
+    }
+}
+
+fn compare<'source>(
+    lhs: Option<&JmlValue<'source>>,
+    comparison: Comparison,
+    rhs: Option<&JmlValue<'source>>,
+) -> bool {
+    let ordering = match (lhs, rhs) {
+        (Some(JmlValue::Int(l)), Some(JmlValue::Int(r))) => Some(l.0.cmp(&r.0)),
+        (Some(l), Some(r)) if l.type_of().is_number() && r.type_of().is_number() => {
+            number(l).partial_cmp(&number(r))
+        }
+        (Some(JmlValue::String(l)), Some(JmlValue::String(r))) => Some(l.0.cmp(&r.0)),
+        (l, r) => {
+            // Everything else, including missing values, can only be equal.
+            return match comparison {
+                Comparison::Eq => l == r,
+                Comparison::Ne => l != r,
+                _ => false,
+            };
+        }
+    };
+
+    match comparison {
+        Comparison::Eq => ordering == Some(Ordering::Equal),
+        Comparison::Ne => ordering != Some(Ordering::Equal),
+        Comparison::Lt => ordering == Some(Ordering::Less),
+        Comparison::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        Comparison::Gt => ordering == Some(Ordering::Greater),
+        Comparison::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+fn number(value: &JmlValue) -> f64 {
+    match value {
+        JmlValue::Int(v) => v.0 as f64,
+        JmlValue::Float(v) => v.0,
+        _ => f64::NAN,
+    }
+}
+
+struct Parser<'path> {
+    path: &'path str,
+    chars: Peekable<CharIndices<'path>>,
+}
+
+type ParseResult<T> = Result<T, RuntimeErrorKind>;
+
+impl<'path> Parser<'path> {
+    fn new(path: &'path str) -> Self {
+        Parser {
+            path,
+            chars: path.char_indices().peekable(),
+        }
+    }
+
+    fn parse(mut self) -> ParseResult<Vec<Segment>> {
+        self.skip_whitespace();
+        self.expect('$')?;
+        let segments = self.segments()?;
+        self.skip_whitespace();
+
+        match self.chars.peek() {
+            None => Ok(segments),
+            Some(&(_, c)) => Err(self.error(format!("unexpected `{}`", c))),
+        }
+    }
+
+    fn segments(&mut self) -> ParseResult<Vec<Segment>> {
+        let mut segments = vec![];
+
+        loop {
+            if self.eat_str("..") {
+                let selectors = if self.peek() == Some('[') {
+                    self.bracket()?
+                } else {
+                    vec![self.shorthand()?]
+                };
+                segments.push(Segment::Descendant(selectors));
+            } else if self.eat('.') {
+                segments.push(Segment::Child(vec![self.shorthand()?]));
+            } else if self.peek() == Some('[') {
+                segments.push(Segment::Child(self.bracket()?));
+            } else {
+                return Ok(segments);
+            }
+        }
+    }
+
+    /// The name or `*` after a `.`.
+    fn shorthand(&mut self) -> ParseResult<Selector> {
+        if self.eat('*') {
+            return Ok(Selector::Wildcard);
+        }
+
+        let name = self.name();
+        if name.is_empty() {
+            return Err(self.error("expected a name or `*`"));
+        }
+        Ok(Selector::Name(name))
+    }
+
+    fn name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(c) = self.peek() {
+            if !(c.is_alphanumeric() || c == '_') {
+                break;
+            }
+            name.push(c);
+            self.chars.next();
+        }
+        name
+    }
+
+    fn bracket(&mut self) -> ParseResult<Vec<Selector>> {
+        self.expect('[')?;
+
+        let mut selectors = vec![];
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.selector()?);
+            self.skip_whitespace();
+
+            if self.eat(']') {
+                return Ok(selectors);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn selector(&mut self) -> ParseResult<Selector> {
+        match self.peek() {
+            Some('*') => {
+                self.chars.next();
+                Ok(Selector::Wildcard)
+            }
+            Some('\'' | '"') => Ok(Selector::Name(self.string()?)),
+            Some('?') => {
+                self.chars.next();
+                self.skip_whitespace();
+                Ok(Selector::Filter(self.filter()?))
+            }
+            _ => {
+                let start = self.optional_integer()?;
+                self.skip_whitespace();
+                if !self.eat(':') {
+                    return start
+                        .map(Selector::Index)
+                        .ok_or_else(|| self.error("expected a selector"));
+                }
+
+                self.skip_whitespace();
+                let end = self.optional_integer()?;
+                self.skip_whitespace();
+                let step = if self.eat(':') {
+                    self.skip_whitespace();
+                    self.optional_integer()?
+                } else {
+                    None
+                };
+                Ok(Selector::Slice(start, end, step))
+            }
+        }
+    }
+
+    fn filter(&mut self) -> ParseResult<Filter> {
+        let lhs = self.conjunction()?;
+        self.skip_whitespace();
+        if self.eat_str("||") {
+            self.skip_whitespace();
+            return Ok(Filter::Or(Box::new(lhs), Box::new(self.filter()?)));
+        }
+        Ok(lhs)
+    }
+
+    fn conjunction(&mut self) -> ParseResult<Filter> {
+        let lhs = self.negation()?;
+        self.skip_whitespace();
+        if self.eat_str("&&") {
+            self.skip_whitespace();
+            return Ok(Filter::And(Box::new(lhs), Box::new(self.conjunction()?)));
+        }
+        Ok(lhs)
+    }
+
+    fn negation(&mut self) -> ParseResult<Filter> {
+        if self.peek() == Some('!') && !self.path[self.offset()..].starts_with("!=") {
+            self.chars.next();
+            self.skip_whitespace();
+            return Ok(Filter::Not(Box::new(self.negation()?)));
+        }
+
+        if self.eat('(') {
+            self.skip_whitespace();
+            let filter = self.filter()?;
+            self.skip_whitespace();
+            self.expect(')')?;
+            return Ok(filter);
+        }
+
+        let lhs = self.operand()?;
+        self.skip_whitespace();
+        let Some(comparison) = self.comparison() else {
+            return match lhs {
+                Operand::Path(path) => Ok(Filter::Exists(path)),
+                Operand::Literal(_) => Err(self.error("expected a comparison")),
+            };
+        };
+        self.skip_whitespace();
+        let rhs = self.operand()?;
+
+        Ok(Filter::Compare(lhs, comparison, rhs))
+    }
+
+    fn comparison(&mut self) -> Option<Comparison> {
+        let comparisons = [
+            ("==", Comparison::Eq),
+            ("!=", Comparison::Ne),
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ];
+
+        comparisons
+            .into_iter()
+            .find(|(symbol, _)| self.eat_str(symbol))
+            .map(|(_, comparison)| comparison)
+    }
+
+    fn operand(&mut self) -> ParseResult<Operand> {
+        match self.peek() {
+            Some(c @ ('@' | '$')) => {
+                self.chars.next();
+                Ok(Operand::Path(Path {
+                    relative: c == '@',
+                    segments: self.segments()?,
+                }))
+            }
+            Some('\'' | '"') => Ok(Operand::Literal(Literal::String(self.string()?))),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ => match self.name().as_str() {
+                "true" => Ok(Operand::Literal(Literal::Bool(true))),
+                "false" => Ok(Operand::Literal(Literal::Bool(false))),
+                "null" => Ok(Operand::Literal(Literal::Null)),
+                _ => Err(self.error("expected `@`, `$` or a literal")),
+            },
+        }
+    }
+
+    fn number(&mut self) -> ParseResult<Operand> {
+        let start = self.offset();
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                break;
+            }
+            self.chars.next();
+        }
+
+        let text = &self.path[start..self.offset()];
+        if let Ok(v) = text.parse() {
+            return Ok(Operand::Literal(Literal::Int(v)));
+        }
+        text.parse()
+            .map(|v| Operand::Literal(Literal::Float(v)))
+            .map_err(|_| self.error_at(start, format!("invalid number `{}`", text)))
+    }
+
+    fn optional_integer(&mut self) -> ParseResult<Option<i64>> {
+        let start = self.offset();
+        if self.peek() == Some('-') {
+            self.chars.next();
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.chars.next();
+        }
+
+        let text = &self.path[start..self.offset()];
+        if text.is_empty() {
+            return Ok(None);
+        }
+        text.parse()
+            .map(Some)
+            .map_err(|_| self.error_at(start, format!("invalid index `{}`", text)))
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        let start = self.offset();
+        let Some((_, quote)) = self.chars.next() else {
+            return Err(self.error("expected a string"));
+        };
+
+        let mut value = String::new();
+        while let Some((offset, c)) = self.chars.next() {
+            match c {
+                c if c == quote => return Ok(value),
+                '\\' => match self.chars.next() {
+                    Some((_, escaped)) => value.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        '\\' | '/' | '\'' | '"' => escaped,
+                        other => {
+                            return Err(
+                                self.error_at(offset, format!("unknown escape `\\{}`", other))
+                            )
+                        }
+                    }),
+                    None => break,
+                },
+                c => value.push(c),
+            }
+        }
+
+        Err(self.error_at(start, "unterminated string"))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.chars.next();
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn offset(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.path.len(), |&(offset, _)| offset)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+        if found {
+            self.chars.next();
+        }
+        found
+    }
+
+    fn eat_str(&mut self, expected: &str) -> bool {
+        let found = self.path[self.offset()..].starts_with(expected);
+        if found {
+            for _ in expected.chars() {
+                self.chars.next();
+            }
+        }
+        found
+    }
+
+    fn expect(&mut self, expected: char) -> ParseResult<()> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", expected)))
+        }
+    }
+
+    fn error(&mut self, reason: impl Into<String>) -> RuntimeErrorKind {
+        let offset = self.offset();
+        self.error_at(offset, reason)
+    }
+
+    fn error_at(&self, offset: usize, reason: impl Into<String>) -> RuntimeErrorKind {
+        RuntimeErrorKind::InvalidQuery {
+            path: self.path.to_owned(),
+            offset,
+            reason: reason.into(),
+        }
+    }
+}
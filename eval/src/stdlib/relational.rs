@@ -0,0 +1,207 @@
+//! `group_by`, `index_by`, `count_by` and `join`, which relate the elements of
+//! lists by a key computed for each element.
+
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+
+use crate::{
+    context::Context,
+    errors::{EvalError, RuntimeError, RuntimeErrorKind, TypeError},
+    expr::{
+        lambda::eval_lambda_application_with_evaluated_args, object_constructor::key_from_value,
+    },
+    jml_type::JmlType,
+    value::JmlValue,
+};
+
+use super::{expect_list, expect_string, mismatch};
+
+/// Groups the elements by the key the lambda returns, as an object of lists
+/// in the order the keys first appear.
+pub fn group_by<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    let mut groups: IndexMap<String, Vec<JmlValue>> = IndexMap::new();
+    for elem in list.iter() {
+        let key = object_key(span, &args[1], elem, ctx)?;
+        groups.entry(key).or_default().push(elem.clone());
+    }
+
+    Ok(JmlValue::object(
+        groups
+            .into_iter()
+            .map(|(key, group)| (key, JmlValue::list(group)))
+            .collect::<IndexMap<_, _>>(),
+    ))
+}
+
+/// An object of the elements by the key the lambda returns. When several
+/// elements share a key, the last one wins.
+pub fn index_by<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    let mut index = IndexMap::new();
+    for elem in list.iter() {
+        let key = object_key(span, &args[1], elem, ctx)?;
+        index.insert(key, elem.clone());
+    }
+
+    Ok(JmlValue::object(index))
+}
+
+/// Counts the elements for every key the lambda returns.
+pub fn count_by<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let list = expect_list(span, &args[0])?;
+
+    let mut counts: IndexMap<String, i64> = IndexMap::new();
+    for elem in list.iter() {
+        let key = object_key(span, &args[1], elem, ctx)?;
+        *counts.entry(key).or_default() += 1;
+    }
+
+    Ok(JmlValue::object(
+        counts
+            .into_iter()
+            .map(|(key, count)| (key, JmlValue::int(count)))
+            .collect::<IndexMap<_, _>>(),
+    ))
+}
+
+/// Joins two lists of objects on the keys the two lambdas return, merging
+/// every matching pair with `++`. `kind` is `"inner"`, `"left"`, which also
+/// keeps the unmatched left objects, or `"full"`, which keeps the unmatched
+/// objects of both sides. As in SQL, `null` keys never match.
+pub fn join<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let left = expect_list(span, &args[0])?;
+    let right = expect_list(span, &args[1])?;
+    let (keep_left, keep_right) = match expect_string(span, &args[4])? {
+        "inner" => (false, false),
+        "left" => (true, false),
+        "full" => (true, true),
+        kind => {
+            return Err(RuntimeError {
+                kind: RuntimeErrorKind::InvalidArgument {
+                    name: "kind".to_owned(),
+                    reason: format!(
+                        "expected \"inner\", \"left\" or \"full\", found \"{}\"",
+                        kind
+                    ),
+                },
+                span,
+            }
+            .into())
+        }
+    };
+
+    let right_keys = keys(span, &right.0, &args[3], ctx)?;
+    let mut by_key: HashMap<&JmlValue, Vec<usize>> = HashMap::new();
+    for (position, key) in right_keys.iter().enumerate() {
+        if *key != JmlValue::Null {
+            by_key.entry(key).or_default().push(position);
+        }
+    }
+
+    let mut joined = vec![];
+    let mut matched_right = vec![false; right.len()];
+    for left_row in left.iter() {
+        let key = apply(span, &args[2], left_row, ctx)?;
+        let matches = match key {
+            JmlValue::Null => None,
+            ref key => by_key.get(key),
+        };
+
+        match matches {
+            Some(positions) => {
+                for &position in positions {
+                    matched_right[position] = true;
+                    joined.push(merge(span, left_row, &right.0[position])?);
+                }
+            }
+            None if keep_left => joined.push(expect_row(span, left_row)?),
+            None => {}
+        }
+    }
+
+    if keep_right {
+        for (row, matched) in right.iter().zip(matched_right) {
+            if !matched {
+                joined.push(expect_row(span, row)?);
+            }
+        }
+    }
+
+    Ok(JmlValue::list(joined))
+}
+
+fn apply<'source>(
+    span: miette::SourceSpan,
+    lambda: &JmlValue<'source>,
+    elem: &JmlValue<'source>,
+    ctx: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    eval_lambda_application_with_evaluated_args(span, lambda.clone(), vec![elem.clone()], ctx)
+}
+
+fn keys<'source>(
+    span: miette::SourceSpan,
+    list: &[JmlValue<'source>],
+    lambda: &JmlValue<'source>,
+    ctx: &mut Context<'source>,
+) -> Result<Vec<JmlValue<'source>>, EvalError> {
+    list.iter()
+        .map(|elem| apply(span, lambda, elem, ctx))
+        .collect()
+}
+
+fn object_key<'source>(
+    span: miette::SourceSpan,
+    lambda: &JmlValue<'source>,
+    elem: &JmlValue<'source>,
+    ctx: &mut Context<'source>,
+) -> Result<String, EvalError> {
+    key_from_value(apply(span, lambda, elem, ctx)?).map_err(|kind| TypeError { kind, span }.into())
+}
+
+fn expect_row<'source>(
+    span: miette::SourceSpan,
+    row: &JmlValue<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    match row {
+        JmlValue::Object(_) => Ok(row.clone()),
+        other => Err(mismatch(span, vec![JmlType::Object], other)),
+    }
+}
+
+fn merge<'source>(
+    span: miette::SourceSpan,
+    left: &JmlValue<'source>,
+    right: &JmlValue<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    match (left, right) {
+        (JmlValue::Object(left), JmlValue::Object(right)) => {
+            let mut merged = IndexMap::clone(&left.0);
+            merged.extend(right.iter().map(|(k, v)| (k.clone(), v.clone())));
+            Ok(JmlValue::object(merged))
+        }
+        (JmlValue::Object(_), other) | (other, _) => {
+            Err(mismatch(span, vec![JmlType::Object], other))
+        }
+    }
+}
@@ -0,0 +1,406 @@
+//! Dates and times. A time is an RFC 3339 string such as
+//! `"2024-03-01T12:30:00+02:00"`, which keeps its UTC offset through every
+//! function, or an Int of seconds since the Unix epoch, which is read as UTC.
+//! Every function returns times as RFC 3339 strings.
+//!
+//! None of these functions reads the clock. Hosts that want scripts to see
+//! the current time bind it with [`bind_now`].
+
+use std::str::FromStr;
+
+use chrono::{
+    format::{Item, Parsed, StrftimeItems},
+    DateTime, Datelike, FixedOffset, Months, NaiveDate, NaiveDateTime, SecondsFormat, TimeDelta,
+    TimeZone, Timelike,
+};
+
+use crate::{
+    context::Context,
+    errors::{EvalError, RuntimeError, RuntimeErrorKind},
+    jml_type::JmlType,
+    value::JmlValue,
+};
+
+use super::{expect_int, expect_object, expect_string, mismatch};
+
+/// The variable [`bind_now`] binds.
+pub const NOW: &str = "now";
+
+/// Binds `now` to the given time, the only way scripts can see the clock.
+pub fn bind_now(ctx: &mut Context, now: DateTime<FixedOffset>) {
+    ctx.bind_with_value(NOW, render(now));
+}
+
+/// Parses `text` as ISO 8601 when `format` is null, and with the strftime
+/// `format` otherwise. Times without an offset are taken to be UTC, and
+/// dates without a time to be midnight.
+pub fn parse_time<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let text = expect_string(span, &args[0])?;
+    let time = match &args[1] {
+        JmlValue::Null => parse_iso(text),
+        format => parse_with_format(text, expect_string(span, format)?),
+    };
+
+    match time {
+        Ok(time) => Ok(render(time)),
+        Err(Unreadable::Invalid) => Err(invalid(
+            span,
+            "text",
+            format!("'{}' is not a valid time", text),
+        )),
+        Err(Unreadable::OutOfRange) => Err(out_of_range(span, "text")),
+    }
+}
+
+/// Formats a time with a strftime format string, in the time's own offset.
+pub fn format_time<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let time = expect_time(span, "time", &args[0])?;
+    let format = expect_string(span, &args[1])?;
+
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    if items.contains(&Item::Error) {
+        return Err(invalid(
+            span,
+            "format",
+            format!("'{}' is not a valid format", format),
+        ));
+    }
+
+    Ok(JmlValue::string(
+        time.format_with_items(items.iter()).to_string(),
+    ))
+}
+
+pub fn to_epoch<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    Ok(JmlValue::int(
+        expect_time(span, "time", &args[0])?.timestamp(),
+    ))
+}
+
+pub fn to_epoch_millis<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    Ok(JmlValue::int(
+        expect_time(span, "time", &args[0])?.timestamp_millis(),
+    ))
+}
+
+pub fn from_epoch<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let seconds = expect_int(span, &args[0])?;
+
+    DateTime::from_timestamp(seconds, 0)
+        .map(|time| render(time.fixed_offset()))
+        .ok_or_else(|| out_of_range(span, "seconds"))
+}
+
+pub fn from_epoch_millis<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let millis = expect_int(span, &args[0])?;
+
+    DateTime::from_timestamp_millis(millis)
+        .map(|time| render(time.fixed_offset()))
+        .ok_or_else(|| out_of_range(span, "millis"))
+}
+
+/// Shifts a time forward by a duration object such as `{ days: 1, hours: 2 }`.
+pub fn add_time<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let time = expect_time(span, "time", &args[0])?;
+    shift(span, time, &args[1], 1).map(render)
+}
+
+/// Shifts a time back by a duration object.
+pub fn sub_time<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let time = expect_time(span, "time", &args[0])?;
+    shift(span, time, &args[1], -1).map(render)
+}
+
+/// Truncates a time to the start of its year, month, day, hour, minute or
+/// second, in the time's own offset.
+pub fn truncate_time<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let time = expect_time(span, "time", &args[0])?;
+    let unit = expect_string(span, &args[1])?;
+
+    let local = time.naive_local();
+    let date = local.date();
+    let truncated = match unit {
+        "year" => date.with_day(1).and_then(|date| date.with_month(1)),
+        "month" => date.with_day(1),
+        _ => Some(date),
+    }
+    .and_then(|date| match unit {
+        "year" | "month" | "day" => date.and_hms_opt(0, 0, 0),
+        "hour" => date.and_hms_opt(local.hour(), 0, 0),
+        "minute" => date.and_hms_opt(local.hour(), local.minute(), 0),
+        "second" => date.and_hms_opt(local.hour(), local.minute(), local.second()),
+        _ => None,
+    })
+    .ok_or_else(|| {
+        invalid(
+            span,
+            "unit",
+            format!(
+                "expected one of \"year\", \"month\", \"day\", \"hour\", \"minute\" or \"second\", found \"{}\"",
+                unit
+            ),
+        )
+    })?;
+
+    in_offset(truncated, time.offset())
+        .map(render)
+        .ok_or_else(|| out_of_range(span, "time"))
+}
+
+/// The whole number of `unit`s from `start` to `end`, negative when `end`
+/// comes first.
+pub fn time_diff<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let start = expect_time(span, "start", &args[0])?;
+    let end = expect_time(span, "end", &args[1])?;
+    let unit = expect_string(span, &args[2])?;
+
+    let delta = end - start;
+    let count = match unit {
+        "weeks" => delta.num_weeks(),
+        "days" => delta.num_days(),
+        "hours" => delta.num_hours(),
+        "minutes" => delta.num_minutes(),
+        "seconds" => delta.num_seconds(),
+        "milliseconds" => delta.num_milliseconds(),
+        _ => {
+            return Err(invalid(
+                span,
+                "unit",
+                format!(
+                    "expected one of \"weeks\", \"days\", \"hours\", \"minutes\", \"seconds\" or \"milliseconds\", found \"{}\"",
+                    unit
+                ),
+            ))
+        }
+    };
+
+    Ok(JmlValue::int(count))
+}
+
+/// -1, 0 or 1 as the first time is before, at or after the second, whatever
+/// their offsets.
+pub fn compare_time<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let left = expect_time(span, "left", &args[0])?;
+    let right = expect_time(span, "right", &args[1])?;
+
+    Ok(JmlValue::int(left.cmp(&right) as i64))
+}
+
+/// The same instant, written in another offset such as `"+05:30"` or `"Z"`.
+pub fn to_offset<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let time = expect_time(span, "time", &args[0])?;
+    let offset = expect_string(span, &args[1])?;
+
+    let offset = match offset {
+        "Z" | "z" => Some(FixedOffset::east_opt(0).unwrap()),
+        other => FixedOffset::from_str(other).ok(),
+    }
+    .ok_or_else(|| {
+        invalid(
+            span,
+            "offset",
+            format!("'{}' is not a valid UTC offset", offset),
+        )
+    })?;
+
+    Ok(render(time.with_timezone(&offset)))
+}
+
+/// The UTC offset of a time, such as `"+02:00"`.
+pub fn time_offset<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let time = expect_time(span, "time", &args[0])?;
+    Ok(JmlValue::string(time.offset().to_string()))
+}
+
+fn expect_time(
+    span: miette::SourceSpan,
+    name: &str,
+    value: &JmlValue,
+) -> Result<DateTime<FixedOffset>, EvalError> {
+    match value {
+        JmlValue::String(text) => parse_iso(&text.0).map_err(|error| match error {
+            Unreadable::Invalid => {
+                invalid(span, name, format!("'{}' is not an RFC 3339 time", text.0))
+            }
+            Unreadable::OutOfRange => out_of_range(span, name),
+        }),
+        JmlValue::Int(seconds) => DateTime::from_timestamp(seconds.0, 0)
+            .map(|time| time.fixed_offset())
+            .ok_or_else(|| out_of_range(span, name)),
+        other => Err(mismatch(span, vec![JmlType::String, JmlType::Int], other)),
+    }
+}
+
+/// Why a text couldn't be read as a time.
+enum Unreadable {
+    Invalid,
+    /// The text is a time, but one chrono can't represent in its offset.
+    OutOfRange,
+}
+
+fn parse_iso(text: &str) -> Result<DateTime<FixedOffset>, Unreadable> {
+    if let Ok(time) = DateTime::<FixedOffset>::from_str(text) {
+        return Ok(time);
+    }
+
+    let local = NaiveDateTime::from_str(text)
+        .ok()
+        .or_else(|| {
+            NaiveDate::from_str(text)
+                .ok()
+                .map(|date| date.and_time(Default::default()))
+        })
+        .ok_or(Unreadable::Invalid)?;
+    in_offset(local, &FixedOffset::east_opt(0).unwrap()).ok_or(Unreadable::OutOfRange)
+}
+
+/// Parses with a strftime format, defaulting a missing offset to UTC and a
+/// missing time to midnight.
+fn parse_with_format(text: &str, format: &str) -> Result<DateTime<FixedOffset>, Unreadable> {
+    let mut parsed = Parsed::new();
+    chrono::format::parse(&mut parsed, text, StrftimeItems::new(format))
+        .map_err(|_| Unreadable::Invalid)?;
+
+    if let Ok(time) = parsed.to_datetime() {
+        return Ok(time);
+    }
+
+    let offset = parsed
+        .to_fixed_offset()
+        .unwrap_or(FixedOffset::east_opt(0).unwrap());
+    let time = parsed.to_naive_time().unwrap_or_default();
+    let date = parsed.to_naive_date().map_err(|_| Unreadable::Invalid)?;
+    in_offset(date.and_time(time), &offset).ok_or(Unreadable::OutOfRange)
+}
+
+/// Applies every unit of the duration object, scaled by `sign`. Years and
+/// months move by calendar months and clamp to the end of shorter months.
+fn shift(
+    span: miette::SourceSpan,
+    time: DateTime<FixedOffset>,
+    duration: &JmlValue,
+    sign: i64,
+) -> Result<DateTime<FixedOffset>, EvalError> {
+    let mut shifted = Some(time);
+
+    for (unit, amount) in expect_object(span, duration)?.iter() {
+        let amount = expect_int(span, amount)?.checked_mul(sign);
+        shifted = match unit.as_str() {
+            "years" | "months" => amount
+                .and_then(|amount| match unit.as_str() {
+                    "years" => amount.checked_mul(12),
+                    _ => Some(amount),
+                })
+                .zip(shifted)
+                .and_then(|(months, time)| {
+                    let magnitude = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+                    if months < 0 {
+                        time.checked_sub_months(magnitude)
+                    } else {
+                        time.checked_add_months(magnitude)
+                    }
+                }),
+            "weeks" | "days" | "hours" | "minutes" | "seconds" | "milliseconds" => amount
+                .and_then(|amount| match unit.as_str() {
+                    "weeks" => TimeDelta::try_weeks(amount),
+                    "days" => TimeDelta::try_days(amount),
+                    "hours" => TimeDelta::try_hours(amount),
+                    "minutes" => TimeDelta::try_minutes(amount),
+                    "seconds" => TimeDelta::try_seconds(amount),
+                    _ => TimeDelta::try_milliseconds(amount),
+                })
+                .zip(shifted)
+                .and_then(|(delta, time)| time.checked_add_signed(delta)),
+            _ => {
+                return Err(invalid(
+                    span,
+                    "duration",
+                    format!("unknown unit '{}'", unit),
+                ))
+            }
+        };
+    }
+
+    shifted.ok_or_else(|| out_of_range(span, "duration"))
+}
+
+/// The instant of a local time in a fixed offset, which doesn't exist when
+/// the instant falls outside chrono's range.
+fn in_offset(time: NaiveDateTime, offset: &FixedOffset) -> Option<DateTime<FixedOffset>> {
+    offset.from_local_datetime(&time).single()
+}
+
+fn render<'source>(time: DateTime<FixedOffset>) -> JmlValue<'source> {
+    JmlValue::string(time.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+}
+
+fn invalid(span: miette::SourceSpan, name: &str, reason: String) -> EvalError {
+    RuntimeError {
+        kind: RuntimeErrorKind::InvalidArgument {
+            name: name.to_owned(),
+            reason,
+        },
+        span,
+    }
+    .into()
+}
+
+fn out_of_range(span: miette::SourceSpan, name: &str) -> EvalError {
+    invalid(
+        span,
+        name,
+        "the time is out of the supported range".to_owned(),
+    )
+}
@@ -0,0 +1,217 @@
+//! Type introspection and conversions between types, for payloads whose
+//! fields don't always hold the same type.
+
+use crate::{
+    context::Context,
+    errors::{EvalError, RuntimeError, RuntimeErrorKind},
+    jml_type::JmlType,
+    value::JmlValue,
+};
+
+use super::{expect_string, math::number_from_str, mismatch};
+
+/// The name of the value's type: `"Null"`, `"Bool"`, `"Int"`, `"Float"`,
+/// `"String"`, `"List"`, `"Object"` or `"Lambda"`.
+pub fn type_of<'source>(
+    _: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    Ok(JmlValue::string(args[0].type_of().name()))
+}
+
+macro_rules! type_predicate {
+    ($func_name:ident, $pattern:pat) => {
+        pub fn $func_name<'source>(
+            _: miette::SourceSpan,
+            args: Vec<JmlValue<'source>>,
+            _: &mut Context<'source>,
+        ) -> Result<JmlValue<'source>, EvalError> {
+            Ok(JmlValue::bool(matches!(args[0].type_of(), $pattern)))
+        }
+    };
+}
+
+type_predicate!(is_null, JmlType::Null);
+type_predicate!(is_bool, JmlType::Bool);
+type_predicate!(is_int, JmlType::Int);
+type_predicate!(is_float, JmlType::Float);
+type_predicate!(is_number, JmlType::Int | JmlType::Float);
+type_predicate!(is_string, JmlType::String);
+type_predicate!(is_list, JmlType::List);
+type_predicate!(is_object, JmlType::Object);
+type_predicate!(is_lambda, JmlType::Lambda { .. });
+
+/// Writes the value as compact JSON, quoting strings.
+pub fn to_json<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    json(span, &args[0]).map(JmlValue::string)
+}
+
+/// Parses a JSON document, such as a payload embedded in a string field.
+pub fn parse_json<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let text = expect_string(span, &args[0])?;
+
+    serde_json::from_str::<JmlValue>(text).map_err(|error| {
+        RuntimeError {
+            kind: RuntimeErrorKind::InvalidArgument {
+                name: "text".to_owned(),
+                reason: format!("not valid JSON: {}", error),
+            },
+            span,
+        }
+        .into()
+    })
+}
+
+/// `null` and zero are false, and strings must be `"true"` or `"false"`.
+pub fn to_bool<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    let bool = match &args[0] {
+        JmlValue::Null => false,
+        JmlValue::Bool(bool) => bool.0,
+        JmlValue::Int(int) => int.0 != 0,
+        JmlValue::Float(float) => float.0 != 0.0 && !float.0.is_nan(),
+        JmlValue::String(string) => match string.0.as_str() {
+            "true" => true,
+            "false" => false,
+            other => {
+                return Err(RuntimeError {
+                    kind: RuntimeErrorKind::InvalidArgument {
+                        name: "value".to_owned(),
+                        reason: format!("expected \"true\" or \"false\", found \"{}\"", other),
+                    },
+                    span,
+                }
+                .into())
+            }
+        },
+        other => {
+            return Err(mismatch(
+                span,
+                vec![
+                    JmlType::Null,
+                    JmlType::Bool,
+                    JmlType::Int,
+                    JmlType::Float,
+                    JmlType::String,
+                ],
+                other,
+            ))
+        }
+    };
+
+    Ok(JmlValue::bool(bool))
+}
+
+/// Numbers stay as they are, bools become `1` or `0`, and strings are parsed
+/// like `parse_number`.
+pub fn to_number<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    match &args[0] {
+        JmlValue::Int(_) | JmlValue::Float(_) => Ok(args[0].clone()),
+        JmlValue::Bool(bool) => Ok(JmlValue::int(bool.0 as i64)),
+        JmlValue::String(string) => number_from_str(span, &string.0),
+        other => Err(mismatch(
+            span,
+            vec![JmlType::Bool, JmlType::Int, JmlType::Float, JmlType::String],
+            other,
+        )),
+    }
+}
+
+/// Strings are returned as they are and any other value is written as
+/// compact JSON. Floats always keep a fraction or an exponent, so
+/// `parse_number` gives back the same value.
+pub fn to_string<'source>(
+    span: miette::SourceSpan,
+    args: Vec<JmlValue<'source>>,
+    _: &mut Context<'source>,
+) -> Result<JmlValue<'source>, EvalError> {
+    match &args[0] {
+        JmlValue::String(string) => Ok(JmlValue::string(string.0.clone())),
+        JmlValue::Lambda(_) => Err(mismatch(
+            span,
+            vec![
+                JmlType::Null,
+                JmlType::Bool,
+                JmlType::Int,
+                JmlType::Float,
+                JmlType::String,
+                JmlType::List,
+                JmlType::Object,
+            ],
+            &args[0],
+        )),
+        value => json(span, value).map(JmlValue::string),
+    }
+}
+
+/// Lambdas have no JSON form, and neither do NaN and the infinities, which
+/// `serde_json` would quietly write as `null`.
+fn json(span: miette::SourceSpan, value: &JmlValue) -> Result<String, EvalError> {
+    if let Some(float) = non_finite(value) {
+        return Err(RuntimeError {
+            kind: RuntimeErrorKind::NotFinite {
+                operation: format!("to_json({})", float),
+            },
+            span,
+        }
+        .into());
+    }
+
+    if let Some(path) = lambda_path(value) {
+        let place = if path.is_empty() {
+            "the value is one".to_owned()
+        } else {
+            format!("found one at {}", path)
+        };
+        return Err(RuntimeError {
+            kind: RuntimeErrorKind::InvalidArgument {
+                name: "value".to_owned(),
+                reason: format!("lambdas can't be serialized to JSON, {}", place),
+            },
+            span,
+        }
+        .into());
+    }
+
+    Ok(serde_json::to_string(value).expect("values without lambdas serialize to JSON"))
+}
+
+/// Where the first lambda in the value is, as a path like `[0].handler`.
+fn lambda_path(value: &JmlValue) -> Option<String> {
+    match value {
+        JmlValue::Lambda(_) => Some(String::new()),
+        JmlValue::List(list) => list
+            .iter()
+            .enumerate()
+            .find_map(|(i, elem)| lambda_path(elem).map(|path| format!("[{}]{}", i, path))),
+        JmlValue::Object(object) => object
+            .iter()
+            .find_map(|(key, value)| lambda_path(value).map(|path| format!(".{}{}", key, path))),
+        _ => None,
+    }
+}
+
+fn non_finite(value: &JmlValue) -> Option<f64> {
+    match value {
+        JmlValue::Float(float) if !float.0.is_finite() => Some(float.0),
+        JmlValue::List(list) => list.iter().find_map(non_finite),
+        JmlValue::Object(object) => object.values().find_map(non_finite),
+        _ => None,
+    }
+}
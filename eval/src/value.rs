@@ -1,4 +1,8 @@
-use std::fmt::{self};
+use std::{
+    cmp::Ordering,
+    fmt::{self},
+    marker::PhantomData,
+};
 
 use bool::JmlBool;
 use float::JmlFloat;
@@ -9,7 +13,7 @@ use list::JmlList;
 use object::JmlObject;
 use serde::{
     de::{self, MapAccess, Visitor},
-    Deserialize, Deserializer, Serialize, Serializer,
+    ser, Deserialize, Deserializer, Serialize, Serializer,
 };
 use serde_json::Value;
 use string::JmlString;
@@ -26,7 +30,7 @@ pub mod list;
 pub mod object;
 pub mod string;
 
-#[derive(Default, Debug, Clone, PartialEq, Eq, Display, From)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Display, From)]
 pub enum JmlValue<'source> {
     #[default]
     #[display("null")]
@@ -65,16 +69,16 @@ impl From<Value> for JmlValue<'_> {
             Value::String(s) => JmlValue::string(s),
 
             Value::Array(arr) => {
-                let list = arr.into_iter().map(JmlValue::from).collect();
-                JmlValue::List(JmlList(list))
+                let list: Vec<_> = arr.into_iter().map(JmlValue::from).collect();
+                JmlValue::list(list)
             }
 
             Value::Object(obj) => {
-                let object = obj
+                let object: IndexMap<_, _> = obj
                     .into_iter()
                     .map(|(k, v)| (k, JmlValue::from(v)))
                     .collect();
-                JmlValue::Object(JmlObject(object))
+                JmlValue::object(object)
             }
         }
     }
@@ -184,6 +188,73 @@ impl<'source> JmlValue<'source> {
     }
 }
 
+/// A total order over all values. Values of different types are ordered
+/// `null` < bools < numbers < strings < lists < objects < lambdas. Ints and
+/// floats are compared by value, with NaN above every other number and an Int
+/// before a Float of the same value, since the two are not equal. Lists
+/// compare element by element and objects compare their entries sorted by
+/// key, as equality ignores the order of entries.
+impl Ord for JmlValue<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (JmlValue::Null, JmlValue::Null) => Ordering::Equal,
+            (JmlValue::Bool(a), JmlValue::Bool(b)) => a.cmp(b),
+            (JmlValue::Int(a), JmlValue::Int(b)) => a.cmp(b),
+            (JmlValue::Float(a), JmlValue::Float(b)) => a.cmp(b),
+            (JmlValue::Int(a), JmlValue::Float(b)) => {
+                compare_int_float(a.0, b.0).then(Ordering::Less)
+            }
+            (JmlValue::Float(a), JmlValue::Int(b)) => compare_int_float(b.0, a.0)
+                .reverse()
+                .then(Ordering::Greater),
+            (JmlValue::String(a), JmlValue::String(b)) => a.cmp(b),
+            (JmlValue::List(a), JmlValue::List(b)) => a.cmp(b),
+            (JmlValue::Object(a), JmlValue::Object(b)) => a.cmp(b),
+            (JmlValue::Lambda(a), JmlValue::Lambda(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for JmlValue<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl JmlValue<'_> {
+    /// Position of the type in the order of values.
+    fn rank(&self) -> u8 {
+        match self {
+            JmlValue::Null => 0,
+            JmlValue::Bool(_) => 1,
+            JmlValue::Int(_) | JmlValue::Float(_) => 2,
+            JmlValue::String(_) => 3,
+            JmlValue::List(_) => 4,
+            JmlValue::Object(_) => 5,
+            JmlValue::Lambda(_) => 6,
+        }
+    }
+}
+
+/// Compares an Int with a Float exactly, which converting either to the
+/// other's type wouldn't. NaN is above every Int.
+pub(crate) fn compare_int_float(int: i64, float: f64) -> Ordering {
+    // 2^63, the first float above every i64.
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+
+    if float.is_nan() || float >= LIMIT {
+        return Ordering::Less;
+    }
+    if float < -LIMIT {
+        return Ordering::Greater;
+    }
+
+    let whole = float.trunc();
+    int.cmp(&(whole as i64))
+        .then_with(|| 0.0.partial_cmp(&(float - whole)).unwrap_or(Ordering::Equal))
+}
+
 impl<'a> Serialize for JmlValue<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -197,20 +268,22 @@ impl<'a> Serialize for JmlValue<'a> {
             JmlValue::String(JmlString(s)) => serializer.serialize_str(s),
             JmlValue::List(JmlList(list)) => list.serialize(serializer),
             JmlValue::Object(JmlObject(map)) => map.serialize(serializer),
-            JmlValue::Lambda(_) => todo!(),
+            JmlValue::Lambda(_) => Err(ser::Error::custom("lambdas cannot be serialized")),
         }
     }
 }
 
-impl<'de> Deserialize<'de> for JmlValue<'de> {
+/// Objects keep the order of their keys, whatever features `serde_json` is
+/// built with.
+impl<'de, 'source> Deserialize<'de> for JmlValue<'source> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        struct JmlValueVisitor;
+        struct JmlValueVisitor<'source>(PhantomData<JmlValue<'source>>);
 
-        impl<'de> Visitor<'de> for JmlValueVisitor {
-            type Value = JmlValue<'de>;
+        impl<'de, 'source> Visitor<'de> for JmlValueVisitor<'source> {
+            type Value = JmlValue<'source>;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
                 formatter.write_str("a valid JSON value")
@@ -224,6 +297,14 @@ impl<'de> Deserialize<'de> for JmlValue<'de> {
                 Ok(JmlValue::Int(JmlInt(v)))
             }
 
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(
+                    i64::try_from(v).map_or(JmlValue::Float(JmlFloat(v as f64)), |v| {
+                        JmlValue::Int(JmlInt(v))
+                    }),
+                )
+            }
+
             fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
                 Ok(JmlValue::Float(JmlFloat(v)))
             }
@@ -261,7 +342,7 @@ impl<'de> Deserialize<'de> for JmlValue<'de> {
                 A: de::SeqAccess<'de>,
             {
                 let values = Vec::deserialize(de::value::SeqAccessDeserializer::new(seq))?;
-                Ok(JmlValue::List(JmlList(values)))
+                Ok(JmlValue::list(values))
             }
 
             fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
@@ -270,10 +351,10 @@ impl<'de> Deserialize<'de> for JmlValue<'de> {
             {
                 let map = IndexMap::deserialize(de::value::MapAccessDeserializer::new(map))?;
 
-                Ok(JmlValue::Object(JmlObject(map)))
+                Ok(JmlValue::object(map))
             }
         }
 
-        deserializer.deserialize_any(JmlValueVisitor)
+        deserializer.deserialize_any(JmlValueVisitor(PhantomData))
     }
 }
@@ -1,6 +1,6 @@
 use derive_more::{derive::Display, From, FromStr};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, From, Display, FromStr)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, From, Display, FromStr)]
 pub struct JmlBool(#[display("{}")] pub(crate) bool);
 
 impl JmlBool {
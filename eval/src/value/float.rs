@@ -1,4 +1,8 @@
-use std::ops::Neg;
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    ops::Neg,
+};
 
 use derive_more::{derive::Display, From, FromStr};
 
@@ -6,16 +10,50 @@ use derive_more::{derive::Display, From, FromStr};
 #[from(f64, f32, i32, i16, i8, u32, u16, u8)]
 pub struct JmlFloat(#[display("{}")] pub(crate) f64);
 
+/// Unlike `f64`, NaN equals itself, so that equality is an equivalence and
+/// floats can be hashed and sorted. `0.0` and `-0.0` are equal.
 impl PartialEq for JmlFloat {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        self.0 == other.0 || (self.0.is_nan() && other.0.is_nan())
     }
 }
 
 impl JmlFloat {
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
     pub fn negative(self) -> Self {
         JmlFloat(self.0.neg())
     }
 }
 
 impl Eq for JmlFloat {}
+
+impl Hash for JmlFloat {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let canonical = if self.0 == 0.0 {
+            0.0
+        } else if self.0.is_nan() {
+            f64::NAN
+        } else {
+            self.0
+        };
+        canonical.to_bits().hash(state);
+    }
+}
+
+/// Orders by value, with NaN above every other float.
+impl Ord for JmlFloat {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .partial_cmp(&other.0)
+            .unwrap_or_else(|| self.0.is_nan().cmp(&other.0.is_nan()))
+    }
+}
+
+impl PartialOrd for JmlFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -2,12 +2,21 @@ use std::ops::Neg;
 
 use derive_more::{derive::Display, From, FromStr};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, From, Display, FromStr)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, From, Display, FromStr)]
 #[from(i64, i32, i16, i8, u32, u16, u8)]
 pub struct JmlInt(#[display("{}")] pub(crate) i64);
 
 impl JmlInt {
+    pub fn value(self) -> i64 {
+        self.0
+    }
+
     pub fn negative(self) -> Self {
         JmlInt(self.0.neg())
     }
+
+    /// Negates the value, or `None` for `i64::MIN`, whose negation overflows.
+    pub fn checked_negative(self) -> Option<Self> {
+        self.0.checked_neg().map(JmlInt)
+    }
 }
@@ -1,6 +1,10 @@
 use derive_more::{Debug, Display};
 use parser::ast::Expression;
-use std::rc::Rc;
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    rc::Rc,
+};
 
 use crate::{context::Context, errors::EvalError};
 
@@ -14,8 +18,12 @@ pub type NativeFunction<'source, Span> = dyn Fn(Span, Vec<JmlValue<'source>>, &m
 #[derive(Debug, Clone)]
 pub enum LambdaBody<'source, S> {
     Common(Expression<'source>),
-    #[debug("Native function")]
-    Native(Rc<NativeFunction<'source, S>>),
+    /// A function of the host, known by the name it was registered under.
+    #[debug("Native function {name}")]
+    Native {
+        name: Rc<str>,
+        function: Rc<NativeFunction<'source, S>>,
+    },
 }
 
 #[derive(Debug, Clone, Display)]
@@ -25,10 +33,55 @@ pub struct JmlLambda<'source> {
     pub(crate) body: LambdaBody<'source, miette::SourceSpan>,
 }
 
+impl<'source> JmlLambda<'source> {
+    pub fn params(&self) -> &[Identifier<'source>] {
+        &self.params
+    }
+}
+
+/// Lambdas are equal when they come from the same definition, or are natives
+/// registered under the same name. A definition is known by the span of its
+/// body, which is enough as lambdas capture nothing: hosts that evaluate several
+/// sources together keep their spans apart by parsing each at its own offset,
+/// as the REPL does with its entries.
 impl<'source> PartialEq for JmlLambda<'source> {
     fn eq(&self, other: &Self) -> bool {
-        self.params.len() == other.params.len()
+        self.cmp(other) == Ordering::Equal
     }
 }
 
 impl<'a> Eq for JmlLambda<'a> {}
+
+impl Hash for JmlLambda<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.params.hash(state);
+        match &self.body {
+            LambdaBody::Common(body) => (body.l, body.r).hash(state),
+            LambdaBody::Native { name, .. } => name.hash(state),
+        }
+    }
+}
+
+/// Lambdas have no meaningful order; this one is only there to make the order
+/// of values total. Natives come first, by name, and definitions follow by
+/// where they are in the source.
+impl Ord for JmlLambda<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.params
+            .cmp(&other.params)
+            .then_with(|| match (&self.body, &other.body) {
+                (LambdaBody::Native { name: a, .. }, LambdaBody::Native { name: b, .. }) => {
+                    a.cmp(b)
+                }
+                (LambdaBody::Native { .. }, LambdaBody::Common(_)) => Ordering::Less,
+                (LambdaBody::Common(_), LambdaBody::Native { .. }) => Ordering::Greater,
+                (LambdaBody::Common(a), LambdaBody::Common(b)) => (a.l, a.r).cmp(&(b.l, b.r)),
+            })
+    }
+}
+
+impl PartialOrd for JmlLambda<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -1,15 +1,116 @@
-use std::fmt;
+use std::{fmt, rc::Rc};
 
 use super::JmlValue;
-use derive_more::From;
+use crate::errors::RuntimeErrorKind;
 
-#[derive(Debug, Clone, PartialEq, Eq, From)]
-pub struct JmlList<'source>(pub(crate) Vec<JmlValue<'source>>);
+/// Elements are shared between clones and copied on write, like [`super::object::JmlObject`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JmlList<'source>(pub(crate) Rc<Vec<JmlValue<'source>>>);
+
+impl<'source> From<Vec<JmlValue<'source>>> for JmlList<'source> {
+    fn from(elements: Vec<JmlValue<'source>>) -> Self {
+        JmlList(Rc::new(elements))
+    }
+}
 
 impl<'source> JmlList<'source> {
-    pub fn access_by_index(&self, index: usize) -> JmlValue<'source> {
-        self.0.get(index).map_or(JmlValue::null(), |v| v.clone())
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, JmlValue<'source>> {
+        self.0.iter()
+    }
+
+    /// The element at `index`, counting from the end when it is negative, or
+    /// `null` when there is none.
+    pub fn access_by_index(&self, index: i64) -> JmlValue<'source> {
+        resolve_index(index, self.0.len()).map_or(JmlValue::null(), |i| self.0[i].clone())
+    }
+
+    /// Like [`Self::access_by_index`], but an index outside the list is an error.
+    pub fn try_access_by_index(&self, index: i64) -> Result<JmlValue<'source>, RuntimeErrorKind> {
+        resolve_index(index, self.0.len())
+            .map(|i| self.0[i].clone())
+            .ok_or(RuntimeErrorKind::IndexOutOfBounds {
+                index,
+                length: self.0.len(),
+            })
     }
+
+    /// The elements selected by `[start:end:step]`, with Python semantics.
+    pub fn slice(
+        &self,
+        start: Option<i64>,
+        end: Option<i64>,
+        step: i64,
+    ) -> Result<JmlList<'source>, RuntimeErrorKind> {
+        let positions = slice_positions(self.0.len(), start, end, step)?;
+        Ok(positions
+            .map(|i| self.0[i].clone())
+            .collect::<Vec<_>>()
+            .into())
+    }
+}
+
+/// Turns an index that may count from the end into a position, if it is in range.
+pub(crate) fn resolve_index(index: i64, length: usize) -> Option<usize> {
+    let index = if index < 0 {
+        index.checked_add(i64::try_from(length).ok()?)?
+    } else {
+        index
+    };
+    usize::try_from(index).ok().filter(|&i| i < length)
+}
+
+/// Positions a slice selects from a sequence of `length` items. Bounds are
+/// clamped to the sequence rather than rejected, as in Python.
+pub(crate) fn slice_positions(
+    length: usize,
+    start: Option<i64>,
+    end: Option<i64>,
+    step: i64,
+) -> Result<impl Iterator<Item = usize>, RuntimeErrorKind> {
+    if step == 0 {
+        return Err(RuntimeErrorKind::SliceStepZero);
+    }
+
+    let length = i64::try_from(length).unwrap_or(i64::MAX);
+    // Backwards slices stop one before the first element.
+    let (lowest, highest) = if step > 0 {
+        (0, length)
+    } else {
+        (-1, length - 1)
+    };
+    let clamp = |bound: i64| {
+        let bound = if bound < 0 {
+            bound.saturating_add(length)
+        } else {
+            bound
+        };
+        bound.clamp(lowest, highest)
+    };
+
+    let start = start.map_or(if step > 0 { lowest } else { highest }, clamp);
+    let end = end.map_or(if step > 0 { highest } else { lowest }, clamp);
+
+    let mut position = start;
+    Ok(std::iter::from_fn(move || {
+        let in_range = if step > 0 {
+            position < end
+        } else {
+            position > end
+        };
+        in_range.then(|| {
+            let current = position;
+            position = position.saturating_add(step);
+            current as usize
+        })
+    }))
 }
 
 impl<'source> fmt::Display for JmlList<'source> {
@@ -1,18 +1,127 @@
-use derive_more::From;
 use indexmap::IndexMap;
-use std::fmt;
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    rc::Rc,
+};
 
 use super::JmlValue;
+use crate::{errors::RuntimeErrorKind, suggest};
 
-#[derive(Debug, Clone, PartialEq, Eq, From)]
-pub struct JmlObject<'source>(pub(crate) IndexMap<String, JmlValue<'source>>);
+/// Entries are shared between clones and copied on write, so passing objects
+/// around is cheap and a nested update only copies the levels it goes through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JmlObject<'source>(pub(crate) Rc<IndexMap<String, JmlValue<'source>>>);
+
+impl<'source> From<IndexMap<String, JmlValue<'source>>> for JmlObject<'source> {
+    fn from(entries: IndexMap<String, JmlValue<'source>>) -> Self {
+        JmlObject(Rc::new(entries))
+    }
+}
 
 impl<'source> JmlObject<'source> {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JmlValue<'source>> {
+        self.0.get(key)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    pub fn keys(&self) -> indexmap::map::Keys<'_, String, JmlValue<'source>> {
+        self.0.keys()
+    }
+
+    pub fn values(&self) -> indexmap::map::Values<'_, String, JmlValue<'source>> {
+        self.0.values()
+    }
+
+    pub fn iter(&self) -> indexmap::map::Iter<'_, String, JmlValue<'source>> {
+        self.0.iter()
+    }
+
     pub fn access_by_key(&self, key: impl AsRef<str>) -> JmlValue<'source> {
         self.0
             .get(key.as_ref())
             .map_or(JmlValue::null(), |v| v.clone())
     }
+
+    /// Like [`Self::access_by_key`], but a missing key is an error that lists
+    /// the available keys and suggests the closest one.
+    pub fn try_access_by_key(
+        &self,
+        key: impl AsRef<str>,
+    ) -> Result<JmlValue<'source>, RuntimeErrorKind> {
+        let key = key.as_ref();
+        self.0
+            .get(key)
+            .cloned()
+            .ok_or_else(|| RuntimeErrorKind::MissingKey {
+                key: key.to_owned(),
+                available: self.available_keys(),
+                suggestion: suggest::closest(key, self.0.keys().map(String::as_str))
+                    .map(|k| format!("did you mean '{}'?", k)),
+            })
+    }
+}
+
+/// How many keys a missing key error lists before it just counts the rest.
+const LISTED_KEYS: usize = 10;
+
+impl JmlObject<'_> {
+    fn available_keys(&self) -> String {
+        if self.0.is_empty() {
+            return "none".to_owned();
+        }
+
+        let mut listed: Vec<String> = self
+            .0
+            .keys()
+            .take(LISTED_KEYS)
+            .map(|k| format!("'{}'", k))
+            .collect();
+        if self.0.len() > LISTED_KEYS {
+            listed.push(format!("and {} more", self.0.len() - LISTED_KEYS));
+        }
+        listed.join(", ")
+    }
+}
+
+// Equality ignores the order of entries, so hashing and ordering compare
+// the entries sorted by key.
+impl<'source> JmlObject<'source> {
+    pub(crate) fn sorted_entries(&self) -> Vec<(&String, &JmlValue<'source>)> {
+        let mut entries: Vec<_> = self.0.iter().collect();
+        entries.sort_by_key(|(a, _)| *a);
+        entries
+    }
+}
+
+impl Hash for JmlObject<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sorted_entries().hash(state);
+    }
+}
+
+impl Ord for JmlObject<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sorted_entries().cmp(&other.sorted_entries())
+    }
+}
+
+impl PartialOrd for JmlObject<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl<'source> fmt::Display for JmlObject<'source> {
@@ -1,16 +1,54 @@
 use derive_more::{derive::Display, From, FromStr};
 
-use super::JmlValue;
+use super::{
+    list::{resolve_index, slice_positions},
+    JmlValue,
+};
+use crate::errors::RuntimeErrorKind;
 
-#[derive(Debug, Clone, PartialEq, Eq, From, FromStr, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, From, FromStr, Display)]
 #[from(String, &String, &str)]
 pub struct JmlString(#[display("\"{}\"")] pub(crate) String);
 
 impl JmlString {
-    pub fn get_by_index<'source>(&self, index: usize) -> JmlValue<'source> {
-        match self.0.chars().nth(index) {
-            Some(c) => JmlValue::string(c.to_string()),
-            None => JmlValue::null(),
-        }
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The character at `index`, counting from the end when it is negative,
+    /// or `null` when there is none.
+    pub fn get_by_index<'source>(&self, index: i64) -> JmlValue<'source> {
+        self.char_at(index)
+            .map_or(JmlValue::null(), |c| JmlValue::string(c.to_string()))
+    }
+
+    /// Like [`Self::get_by_index`], but an index outside the string is an error.
+    pub fn try_get_by_index<'source>(
+        &self,
+        index: i64,
+    ) -> Result<JmlValue<'source>, RuntimeErrorKind> {
+        self.char_at(index)
+            .map(|c| JmlValue::string(c.to_string()))
+            .ok_or_else(|| RuntimeErrorKind::IndexOutOfBounds {
+                index,
+                length: self.0.chars().count(),
+            })
+    }
+
+    /// The characters selected by `[start:end:step]`, with Python semantics.
+    pub fn slice(
+        &self,
+        start: Option<i64>,
+        end: Option<i64>,
+        step: i64,
+    ) -> Result<JmlString, RuntimeErrorKind> {
+        let chars: Vec<char> = self.0.chars().collect();
+        let positions = slice_positions(chars.len(), start, end, step)?;
+        Ok(JmlString(positions.map(|i| chars[i]).collect()))
+    }
+
+    fn char_at(&self, index: i64) -> Option<char> {
+        let position = resolve_index(index, self.0.chars().count())?;
+        self.0.chars().nth(position)
     }
 }
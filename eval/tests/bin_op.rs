@@ -1,4 +1,9 @@
-use eval::value::JmlValue;
+use eval::{
+    context::Context,
+    errors::{EvalError, RuntimeErrorKind},
+    expr::eval_expr,
+    value::JmlValue,
+};
 use proptest::prelude::*;
 
 #[test]
@@ -102,6 +107,32 @@ fn test_addition_operations() {
     });
 }
 
+#[test]
+fn test_integer_overflow_is_an_error() {
+    for source in [
+        "9223372036854775807 + 1",
+        "-9223372036854775807 - 2",
+        "3037000500 * 3037000500",
+        "-9223372036854775808 / -1",
+    ] {
+        let jml = parser::parse(source).expect("should successfully parse");
+
+        let Err(EvalError::RuntimeError(error)) = eval_expr(jml.body, &mut Context::new()) else {
+            panic!("{} should overflow", source);
+        };
+        assert!(
+            matches!(error.kind, RuntimeErrorKind::Overflow),
+            "{}",
+            source
+        );
+    }
+
+    let source = "-9223372036854775808 % -1";
+    let jml = parser::parse(source).expect("should successfully parse");
+    let result: JmlValue = eval::eval_with_source(jml, source).expect("should successfully eval");
+    assert_eq!(result, JmlValue::int(0));
+}
+
 // #[test]
 // fn test_string_concatenation() {
 //     proptest!(|(s1 in ".*", s2 in ".*")| {
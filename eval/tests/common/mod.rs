@@ -0,0 +1,50 @@
+//! The fixture the evaluation tests share: a header of bindings that every
+//! test body is evaluated after.
+
+// Each test crate only uses some of the helpers.
+#![allow(dead_code)]
+
+use eval::{context::Context, errors::EvalError, expr::eval_expr, value::JmlValue};
+
+pub struct Fixture {
+    pub header: &'static str,
+}
+
+/// Bodies evaluated with nothing bound but the standard library.
+pub const BARE: Fixture = Fixture::new("");
+
+impl Fixture {
+    /// `header` holds the bindings, followed by the `---` line.
+    pub const fn new(header: &'static str) -> Self {
+        Fixture { header }
+    }
+
+    /// The source `body` is evaluated as, which error spans point into.
+    pub fn source(&self, body: &str) -> &'static str {
+        format!("{}{}", self.header, body).leak()
+    }
+
+    pub fn eval(&self, body: &str) -> Result<JmlValue<'static>, EvalError> {
+        self.eval_with_ctx(body, Context::new())
+    }
+
+    pub fn eval_with_ctx(
+        &self,
+        body: &str,
+        mut ctx: Context<'static>,
+    ) -> Result<JmlValue<'static>, EvalError> {
+        let jml = parser::parse(self.source(body)).expect("should successfully parse");
+
+        eval::eval_header_with_ctx(jml.header, &mut ctx).expect("header should evaluate");
+        eval_expr(jml.body, &mut ctx)
+    }
+
+    pub fn assert_evals_same(&self, body: &str, expected: &str) {
+        assert_eq!(
+            self.eval(body).unwrap(),
+            self.eval(expected).unwrap(),
+            "{}",
+            body
+        );
+    }
+}
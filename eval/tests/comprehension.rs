@@ -0,0 +1,67 @@
+use eval::errors::{EvalError, TypeErrorKind};
+
+mod common;
+
+use common::Fixture;
+
+const USERS: Fixture = Fixture::new(
+    r#"users = [
+    { id: 1, name: "Ada", active: true, tags: ["admin", "dev"] },
+    { id: 2, name: "Bob", active: false, tags: [] },
+    { id: 3, name: "Cy", active: true, tags: ["dev"] }
+]
+---
+"#,
+);
+
+#[test]
+fn test_list_comprehension() {
+    USERS.assert_evals_same("[u.name for u in users if u.active]", r#"["Ada", "Cy"]"#);
+    USERS.assert_evals_same("[x * x for x in [1, 2, 3]]", "[1, 4, 9]");
+    USERS.assert_evals_same("[x for x in []]", "[]");
+}
+
+#[test]
+fn test_object_comprehension() {
+    USERS.assert_evals_same(
+        "{ u.name: u.id for u in users }",
+        "{ Ada: 1, Bob: 2, Cy: 3 }",
+    );
+    USERS.assert_evals_same(
+        r#"{ ("user_" ++ u.name): u.active for u in users if u.id > 1 }"#,
+        "{ user_Bob: false, user_Cy: true }",
+    );
+    USERS.assert_evals_same("{ (k): 0 for k in [\"a\", \"b\"] }", "{ a: 0, b: 0 }");
+}
+
+#[test]
+fn test_multiple_for_clauses() {
+    USERS.assert_evals_same(
+        "[[x, y] for x in [1, 2] for y in [\"a\", \"b\"]]",
+        r#"[[1, "a"], [1, "b"], [2, "a"], [2, "b"]]"#,
+    );
+    USERS.assert_evals_same(
+        "[tag for u in users if u.active for tag in u.tags if tag != \"admin\"]",
+        r#"["dev", "dev"]"#,
+    );
+}
+
+#[test]
+fn test_bindings_do_not_leak() {
+    USERS.assert_evals_same("[[x for x in [1, 2]], users[0].id]", "[[1, 2], 1]");
+    USERS.assert_evals_same("[[x for x in [1, 2]] for x in [\"a\"]]", "[[1, 2]]");
+}
+
+#[test]
+fn test_comprehension_type_errors() {
+    let Err(EvalError::TypeError(error)) = USERS.eval("[x for x in users[0]]") else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+    assert_eq!(error.span, (USERS.header.len() + 12, 8).into());
+
+    let Err(EvalError::TypeError(error)) = USERS.eval("[x for x in [1] if x]") else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+}
@@ -0,0 +1,91 @@
+use eval::{
+    errors::{EvalError, RuntimeErrorKind},
+    value::JmlValue,
+};
+use proptest::prelude::*;
+
+mod common;
+
+use common::BARE;
+
+fn assert_invalid_encoding(source: &str, expected: &str) {
+    let Err(EvalError::RuntimeError(error)) = BARE.eval(source) else {
+        panic!("expected a runtime error for {}", source);
+    };
+    let RuntimeErrorKind::InvalidEncoding { encoding, .. } = error.kind else {
+        panic!("expected an invalid encoding error for {}", source);
+    };
+    assert_eq!(encoding, expected, "{}", source);
+}
+
+#[test]
+fn test_base64() {
+    BARE.assert_evals_same(
+        r#"[base64_encode("hello"), base64_encode(""), base64_decode("aGVsbG8="), base64_decode("aGVsbG8"), base64_decode(base64_encode("héllo"))]"#,
+        r#"["aGVsbG8=", "", "hello", "hello", "héllo"]"#,
+    );
+    assert_invalid_encoding(r#"base64_decode("a$b")"#, "base64");
+    assert_invalid_encoding(r#"base64_decode("/w==")"#, "base64");
+}
+
+#[test]
+fn test_url_encoding() {
+    BARE.assert_evals_same(
+        r#"[url_encode("a b&c=d/é~"), url_decode("a%20b%26c%3dd+"), url_decode("plain")]"#,
+        r#"["a%20b%26c%3Dd%2F%C3%A9~", "a b&c=d+", "plain"]"#,
+    );
+    assert_invalid_encoding(r#"url_decode("100%")"#, "URL encoding");
+    assert_invalid_encoding(r#"url_decode("%zz")"#, "URL encoding");
+    assert_invalid_encoding(r#"url_decode("%FF")"#, "URL encoding");
+}
+
+#[test]
+fn test_hex() {
+    BARE.assert_evals_same(
+        r#"[hex_encode("hi"), hex_decode("6869"), hex_decode("C3A9")]"#,
+        r#"["6869", "hi", "é"]"#,
+    );
+    assert_invalid_encoding(r#"hex_decode("686")"#, "hex");
+    assert_invalid_encoding(r#"hex_decode("zz")"#, "hex");
+    assert_invalid_encoding(r#"hex_decode("89504e47")"#, "hex");
+}
+
+#[test]
+fn test_hashes() {
+    BARE.assert_evals_same(
+        r#"[sha256("abc"), sha256(""), sha1("abc"), md5("abc")]"#,
+        r#"[
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+            "900150983cd24fb0d6963f7d28e17f72"
+        ]"#,
+    );
+    assert!(BARE.eval("sha256(1)").is_err());
+}
+
+#[test]
+fn test_uuid_v5() {
+    BARE.assert_evals_same(
+        r#"[uuid_v5("dns", "python.org"), uuid_v5("6ba7b810-9dad-11d1-80b4-00c04fd430c8", "python.org")]"#,
+        r#"["886313e1-3b8a-5372-9b90-0c9aee199e5d", "886313e1-3b8a-5372-9b90-0c9aee199e5d"]"#,
+    );
+
+    let Err(EvalError::RuntimeError(error)) = BARE.eval(r#"uuid_v5("example", "a")"#) else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(
+        error.kind,
+        RuntimeErrorKind::InvalidArgument { .. }
+    ));
+}
+
+#[test]
+fn test_encodings_round_trip() {
+    proptest!(|(text in "[^\"\\\\]*")| {
+        for encoding in ["base64", "url", "hex"] {
+            let source = format!("{0}_decode({0}_encode(\"{1}\"))", encoding, text);
+            prop_assert_eq!(BARE.eval(&source).unwrap(), JmlValue::string(text.clone()));
+        }
+    });
+}
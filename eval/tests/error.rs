@@ -0,0 +1,94 @@
+use eval::errors::{EvalError, RuntimeErrorKind};
+
+mod common;
+
+use common::Fixture;
+
+const DATA: Fixture = Fixture::new(
+    r#"order = { id: 7, items: [{ sku: "a", qty: 2 }], customer: { name: "Ada" } }
+---
+"#,
+);
+
+#[test]
+fn test_error_fails_at_the_call() {
+    let body = r#"if order.id > 5 then error("order " ++ to_string(order.id) ++ " is too late") else order"#;
+    let source = DATA.source(body);
+
+    let Err(EvalError::RuntimeError(error)) = DATA.eval(body) else {
+        panic!("expected a runtime error");
+    };
+    let RuntimeErrorKind::GenericError { message } = &error.kind else {
+        panic!("expected a generic error");
+    };
+    assert_eq!(message, "order 7 is too late");
+
+    let start = error.span.offset();
+    assert!(source[start..start + error.span.len()].starts_with("error("));
+}
+
+#[test]
+fn test_assert() {
+    DATA.assert_evals_same(
+        r#"assert(len(order.items) > 0, "order has no items") ?? order.id"#,
+        "7",
+    );
+
+    let Err(EvalError::RuntimeError(error)) =
+        DATA.eval(r#"assert(order.customer.name == "Bob", "wrong customer") ?? order"#)
+    else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(
+        error.kind,
+        RuntimeErrorKind::AssertionFailed { ref message } if message == "wrong customer"
+    ));
+
+    assert!(matches!(
+        DATA.eval(r#"assert(1, "not a bool")"#),
+        Err(EvalError::TypeError(_))
+    ));
+}
+
+#[test]
+fn test_try_returns_the_body_or_the_fallback() {
+    DATA.assert_evals_same(
+        r#"[
+            try(\. order.customer.name, "unknown"),
+            try(\. order.shipping.address.zip, "unknown"),
+            try(\. 1 / 0, 0),
+            try(\. error("boom"), null),
+            try(\. to_number("n/a"), -1)
+        ]"#,
+        r#"["Ada", "unknown", 0, null, -1]"#,
+    );
+}
+
+#[test]
+fn test_try_passes_the_error_to_a_fallback_lambda() {
+    DATA.assert_evals_same(
+        r#"[
+            try(\. error("boom"), \e. e),
+            try(\. 1 / 0, \e. e.code),
+            try(\. parse_json("{"), \e. e.code)
+        ]"#,
+        r#"[
+            { message: "boom", code: "runtime_error::generic_runtime_error" },
+            "eval::division_by_zero",
+            "eval::invalid_argument"
+        ]"#,
+    );
+}
+
+#[test]
+fn test_try_only_catches_failures_of_its_body() {
+    assert!(matches!(
+        DATA.eval(r#"try(\. error("first"), \e. error("again: " ++ e.message))"#),
+        Err(EvalError::RuntimeError(ref error))
+            if matches!(&error.kind, RuntimeErrorKind::GenericError { message } if message == "again: first")
+    ));
+    assert!(matches!(
+        DATA.eval(r#"try(1, 2)"#),
+        Err(EvalError::TypeError(_))
+    ));
+}
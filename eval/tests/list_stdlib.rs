@@ -0,0 +1,162 @@
+use eval::{
+    errors::{EvalError, RuntimeErrorKind, TypeErrorKind},
+    value::JmlValue,
+};
+use proptest::prelude::*;
+
+mod common;
+
+use common::BARE;
+
+#[test]
+fn test_len() {
+    BARE.assert_evals_same(
+        r#"[len([1, 2, 3]), len("héllo"), len({ a: 1 }), len([])]"#,
+        "[3, 5, 1, 0]",
+    );
+}
+
+#[test]
+fn test_sort() {
+    BARE.assert_evals_same("sort([3, 1.5, 2, -1])", "[-1, 1.5, 2, 3]");
+    BARE.assert_evals_same(r#"sort(["b", "a", "c"])"#, r#"["a", "b", "c"]"#);
+    BARE.assert_evals_same("sort([])", "[]");
+}
+
+#[test]
+fn test_sort_by_is_stable() {
+    BARE.assert_evals_same(
+        r#"sort_by([{ n: "a", k: 2 }, { n: "b", k: 1 }, { n: "c", k: 2 }, { n: "d", k: 1 }], \x. x.k)"#,
+        r#"[{ n: "b", k: 1 }, { n: "d", k: 1 }, { n: "a", k: 2 }, { n: "c", k: 2 }]"#,
+    );
+}
+
+#[test]
+fn test_sort_of_mixed_types() {
+    BARE.assert_evals_same(
+        r#"sort([[1], "a", 2.5, null, { a: 1 }, true, 1, [0, 5]])"#,
+        r#"[null, true, 1, 2.5, "a", [0, 5], [1], { a: 1 }]"#,
+    );
+
+    let Err(EvalError::TypeError(error)) = BARE.eval(r#"sort([1, \x. x])"#) else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::NotOrderedType { .. }));
+
+    let Err(EvalError::TypeError(error)) = BARE.eval(r#"sort_by([1, 2], \x. (\y. x))"#) else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::NotOrderedType { .. }));
+}
+
+#[test]
+fn test_reshaping() {
+    BARE.assert_evals_same("reverse([1, 2, 3])", "[3, 2, 1]");
+    BARE.assert_evals_same("unique([1, 2, 1, 3, 2])", "[1, 2, 3]");
+    BARE.assert_evals_same("flatten([[1, 2], 3, [[4]]])", "[1, 2, 3, [4]]");
+    BARE.assert_evals_same(r#"flat_map([1, 2], \x. [x, x * 10])"#, "[1, 10, 2, 20]");
+    BARE.assert_evals_same(r#"zip([1, 2, 3], ["a", "b"])"#, r#"[[1, "a"], [2, "b"]]"#);
+    BARE.assert_evals_same(r#"enumerate(["a", "b"])"#, r#"[[0, "a"], [1, "b"]]"#);
+    BARE.assert_evals_same("[take([1, 2, 3], 2), take([1], 5)]", "[[1, 2], [1]]");
+    BARE.assert_evals_same("[drop([1, 2, 3], 2), drop([1], 5)]", "[[3], []]");
+    BARE.assert_evals_same("chunk([1, 2, 3, 4, 5], 2)", "[[1, 2], [3, 4], [5]]");
+    BARE.assert_evals_same(
+        r#"partition([1, 2, 3, 4], \x. x % 2 == 0)"#,
+        "[[2, 4], [1, 3]]",
+    );
+}
+
+#[test]
+fn test_searching() {
+    BARE.assert_evals_same(r#"find([1, 2, 3], \x. x > 1)"#, "2");
+    BARE.assert_evals_same(r#"find([1, 2, 3], \x. x > 5)"#, "null");
+    BARE.assert_evals_same(
+        r#"[any([1, 2], \x. x > 1), any([], \x. true)]"#,
+        "[true, false]",
+    );
+    BARE.assert_evals_same(
+        r#"[all([1, 2], \x. x > 1), all([], \x. false)]"#,
+        "[false, true]",
+    );
+    BARE.assert_evals_same(
+        r#"[contains([1, "a"], "a"), contains([1], 2)]"#,
+        "[true, false]",
+    );
+    BARE.assert_evals_same(r#"[index_of([1, 2, 2], 2), index_of([1], 3)]"#, "[1, null]");
+}
+
+#[test]
+fn test_range() {
+    BARE.assert_evals_same("range(0, 4)", "[0, 1, 2, 3]");
+    BARE.assert_evals_same("range(3, 1)", "[]");
+    BARE.assert_evals_same("range(-2, 0)", "[-2, -1]");
+}
+
+#[test]
+fn test_range_rejects_huge_ranges() {
+    for source in [
+        "range(0, 9223372036854775807)",
+        "range(-9223372036854775807, 9223372036854775807)",
+    ] {
+        let Err(EvalError::RuntimeError(error)) = BARE.eval(source) else {
+            panic!("expected a runtime error for {}", source);
+        };
+        assert!(matches!(
+            error.kind,
+            RuntimeErrorKind::InvalidArgument { ref name, .. } if name == "end"
+        ));
+    }
+}
+
+#[test]
+fn test_aggregates() {
+    BARE.assert_evals_same("[min([3, 1, 2]), max([3, 1.5, 2])]", "[1, 3]");
+    BARE.assert_evals_same("[min([]), max([])]", "[null, null]");
+    BARE.assert_evals_same("[sum([1, 2, 3]), sum([1, 2.5]), sum([])]", "[6, 3.5, 0]");
+    BARE.assert_evals_same("[avg([1, 2]), avg([])]", "[1.5, null]");
+}
+
+#[test]
+fn test_argument_errors() {
+    let Err(EvalError::TypeError(error)) = BARE.eval(r#"take("abc", 1)"#) else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+    assert_eq!(error.span, (0, 14).into());
+
+    let Err(EvalError::TypeError(error)) = BARE.eval(r#"any([1], \x. x)"#) else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+
+    let Err(EvalError::TypeError(error)) = BARE.eval("map([1], 2)") else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+
+    let Err(EvalError::RuntimeError(error)) = BARE.eval("chunk([1], 0)") else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(
+        error.kind,
+        RuntimeErrorKind::InvalidArgument { ref name, .. } if name == "size"
+    ));
+
+    let Err(EvalError::RuntimeError(error)) = BARE.eval("sum([9223372036854775807, 1])") else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(error.kind, RuntimeErrorKind::Overflow));
+}
+
+#[test]
+fn test_sort_matches_rust_sort() {
+    proptest!(|(list in proptest::collection::vec(any::<i64>(), 0..50))| {
+        let list_str = format!("[{}]", list.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "));
+        let source = format!("sort({})", list_str);
+
+        let mut expected = list.clone();
+        expected.sort();
+        let expected = JmlValue::list(expected.into_iter().map(JmlValue::int).collect::<Vec<_>>());
+        assert_eq!(BARE.eval(&source).unwrap(), expected);
+    });
+}
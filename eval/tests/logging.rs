@@ -0,0 +1,118 @@
+use std::{cell::RefCell, rc::Rc};
+
+use eval::{
+    context::Context,
+    logging::{LogLevel, Position},
+    value::JmlValue,
+};
+
+#[derive(Debug, PartialEq)]
+struct Captured {
+    level: LogLevel,
+    message: String,
+    value: String,
+    file: Option<String>,
+    position: Option<Position>,
+}
+
+fn run(source: &'static str, ctx: &mut Context<'static>) -> Rc<RefCell<Vec<Captured>>> {
+    let records = capture(ctx);
+
+    let jml = parser::parse(source).expect("should successfully parse");
+    eval::eval_with_ctx_source(jml, source, ctx).expect("should evaluate");
+    records
+}
+
+fn capture(ctx: &mut Context<'static>) -> Rc<RefCell<Vec<Captured>>> {
+    let records = Rc::new(RefCell::new(vec![]));
+    let sink = Rc::clone(&records);
+    ctx.set_log_sink(move |record: &eval::logging::LogRecord| {
+        sink.borrow_mut().push(Captured {
+            level: record.level,
+            message: record.message.to_owned(),
+            value: record.value.to_string(),
+            file: record.file.map(str::to_owned),
+            position: record.position,
+        })
+    });
+    records
+}
+
+#[test]
+fn test_log_levels_and_positions() {
+    let source =
+        "xs = [1, 2]\n---\n[log(\"first\", xs), debug(\"second\", 2), trace(3, \"third\")]";
+    let mut ctx = Context::new();
+    ctx.set_file_name("script.jml");
+    let records = run(source, &mut ctx);
+
+    let position = |column| Some(Position { line: 3, column });
+    let file = Some("script.jml".to_owned());
+    assert_eq!(
+        *records.borrow(),
+        vec![
+            Captured {
+                level: LogLevel::Info,
+                message: "first".to_owned(),
+                value: "[1, 2]".to_owned(),
+                file: file.clone(),
+                position: position(2),
+            },
+            Captured {
+                level: LogLevel::Debug,
+                message: "second".to_owned(),
+                value: "2".to_owned(),
+                file: file.clone(),
+                position: position(20),
+            },
+            Captured {
+                level: LogLevel::Trace,
+                message: "3".to_owned(),
+                value: "third".to_owned(),
+                file,
+                position: position(40),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_log_returns_its_value_inside_lambdas() {
+    let source = "xs = [1, 2, 3]\n---\nreduce(xs, 0, \\x acc. log(\"acc\", acc) + x)";
+    let mut ctx = Context::new();
+    let records = run(source, &mut ctx);
+
+    let values: Vec<_> = records.borrow().iter().map(|r| r.value.clone()).collect();
+    assert_eq!(values, vec!["0", "1", "3"]);
+    assert!(records.borrow().iter().all(|r| r.file.is_none()));
+
+    let jml = parser::parse(source).unwrap();
+    assert_eq!(
+        eval::eval_with_ctx_source(jml, source, &mut ctx).unwrap(),
+        JmlValue::int(6)
+    );
+}
+
+#[test]
+fn test_log_positions_across_sources() {
+    let first = "f = \\x.\n  log(\"x\", x)";
+    let second = "[1, 2, 3, f(1)]";
+    let offset = first.len() + 1;
+
+    let mut ctx = Context::new();
+    eval::stdlib::define_std_lib(&mut ctx);
+    let records = capture(&mut ctx);
+    ctx.add_source(0, first);
+    ctx.add_source(offset, second);
+
+    let parser::ast::StatementKind::Bind {
+        identifier,
+        expression,
+    } = parser::parse_statement(first).unwrap().node;
+    ctx.bind_with_expr(identifier.node, expression);
+    let body = parser::parse_expression_at(second, offset).unwrap();
+    eval::expr::eval_expr(body, &mut ctx).expect("should evaluate");
+
+    let positions: Vec<_> = records.borrow().iter().map(|r| r.position).collect();
+    assert_eq!(positions, vec![Some(Position { line: 2, column: 3 })]);
+}
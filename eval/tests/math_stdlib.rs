@@ -0,0 +1,131 @@
+use eval::{
+    context::Context,
+    errors::{EvalError, RuntimeErrorKind, TypeErrorKind},
+    value::JmlValue,
+};
+use proptest::prelude::*;
+
+mod common;
+
+use common::BARE;
+
+fn runtime_error(source: &str) -> RuntimeErrorKind {
+    match BARE.eval(source) {
+        Err(EvalError::RuntimeError(error)) => error.kind,
+        other => panic!("expected a runtime error for {}, got {:?}", source, other),
+    }
+}
+
+#[test]
+fn test_rounding_keeps_ints() {
+    BARE.assert_evals_same(
+        "[abs(-3), abs(-2.5), floor(7), floor(-2.5), ceil(2.1), ceil(-2.1)]",
+        "[3, 2.5, 7, -3.0, 3.0, -2.0]",
+    );
+    BARE.assert_evals_same(
+        "[round(2.5, 0), round(-2.5, 0), round(3.14159, 2), round(1250, -2), round(-1250, -2), round(1249, -2), round(1234.5, -2), round(7, 2)]",
+        "[3.0, -3.0, 3.14, 1300, -1300, 1200, 1200.0, 7]",
+    );
+    BARE.assert_evals_same("[round(5, -30), round(1.5, 400)]", "[0, 1.5]");
+    assert!(matches!(
+        runtime_error("abs(-9223372036854775807 - 1)"),
+        RuntimeErrorKind::Overflow
+    ));
+}
+
+#[test]
+fn test_float_functions() {
+    BARE.assert_evals_same(
+        "[sqrt(16), exp(0), ln(1), log2(8), log10(1000), sin(0), cos(0), atan2(0, -1) == acos(-1)]",
+        "[4.0, 1.0, 0.0, 3.0, 3.0, 0.0, 1.0, true]",
+    );
+
+    for source in ["sqrt(-1)", "ln(0)", "asin(2)", "exp(1000)", "log10(-1.5)"] {
+        assert!(
+            matches!(runtime_error(source), RuntimeErrorKind::NotFinite { .. }),
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn test_clamp() {
+    BARE.assert_evals_same(
+        "[clamp(5, 0, 3), clamp(-1, 0, 3), clamp(1.5, 0, 3), clamp(5, 0, 2.5)]",
+        "[3, 0, 1.5, 2.5]",
+    );
+    assert!(matches!(
+        runtime_error("clamp(1, 3, 0)"),
+        RuntimeErrorKind::InvalidArgument { .. }
+    ));
+}
+
+#[test]
+fn test_clamp_compares_by_value() {
+    assert_eq!(BARE.eval("clamp(1, 1.0, 2)").unwrap(), JmlValue::int(1));
+    assert_eq!(BARE.eval("clamp(2, 0.5, 2.0)").unwrap(), JmlValue::int(2));
+    assert_eq!(BARE.eval("clamp(1.0, 1, 2)").unwrap(), JmlValue::float(1.0));
+    assert_eq!(BARE.eval("clamp(0, 0.5, 1)").unwrap(), JmlValue::float(0.5));
+}
+
+#[test]
+fn test_clamp_rejects_nan() {
+    for (name, source) in [
+        ("number", "clamp(nan, 0, 1)"),
+        ("low", "clamp(0, nan, 1)"),
+        ("high", "clamp(0, 0, nan)"),
+    ] {
+        let mut ctx = Context::new();
+        ctx.bind_with_value("nan", JmlValue::float(f64::NAN));
+
+        let Err(EvalError::RuntimeError(error)) = BARE.eval_with_ctx(source, ctx) else {
+            panic!("expected a runtime error for {}", source);
+        };
+        assert!(matches!(
+            error.kind,
+            RuntimeErrorKind::InvalidArgument { name: ref found, .. } if found == name
+        ));
+    }
+}
+
+#[test]
+fn test_conversions() {
+    BARE.assert_evals_same(
+        r#"[to_int(2.9), to_int(-2.9), to_int("42"), to_int("1e3"), to_float(2), to_float("0.5")]"#,
+        "[2, -2, 42, 1000, 2.0, 0.5]",
+    );
+    BARE.assert_evals_same(
+        r#"[parse_number("-12"), parse_number("1.0"), parse_number("2.5e-3"), parse_number("100000000000000000000")]"#,
+        "[-12, 1.0, 0.0025, 1e20]",
+    );
+    BARE.assert_evals_same(
+        r#"[to_string(1), to_string(1.0), to_string(1e300), to_string("a"), to_string(null), to_string([1, [true, null]])]"#,
+        r#"["1", "1.0", "1e+300", "a", "null", "[1,[true,null]]"]"#,
+    );
+
+    assert!(matches!(
+        runtime_error(r#"parse_number("twelve")"#),
+        RuntimeErrorKind::InvalidArgument { .. }
+    ));
+    assert!(matches!(
+        runtime_error("to_int(1e19)"),
+        RuntimeErrorKind::Overflow
+    ));
+
+    let Err(EvalError::TypeError(error)) = BARE.eval("to_int(true)") else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+}
+
+#[test]
+fn test_float_to_string_round_trips() {
+    proptest!(|(float in any::<f64>().prop_filter("finite", |f| f.is_finite()))| {
+        let source = format!("parse_number(to_string({:?}))", float);
+        let JmlValue::Float(parsed) = BARE.eval(&source).unwrap() else {
+            panic!("expected a float");
+        };
+        prop_assert_eq!(parsed, float.into());
+    });
+}
@@ -0,0 +1,115 @@
+use eval::errors::{EvalError, RuntimeErrorKind, TypeErrorKind};
+
+mod common;
+
+use common::Fixture;
+
+const USER: Fixture = Fixture::new(
+    r#"user = { id: 7, name: "Ada", address: { city: "Berlin", zip: "10115" }, tags: ["a"] }
+---
+"#,
+);
+
+#[test]
+fn test_keys_values_and_entries() {
+    USER.assert_evals_same("keys(user)", r#"["id", "name", "address", "tags"]"#);
+    USER.assert_evals_same("values({ b: 1, a: 2 })", "[1, 2]");
+    USER.assert_evals_same("entries({ b: 1, a: 2 })", r#"[["b", 1], ["a", 2]]"#);
+    USER.assert_evals_same("keys({})", "[]");
+}
+
+#[test]
+fn test_from_entries_inverts_entries_and_pluck() {
+    USER.assert_evals_same("from_entries(entries(user)) == user", "true");
+    USER.assert_evals_same("keys(from_entries(pluck(user)))", "keys(user)");
+    USER.assert_evals_same(
+        r#"from_entries([["a", 1], ["b", 2], ["a", 3]])"#,
+        "{ a: 3, b: 2 }",
+    );
+}
+
+#[test]
+fn test_has_key_pick_and_omit() {
+    USER.assert_evals_same(
+        r#"[has_key(user, "name"), has_key(user, "email")]"#,
+        "[true, false]",
+    );
+    USER.assert_evals_same(
+        r#"keys(pick(user, ["tags", "id", "missing"]))"#,
+        r#"["id", "tags"]"#,
+    );
+    USER.assert_evals_same(
+        r#"omit(user, ["address", "tags"])"#,
+        r#"{ id: 7, name: "Ada" }"#,
+    );
+}
+
+#[test]
+fn test_mapping_and_filtering() {
+    USER.assert_evals_same(
+        r#"map_values({ a: 1, b: 2 }, \v. v * 10)"#,
+        "{ a: 10, b: 20 }",
+    );
+    USER.assert_evals_same(
+        r#"keys(map_keys({ a: 1, b: 2 }, \k. "x_" ++ k))"#,
+        r#"["x_a", "x_b"]"#,
+    );
+    USER.assert_evals_same(
+        r#"filter_entries(user, \k v. k != "id" && k != "tags")"#,
+        r#"{ name: "Ada", address: { city: "Berlin", zip: "10115" } }"#,
+    );
+}
+
+#[test]
+fn test_rename_keys_keeps_positions() {
+    USER.assert_evals_same(
+        r#"keys(rename_keys(user, { id: "user_id", tags: "labels" }))"#,
+        r#"["user_id", "name", "address", "labels"]"#,
+    );
+}
+
+#[test]
+fn test_deep_merge() {
+    USER.assert_evals_same(
+        r#"deep_merge(user, { address: { zip: "10117", country: "DE" }, tags: ["b"] })"#,
+        r#"{ id: 7, name: "Ada", address: { city: "Berlin", zip: "10117", country: "DE" }, tags: ["b"] }"#,
+    );
+    USER.assert_evals_same(
+        r#"(user ++ { address: { zip: "10117" } }).address"#,
+        r#"{ zip: "10117" }"#,
+    );
+}
+
+#[test]
+fn test_invert() {
+    USER.assert_evals_same(
+        r#"invert({ a: "x", b: "y", c: 1 })"#,
+        r#"{ x: "a", y: "b", "1": "c" }"#,
+    );
+}
+
+#[test]
+fn test_object_argument_errors() {
+    let Err(EvalError::TypeError(error)) = USER.eval("keys([1])") else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+
+    let Err(EvalError::TypeError(error)) = USER.eval("invert({ a: [1] })") else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+
+    let Err(EvalError::TypeError(error)) = USER.eval(r#"filter_entries(user, \k v. 1)"#) else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+
+    let Err(EvalError::RuntimeError(error)) = USER.eval("from_entries([[1, 2, 3]])") else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(
+        error.kind,
+        RuntimeErrorKind::InvalidArgument { ref name, .. } if name == "entries"
+    ));
+}
@@ -0,0 +1,78 @@
+use eval::value::JmlValue;
+
+fn eval(source: &'static str) -> miette::Result<JmlValue<'static>> {
+    let jml = parser::parse(source).expect("should successfully parse");
+
+    eval::eval_with_source(jml, source)
+}
+
+#[test]
+fn test_optional_chaining_on_null() {
+    let source = r#"user = { name: "Ada", address: null }
+---
+[user?.address?.city ?? "unknown", user?.name, null?[0]]"#;
+
+    let result = eval(source).expect("should successfully eval");
+
+    assert_eq!(
+        result,
+        JmlValue::list(vec![
+            JmlValue::string("unknown"),
+            JmlValue::string("Ada"),
+            JmlValue::null(),
+        ])
+    );
+}
+
+#[test]
+fn test_optional_access_skips_the_rest_of_the_chain() {
+    let result = eval("[null?.a.b, null?.a[0].b, null?[0].a[1:], { a: null }.a?.b.c]")
+        .expect("should successfully eval");
+
+    assert_eq!(
+        result,
+        JmlValue::list(vec![
+            JmlValue::null(),
+            JmlValue::null(),
+            JmlValue::null(),
+            JmlValue::null(),
+        ])
+    );
+    assert!(eval("{ a: null }.a?.b.c.d").is_ok());
+    assert!(eval("{ a: {} }?.a.b.c").is_err());
+}
+
+#[test]
+fn test_plain_access_on_null_still_fails() {
+    assert!(eval("null.a").is_err());
+    assert!(eval("null[0]").is_err());
+}
+
+#[test]
+fn test_optional_index_does_not_evaluate_index() {
+    let result = eval("null?[1 / 0]").expect("should successfully eval");
+
+    assert_eq!(result, JmlValue::null());
+}
+
+#[test]
+fn test_coalesce_is_lazy() {
+    let result =
+        eval("[1 ?? 1 / 0, false ?? true, null ?? null ?? 3]").expect("should successfully eval");
+
+    assert_eq!(
+        result,
+        JmlValue::list(vec![
+            JmlValue::int(1),
+            JmlValue::bool(false),
+            JmlValue::int(3),
+        ])
+    );
+}
+
+#[test]
+fn test_coalesce_binds_tighter_than_comparison() {
+    let result = eval("null ?? 0 > 5").expect("should successfully eval");
+
+    assert_eq!(result, JmlValue::bool(false));
+}
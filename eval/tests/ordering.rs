@@ -0,0 +1,167 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use eval::{context::Context, value::JmlValue};
+use indexmap::indexmap;
+use proptest::prelude::*;
+
+mod common;
+
+use common::BARE;
+
+#[test]
+fn test_order_across_types() {
+    let ordered = [
+        JmlValue::null(),
+        JmlValue::bool(false),
+        JmlValue::bool(true),
+        JmlValue::float(f64::NEG_INFINITY),
+        JmlValue::int(-1),
+        JmlValue::int(1),
+        JmlValue::float(1.0),
+        JmlValue::float(1.5),
+        JmlValue::int(i64::MAX),
+        JmlValue::float(f64::INFINITY),
+        JmlValue::float(f64::NAN),
+        JmlValue::string(""),
+        JmlValue::string("a"),
+        JmlValue::list(vec![]),
+        JmlValue::list(vec![JmlValue::int(1)]),
+        JmlValue::object(indexmap! {}),
+        JmlValue::object(indexmap! { "a".to_owned() => JmlValue::int(1) }),
+    ];
+
+    for (i, a) in ordered.iter().enumerate() {
+        for (j, b) in ordered.iter().enumerate() {
+            assert_eq!(a.cmp(b), i.cmp(&j), "{} vs {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn test_nan_and_zero_equality() {
+    let nan = JmlValue::float(f64::NAN);
+    assert_eq!(nan, nan.clone());
+    assert_eq!(JmlValue::float(0.0), JmlValue::float(-0.0));
+
+    let set: HashSet<_> = [
+        nan.clone(),
+        nan,
+        JmlValue::float(0.0),
+        JmlValue::float(-0.0),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn test_objects_ignore_entry_order() {
+    let ab = BARE.eval("{ a: 1, b: 2 }").unwrap();
+    let ba = BARE.eval("{ b: 2, a: 1 }").unwrap();
+
+    assert_eq!(ab.cmp(&ba), Ordering::Equal);
+    assert_eq!(HashSet::from([ab, ba]).len(), 1);
+}
+
+#[test]
+fn test_comparison_operators_on_lists_and_objects() {
+    BARE.assert_evals_same(
+        "[[1, 2] < [1, 3], [1, 2] < [1], [] <= [], [2] > [1, 5]]",
+        "[true, false, true, true]",
+    );
+    BARE.assert_evals_same(
+        "[{ a: 1 } < { a: 2 }, { a: 1, b: 2 } >= { b: 2, a: 1 }, null <= null]",
+        "[true, true, true]",
+    );
+    assert!(BARE.eval(r#"[1] < "a""#).is_err());
+}
+
+#[test]
+fn test_comparison_operators_compare_numbers_by_value_inside_containers() {
+    BARE.assert_evals_same(
+        "[1 < 1.0, 1 <= 1.0, [1] < [1.0], [1] <= [1.0], [1.0] >= [1], [1, 2] < [1.0, 2.5]]",
+        "[false, true, false, true, true, true]",
+    );
+    BARE.assert_evals_same(
+        "[{ a: 1 } < { a: 1.0 }, { a: 1 } >= { a: 1.0 }, { a: [2] } > { a: [1.5] }]",
+        "[false, true, true]",
+    );
+    BARE.assert_evals_same("[9007199254740993] > [9007199254740992.0]", "true");
+
+    let mut ctx = Context::new();
+    ctx.bind_with_value("nan", JmlValue::float(f64::NAN));
+    assert_eq!(
+        BARE.eval_with_ctx("[[nan] < [1], [nan] >= [1], [1, nan] < [2, nan]]", ctx)
+            .unwrap(),
+        BARE.eval("[false, false, true]").unwrap()
+    );
+}
+
+#[test]
+fn test_lambda_equality() {
+    BARE.assert_evals_same(
+        r#"f = \x. x
+g = \x. x
+---
+[f == f, f == g, map == map, map == filter]"#,
+        "[true, false, true, false]",
+    );
+}
+
+#[test]
+fn test_lambda_order() {
+    let JmlValue::List(list) = BARE.eval(r#"[map, filter, \x y. x, \x y. y]"#).unwrap() else {
+        panic!("expected a list");
+    };
+    let lambdas: Vec<_> = list.iter().collect();
+
+    assert_eq!(lambdas[0].cmp(lambdas[1]), Ordering::Greater);
+    assert_eq!(lambdas[1].cmp(lambdas[2]), Ordering::Less);
+    assert_eq!(lambdas[2].cmp(lambdas[3]), Ordering::Less);
+    assert_eq!(lambdas[3].cmp(&lambdas[3].clone()), Ordering::Equal);
+
+    for a in &lambdas {
+        for b in &lambdas {
+            assert_eq!(a == b, a.cmp(b) == Ordering::Equal, "{} and {}", a, b);
+        }
+    }
+}
+
+fn scalar() -> impl Strategy<Value = JmlValue<'static>> {
+    prop_oneof![
+        Just(JmlValue::null()),
+        any::<bool>().prop_map(JmlValue::bool),
+        (-3i64..3).prop_map(JmlValue::int),
+        prop_oneof![
+            (-3i64..3).prop_map(|i| i as f64),
+            Just(0.5),
+            Just(-0.0),
+            Just(f64::NAN),
+            Just(f64::INFINITY),
+        ]
+        .prop_map(JmlValue::float),
+        "[ab]{0,2}".prop_map(JmlValue::string),
+    ]
+}
+
+fn value() -> impl Strategy<Value = JmlValue<'static>> {
+    scalar().prop_recursive(2, 8, 3, |inner| {
+        prop_oneof![
+            proptest::collection::vec(inner.clone(), 0..3).prop_map(JmlValue::list),
+            proptest::collection::vec(("[ab]", inner), 0..3).prop_map(|entries| {
+                JmlValue::object(entries.into_iter().collect::<indexmap::IndexMap<_, _>>())
+            }),
+        ]
+    })
+}
+
+#[test]
+fn test_order_is_total_and_consistent_with_equality() {
+    proptest!(|(a in value(), b in value(), c in value())| {
+        prop_assert_eq!(a.cmp(&b) == Ordering::Equal, a == b);
+        prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        if a <= b && b <= c {
+            prop_assert!(a <= c);
+        }
+    });
+}
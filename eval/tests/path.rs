@@ -0,0 +1,99 @@
+use eval::errors::{EvalError, RuntimeErrorKind, TypeErrorKind};
+
+mod common;
+
+use common::Fixture;
+
+const ORDER: Fixture = Fixture::new(
+    r#"order = {
+    id: 7,
+    customer: { name: "Ada", address: { zip: "10115", city: "Berlin" } },
+    items: [{ sku: "a", qty: 1 }, { sku: "b", qty: 2 }]
+}
+---
+"#,
+);
+
+#[test]
+fn test_get_in() {
+    ORDER.assert_evals_same(
+        r#"get_in(order, ["customer", "address", "zip"], null)"#,
+        r#""10115""#,
+    );
+    ORDER.assert_evals_same(r#"get_in(order, ["items", -1, "qty"], 0)"#, "2");
+    ORDER.assert_evals_same(r#"get_in(order, ["items", 5, "qty"], 0)"#, "0");
+    ORDER.assert_evals_same(r#"get_in(order, ["id", "nested"], "none")"#, r#""none""#);
+    ORDER.assert_evals_same("get_in(order, [], null) == order", "true");
+}
+
+#[test]
+fn test_set_in() {
+    ORDER.assert_evals_same(
+        r#"set_in(order, ["customer", "address", "zip"], "10117").customer.address"#,
+        r#"{ zip: "10117", city: "Berlin" }"#,
+    );
+    ORDER.assert_evals_same(
+        r#"set_in(order, ["items", 0, "qty"], 5).items"#,
+        r#"[{ sku: "a", qty: 5 }, { sku: "b", qty: 2 }]"#,
+    );
+    ORDER.assert_evals_same(r#"set_in({}, ["a", "b"], 1)"#, "{ a: { b: 1 } }");
+}
+
+#[test]
+fn test_set_in_leaves_the_original_unchanged() {
+    ORDER.assert_evals_same(
+        r#"[set_in(order, ["id"], 8).id, order.id, order.customer == set_in(order, ["id"], 8).customer]"#,
+        "[8, 7, true]",
+    );
+}
+
+#[test]
+fn test_update_in() {
+    ORDER.assert_evals_same(
+        r#"update_in(order, ["items", 1, "qty"], \q. q * 10).items[1]"#,
+        r#"{ sku: "b", qty: 20 }"#,
+    );
+    ORDER.assert_evals_same(
+        r#"update_in(order, ["note"], \n. n ?? "none").note"#,
+        r#""none""#,
+    );
+}
+
+#[test]
+fn test_delete_in() {
+    ORDER.assert_evals_same(
+        r#"delete_in(order, ["customer", "address", "city"]).customer.address"#,
+        r#"{ zip: "10115" }"#,
+    );
+    ORDER.assert_evals_same(
+        r#"delete_in(order, ["items", 0]).items"#,
+        r#"[{ sku: "b", qty: 2 }]"#,
+    );
+    ORDER.assert_evals_same(r#"delete_in(order, ["missing", "key"]) == order"#, "true");
+}
+
+#[test]
+fn test_path_errors() {
+    let Err(EvalError::RuntimeError(error)) = ORDER.eval(r#"set_in(order, ["items", 2], 1)"#)
+    else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(
+        error.kind,
+        RuntimeErrorKind::IndexOutOfBounds {
+            index: 2,
+            length: 2
+        }
+    ));
+
+    let Err(EvalError::TypeError(error)) = ORDER.eval(r#"set_in(order, ["id", "x"], 1)"#) else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+
+    let Err(EvalError::TypeError(error)) = ORDER.eval(r#"get_in(order, ["items", true], 1)"#)
+    else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+}
@@ -0,0 +1,112 @@
+use eval::{
+    context::Context,
+    errors::{EvalError, RuntimeErrorKind},
+    expr::eval_expr,
+    value::JmlValue,
+};
+
+mod common;
+
+use common::Fixture;
+
+const DATA: Fixture = Fixture::new(
+    r#"data = {
+    items: [
+        { id: 1, price: 5, children: [{ id: 11, active: true }, { id: 12 }] },
+        { id: 2, price: 20, children: [{ id: 21, active: false }] },
+        { id: 3, price: 9.5, children: [] }
+    ],
+    limit: 10
+}
+---
+"#,
+);
+
+fn query(path: &str) -> Result<JmlValue<'static>, EvalError> {
+    DATA.eval(&format!("query(data, \"{}\")", path))
+}
+
+fn ints(values: &[i64]) -> JmlValue<'static> {
+    JmlValue::list(values.iter().map(|&v| JmlValue::int(v)).collect::<Vec<_>>())
+}
+
+#[test]
+fn test_query_child_and_wildcard() {
+    assert_eq!(query("$.items[*].id").unwrap(), ints(&[1, 2, 3]));
+    assert_eq!(
+        query("$.items[*].children[*].id").unwrap(),
+        ints(&[11, 12, 21])
+    );
+    assert_eq!(query("$['limit']").unwrap(), ints(&[10]));
+    assert_eq!(query("$.missing.id").unwrap(), ints(&[]));
+}
+
+#[test]
+fn test_query_recursive_descent() {
+    assert_eq!(query("$..id").unwrap(), ints(&[1, 11, 12, 2, 21, 3]));
+    assert_eq!(query("$..children[0].id").unwrap(), ints(&[11, 21]));
+}
+
+#[test]
+fn test_query_indices_slices_and_unions() {
+    assert_eq!(query("$.items[-1].id").unwrap(), ints(&[3]));
+    assert_eq!(query("$.items[:2].id").unwrap(), ints(&[1, 2]));
+    assert_eq!(query("$.items[::-2].id").unwrap(), ints(&[3, 1]));
+    assert_eq!(query("$.items[0, 2].id").unwrap(), ints(&[1, 3]));
+}
+
+#[test]
+fn test_query_filters() {
+    assert_eq!(query("$.items[?(@.price < 10)].id").unwrap(), ints(&[1, 3]));
+    assert_eq!(query("$.items[?@.price > $.limit].id").unwrap(), ints(&[2]));
+    assert_eq!(
+        query("$..children[?(@.active)].id").unwrap(),
+        ints(&[11, 21])
+    );
+    assert_eq!(
+        query("$..children[?(@.active == true || !@.active)].id").unwrap(),
+        ints(&[11, 12])
+    );
+    assert_eq!(
+        query("$.items[?(@.id >= 2 && @.children[0].id == 21)].price").unwrap(),
+        ints(&[20])
+    );
+}
+
+#[test]
+fn test_query_invalid_path() {
+    let Err(EvalError::RuntimeError(error)) = query("$.items[?(@.price <)]") else {
+        panic!("expected a runtime error");
+    };
+
+    let RuntimeErrorKind::InvalidQuery { offset, .. } = error.kind else {
+        panic!("expected an invalid query error, got {:?}", error.kind);
+    };
+    assert_eq!(offset, 19);
+}
+
+#[test]
+fn test_query_decodes_escapes_in_names() {
+    let source =
+        r#"[query(data, "$['a\nb']"), query(data, "$['a\tb']"), query(data, "$['a\\nb']")]"#;
+    let expression = parser::parse_expression(source).expect("should successfully parse");
+
+    let mut ctx = Context::new();
+    eval::stdlib::define_std_lib(&mut ctx);
+    ctx.bind_with_value(
+        "data",
+        serde_json::json!({ "a\nb": 1, "a\tb": 2, "a\\nb": 3, "anb": 4 }),
+    );
+
+    assert_eq!(
+        eval_expr(expression, &mut ctx).unwrap(),
+        JmlValue::list(vec![ints(&[1]), ints(&[2]), ints(&[3])])
+    );
+
+    ctx.bind_with_value("unknown", serde_json::json!(r"$['a\qb']"));
+    let expression = parser::parse_expression("query(data, unknown)").unwrap();
+    let Err(EvalError::RuntimeError(error)) = eval_expr(expression, &mut ctx) else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(error.kind, RuntimeErrorKind::InvalidQuery { .. }));
+}
@@ -0,0 +1,121 @@
+use eval::{
+    context::Context,
+    errors::{EvalError, RuntimeErrorKind, TypeErrorKind},
+    expr::eval_expr,
+};
+
+mod common;
+
+use common::Fixture;
+
+const DATA: Fixture = Fixture::new(
+    r#"persons = [
+    { personId: 1, name: "John", team: "a" },
+    { personId: 2, name: "Jane", team: "b" },
+    { personId: 3, name: "Mark", team: "a" }
+]
+students = [
+    { studentId: 101, personId: 1 },
+    { studentId: 102, personId: 2 },
+    { studentId: 104, personId: 4 },
+    { studentId: 105, personId: 1 }
+]
+---
+"#,
+);
+
+#[test]
+fn test_group_by() {
+    DATA.assert_evals_same(
+        r#"map_values(group_by(persons, \p. p.team), \ps. map(ps, \p. p.name))"#,
+        r#"{ a: ["John", "Mark"], b: ["Jane"] }"#,
+    );
+    DATA.assert_evals_same(
+        r#"keys(group_by([3, 1, 2, 4], \x. x % 2))"#,
+        r#"["1", "0"]"#,
+    );
+}
+
+#[test]
+fn test_index_by_and_count_by() {
+    DATA.assert_evals_same(
+        r#"index_by(students, \s. s.personId)["1"].studentId"#,
+        "105",
+    );
+    DATA.assert_evals_same(r#"count_by(persons, \p. p.team)"#, "{ a: 2, b: 1 }");
+}
+
+#[test]
+fn test_inner_join() {
+    DATA.assert_evals_same(
+        r#"map(join(persons, students, \p. p.personId, \s. s.personId, "inner"), \r. [r.name, r.studentId])"#,
+        r#"[["John", 101], ["John", 105], ["Jane", 102]]"#,
+    );
+}
+
+#[test]
+fn test_left_and_full_join() {
+    DATA.assert_evals_same(
+        r#"map(join(persons, students, \p. p.personId, \s. s.personId, "left"), \r. [r?.name, r?.studentId])"#,
+        r#"[["John", 101], ["John", 105], ["Jane", 102], ["Mark", null]]"#,
+    );
+    DATA.assert_evals_same(
+        r#"map(join(persons, students, \p. p.personId, \s. s.personId, "full"), \r. [r?.name, r?.studentId])"#,
+        r#"[["John", 101], ["John", 105], ["Jane", 102], ["Mark", null], [null, 104]]"#,
+    );
+}
+
+#[test]
+fn test_join_keys() {
+    // Null keys never match, and list keys compare by value.
+    DATA.assert_evals_same(
+        r#"join([{ k: null, l: 1 }, { k: [1, "x"], l: 2 }], [{ k: null, r: 1 }, { k: [1, "x"], r: 2 }], \a. a.k, \b. b.k, "inner")"#,
+        r#"[{ k: [1, "x"], l: 2, r: 2 }]"#,
+    );
+    DATA.assert_evals_same(
+        r#"len(join([{ k: { a: 1, b: 2 } }], [{ k: { b: 2, a: 1 } }], \a. a.k, \b. b.k, "inner"))"#,
+        "1",
+    );
+}
+
+#[test]
+fn test_join_many_rows() {
+    let body = r#"left = map(range(0, 2000), \i. { id: i, l: i * 2 })
+right = map(range(0, 2000), \i. { id: 1999 - i, r: i })
+joined = join(left, right, \a. a.id, \b. b.id, "inner")
+---
+[len(joined), joined[10]]"#;
+    let jml = parser::parse(body).expect("should successfully parse");
+    let mut ctx = Context::new();
+    eval::eval_header_with_ctx(jml.header, &mut ctx).expect("header should evaluate");
+    let result = eval_expr(jml.body, &mut ctx).unwrap();
+
+    assert_eq!(
+        result,
+        DATA.eval("[2000, { id: 10, l: 20, r: 1989 }]").unwrap()
+    );
+}
+
+#[test]
+fn test_relational_errors() {
+    let Err(EvalError::RuntimeError(error)) =
+        DATA.eval(r#"join(persons, students, \p. p.personId, \s. s.personId, "outer")"#)
+    else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(
+        error.kind,
+        RuntimeErrorKind::InvalidArgument { ref name, .. } if name == "kind"
+    ));
+
+    let Err(EvalError::TypeError(error)) = DATA.eval(r#"group_by(persons, \p. [p.team])"#) else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+
+    let Err(EvalError::TypeError(error)) = DATA.eval(r#"join([1], [1], \a. a, \b. b, "inner")"#)
+    else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+}
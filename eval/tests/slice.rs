@@ -0,0 +1,94 @@
+use eval::{
+    context::Context,
+    errors::{EvalError, RuntimeErrorKind, TypeErrorKind},
+    expr::eval_expr,
+    value::JmlValue,
+};
+
+fn eval(source: &str) -> Result<JmlValue<'_>, EvalError> {
+    let jml = parser::parse(source).expect("should successfully parse");
+
+    eval_expr(jml.body, &mut Context::new())
+}
+
+fn ints(values: &[i64]) -> JmlValue<'static> {
+    JmlValue::list(values.iter().map(|&v| JmlValue::int(v)).collect::<Vec<_>>())
+}
+
+#[test]
+fn test_negative_index() {
+    assert_eq!(eval("[1, 2, 3][-1]").unwrap(), JmlValue::int(3));
+    assert_eq!(eval("[1, 2, 3][-3]").unwrap(), JmlValue::int(1));
+    assert_eq!(eval("[1, 2, 3][-4]").unwrap(), JmlValue::null());
+    assert_eq!(eval(r#""héllo"[-4]"#).unwrap(), JmlValue::string("é"));
+}
+
+#[test]
+fn test_list_slices() {
+    assert_eq!(eval("[0, 1, 2, 3, 4][1:3]").unwrap(), ints(&[1, 2]));
+    assert_eq!(eval("[0, 1, 2, 3, 4][:-1]").unwrap(), ints(&[0, 1, 2, 3]));
+    assert_eq!(eval("[0, 1, 2, 3, 4][::2]").unwrap(), ints(&[0, 2, 4]));
+    assert_eq!(
+        eval("[0, 1, 2, 3, 4][::-1]").unwrap(),
+        ints(&[4, 3, 2, 1, 0])
+    );
+    assert_eq!(eval("[0, 1, 2, 3, 4][3:0:-2]").unwrap(), ints(&[3, 1]));
+    assert_eq!(
+        eval("[0, 1, 2, 3, 4][-10:10]").unwrap(),
+        ints(&[0, 1, 2, 3, 4])
+    );
+    assert_eq!(eval("[0, 1, 2, 3, 4][4:1]").unwrap(), ints(&[]));
+    assert_eq!(eval("[0, 1, 2][null:2]").unwrap(), ints(&[0, 1]));
+}
+
+#[test]
+fn test_string_slices_are_char_based() {
+    assert_eq!(eval(r#""héllo"[1:3]"#).unwrap(), JmlValue::string("él"));
+    assert_eq!(eval(r#""héllo"[:-1]"#).unwrap(), JmlValue::string("héll"));
+    assert_eq!(eval(r#""héllo"[::-1]"#).unwrap(), JmlValue::string("olléh"));
+}
+
+#[test]
+fn test_optional_slice_of_null() {
+    assert_eq!(eval(r#"null?[1:"x"]"#).unwrap(), JmlValue::null());
+}
+
+#[test]
+fn test_non_integer_bound_is_type_error() {
+    let Err(EvalError::TypeError(error)) = eval(r#"[1, 2, 3][1:"2"]"#) else {
+        panic!("expected a type error");
+    };
+
+    assert!(matches!(
+        error.kind,
+        TypeErrorKind::InvalidSliceBound { ref bound, .. } if bound == "end"
+    ));
+    assert_eq!(error.span, (12, 3).into());
+}
+
+#[test]
+fn test_zero_step_is_runtime_error() {
+    let Err(EvalError::RuntimeError(error)) = eval("[1, 2, 3][::0]") else {
+        panic!("expected a runtime error");
+    };
+
+    assert!(matches!(error.kind, RuntimeErrorKind::SliceStepZero));
+}
+
+#[test]
+fn test_strict_negative_index_out_of_bounds() {
+    let jml = parser::parse("[1, 2, 3][-4]").expect("should successfully parse");
+    let mut ctx = Context::new();
+    ctx.set_strict(true);
+
+    let Err(EvalError::RuntimeError(error)) = eval_expr(jml.body, &mut ctx) else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(
+        error.kind,
+        RuntimeErrorKind::IndexOutOfBounds {
+            index: -4,
+            length: 3
+        }
+    ));
+}
@@ -0,0 +1,52 @@
+use eval::errors::{EvalError, TypeErrorKind};
+
+mod common;
+
+use common::BARE;
+
+#[test]
+fn test_object_spread() {
+    BARE.assert_evals_same(
+        "base = { a: 1, b: 2 }\n---\n{ ...base, b: 3, c: 4 }",
+        "{ a: 1, b: 3, c: 4 }",
+    );
+    BARE.assert_evals_same(
+        "base = { a: 1, b: 2 }\n---\n{ b: 3, ...base }",
+        "{ b: 2, a: 1 }",
+    );
+}
+
+#[test]
+fn test_list_spread() {
+    BARE.assert_evals_same(
+        "a = [1, 2]\nb = [4]\n---\n[...a, 3, ...b, ...[]]",
+        "[1, 2, 3, 4]",
+    );
+}
+
+#[test]
+fn test_conditional_members() {
+    BARE.assert_evals_same(
+        "verbose = false\n---\n{ name: \"x\", debug: 1 / 0 if verbose, level: 2 if !verbose }",
+        "{ name: \"x\", level: 2 }",
+    );
+}
+
+#[test]
+fn test_spread_type_errors() {
+    let Err(EvalError::TypeError(error)) = BARE.eval("{ a: 1, ...[1, 2] }") else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+    assert_eq!(error.span, (11, 6).into());
+
+    let Err(EvalError::TypeError(error)) = BARE.eval("[...{ a: 1 }]") else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+
+    let Err(EvalError::TypeError(error)) = BARE.eval("{ a: 1 if 1 }") else {
+        panic!("expected a type error");
+    };
+    assert!(matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }));
+}
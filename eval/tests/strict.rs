@@ -0,0 +1,127 @@
+use eval::{
+    context::Context,
+    errors::{EvalError, RuntimeErrorKind},
+    value::JmlValue,
+};
+
+mod common;
+
+use common::BARE;
+
+fn eval_strict(source: &str) -> Result<JmlValue<'static>, EvalError> {
+    let mut ctx = Context::new();
+    ctx.set_strict(true);
+    BARE.eval_with_ctx(source, ctx)
+}
+
+fn runtime_error(source: &str) -> RuntimeErrorKind {
+    match eval_strict(source) {
+        Err(EvalError::RuntimeError(error)) => error.kind,
+        other => panic!("expected a runtime error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_strict_missing_key_suggests_closest() {
+    let error = runtime_error(
+        r#"p = { personId: 1, name: "Ada" }
+---
+p.personid"#,
+    );
+
+    let RuntimeErrorKind::MissingKey {
+        key,
+        available,
+        suggestion,
+    } = error
+    else {
+        panic!("expected a missing key error, got {:?}", error);
+    };
+    assert_eq!(key, "personid");
+    assert_eq!(available, "'personId', 'name'");
+    assert_eq!(suggestion.as_deref(), Some("did you mean 'personId'?"));
+}
+
+#[test]
+fn test_strict_missing_key_without_close_match() {
+    let error = runtime_error(r#"{ a: 1 }["something"]"#);
+
+    assert!(matches!(
+        error,
+        RuntimeErrorKind::MissingKey {
+            suggestion: None,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_strict_missing_key_lists_at_most_ten_keys() {
+    let error = runtime_error(
+        "{ k1: 1, k2: 2, k3: 3, k4: 4, k5: 5, k6: 6, k7: 7, k8: 8, k9: 9, k10: 10, k11: 11, k12: 12 }.k",
+    );
+
+    let RuntimeErrorKind::MissingKey {
+        available,
+        suggestion,
+        ..
+    } = error
+    else {
+        panic!("expected a missing key error, got {:?}", error);
+    };
+    assert_eq!(
+        available,
+        "'k1', 'k2', 'k3', 'k4', 'k5', 'k6', 'k7', 'k8', 'k9', 'k10', and 2 more"
+    );
+    // `k` is a single edit away from `k1` to `k9`, which is no real hint.
+    assert_eq!(suggestion, None);
+}
+
+#[test]
+fn test_strict_index_out_of_bounds() {
+    let error = runtime_error("[1, 2, 3][3]");
+    assert!(matches!(
+        error,
+        RuntimeErrorKind::IndexOutOfBounds {
+            index: 3,
+            length: 3
+        }
+    ));
+
+    let error = runtime_error(r#""abc"[5]"#);
+    assert!(matches!(
+        error,
+        RuntimeErrorKind::IndexOutOfBounds {
+            index: 5,
+            length: 3
+        }
+    ));
+}
+
+#[test]
+fn test_strict_mode_reaches_lambda_bodies() {
+    let error = runtime_error("get = \\o. o.missing\n---\nmap([{ a: 1 }], get)");
+
+    assert!(matches!(error, RuntimeErrorKind::MissingKey { .. }));
+}
+
+#[test]
+fn test_strict_mode_keeps_optional_access_lenient() {
+    let result =
+        eval_strict(r#"{ a: 1 }?.b ?? [1]?[4] ?? "default""#).expect("should successfully eval");
+
+    assert_eq!(result, JmlValue::string("default"));
+}
+
+#[test]
+fn test_lenient_mode_is_default() {
+    let source = "[{ a: 1 }.b, [1][4]]";
+    let jml = parser::parse(source).expect("should successfully parse");
+
+    let result = eval::eval_with_source(jml, source).expect("should successfully eval");
+
+    assert_eq!(
+        result,
+        JmlValue::list(vec![JmlValue::null(), JmlValue::null()])
+    );
+}
@@ -0,0 +1,183 @@
+use chrono::DateTime;
+use eval::{
+    context::Context,
+    errors::{EvalError, RuntimeErrorKind},
+    stdlib::time::bind_now,
+    value::JmlValue,
+};
+
+mod common;
+
+use common::Fixture;
+
+const DATA: Fixture = Fixture::new(
+    r#"shipped = "2024-01-31T22:30:00+02:00"
+---
+"#,
+);
+
+fn assert_invalid_argument(body: &str, argument: &str) {
+    let Err(EvalError::RuntimeError(error)) = DATA.eval(body) else {
+        panic!("expected a runtime error for {}", body);
+    };
+    let RuntimeErrorKind::InvalidArgument { name, .. } = error.kind else {
+        panic!("expected an invalid argument error for {}", body);
+    };
+    assert_eq!(name, argument, "{}", body);
+}
+
+#[test]
+fn test_parse_time() {
+    DATA.assert_evals_same(
+        r#"[
+            parse_time("2024-03-01T12:30:00.250+02:00", null),
+            parse_time("2024-03-01T12:30:00", null),
+            parse_time("2024-03-01", null),
+            parse_time("01/03/2024 12:30", "%d/%m/%Y %H:%M"),
+            parse_time("1 Mar 2024 +0530", "%d %b %Y %z")
+        ]"#,
+        r#"[
+            "2024-03-01T12:30:00.250+02:00",
+            "2024-03-01T12:30:00Z",
+            "2024-03-01T00:00:00Z",
+            "2024-03-01T12:30:00Z",
+            "2024-03-01T00:00:00+05:30"
+        ]"#,
+    );
+    assert_invalid_argument(r#"parse_time("yesterday", null)"#, "text");
+}
+
+#[test]
+fn test_format_time() {
+    DATA.assert_evals_same(
+        r#"[format_time(shipped, "%Y-%m-%d %H:%M %:z"), format_time(0, "%A")]"#,
+        r#"["2024-01-31 22:30 +02:00", "Thursday"]"#,
+    );
+    assert_invalid_argument(r#"format_time(shipped, "%Q")"#, "format");
+}
+
+#[test]
+fn test_epoch_conversions() {
+    DATA.assert_evals_same(
+        r#"[
+            to_epoch(shipped),
+            to_epoch_millis("1970-01-01T00:00:01.5Z"),
+            from_epoch(1706733000),
+            from_epoch_millis(-1500),
+            to_epoch(from_epoch(42))
+        ]"#,
+        r#"[1706733000, 1500, "2024-01-31T20:30:00Z", "1969-12-31T23:59:58.500Z", 42]"#,
+    );
+}
+
+#[test]
+fn test_add_and_sub_time() {
+    DATA.assert_evals_same(
+        r#"[
+            add_time(shipped, { hours: 2, minutes: 15 }),
+            add_time(shipped, { months: 1 }),
+            add_time(shipped, { years: 1, days: -1 }),
+            sub_time(shipped, { weeks: 1, milliseconds: 1 }),
+            sub_time(add_time(shipped, { seconds: 90 }), { seconds: 90 })
+        ]"#,
+        r#"[
+            "2024-02-01T00:45:00+02:00",
+            "2024-02-29T22:30:00+02:00",
+            "2025-01-30T22:30:00+02:00",
+            "2024-01-24T22:29:59.999+02:00",
+            shipped
+        ]"#,
+    );
+    assert_invalid_argument(r#"add_time(shipped, { fortnights: 1 })"#, "duration");
+}
+
+#[test]
+fn test_truncate_time() {
+    DATA.assert_evals_same(
+        r#"map(["year", "month", "day", "hour", "minute", "second"], \unit. truncate_time("2024-03-15T10:20:30.5-04:00", unit))"#,
+        r#"[
+            "2024-01-01T00:00:00-04:00",
+            "2024-03-01T00:00:00-04:00",
+            "2024-03-15T00:00:00-04:00",
+            "2024-03-15T10:00:00-04:00",
+            "2024-03-15T10:20:00-04:00",
+            "2024-03-15T10:20:30-04:00"
+        ]"#,
+    );
+    assert_invalid_argument(r#"truncate_time(shipped, "decade")"#, "unit");
+}
+
+#[test]
+fn test_times_at_the_edge_of_the_range() {
+    for (body, argument) in [
+        (
+            r#"truncate_time("-262143-01-01T05:00:00+05:00", "day")"#,
+            "time",
+        ),
+        (
+            r#"parse_time("-262143-01-01 00:00 +0500", "%Y-%m-%d %H:%M %z")"#,
+            "text",
+        ),
+    ] {
+        let Err(EvalError::RuntimeError(error)) = DATA.eval(body) else {
+            panic!("expected a runtime error for {}", body);
+        };
+        let RuntimeErrorKind::InvalidArgument { name, reason } = error.kind else {
+            panic!("expected an invalid argument error for {}", body);
+        };
+        assert_eq!(name, argument, "{}", body);
+        assert!(reason.contains("out of the supported range"), "{}", body);
+    }
+}
+
+#[test]
+fn test_time_diff_and_compare() {
+    DATA.assert_evals_same(
+        r#"[
+            time_diff(shipped, "2024-02-01T00:00:00Z", "hours"),
+            time_diff("2024-02-01T00:00:00Z", shipped, "minutes"),
+            time_diff(shipped, add_time(shipped, { days: 10 }), "weeks"),
+            compare_time(shipped, "2024-01-31T20:30:00Z"),
+            compare_time(shipped, "2024-01-31T21:00:00Z"),
+            compare_time("2024-01-31T21:00:00Z", shipped)
+        ]"#,
+        "[3, -210, 1, 0, -1, 1]",
+    );
+    assert_invalid_argument(r#"time_diff(shipped, shipped, "months")"#, "unit");
+}
+
+#[test]
+fn test_offsets() {
+    DATA.assert_evals_same(
+        r#"[
+            to_offset(shipped, "Z"),
+            to_offset(shipped, "+05:30"),
+            time_offset(shipped),
+            time_offset(to_offset(shipped, "-03:00"))
+        ]"#,
+        r#"[
+            "2024-01-31T20:30:00Z",
+            "2024-02-01T02:00:00+05:30",
+            "+02:00",
+            "-03:00"
+        ]"#,
+    );
+    assert_invalid_argument(r#"to_offset(shipped, "CET")"#, "offset");
+    assert_invalid_argument(r#"to_epoch("not a time")"#, "time");
+}
+
+#[test]
+fn test_now_is_bound_by_the_host() {
+    assert!(DATA.eval("now").is_err());
+
+    let mut ctx = Context::new();
+    bind_now(
+        &mut ctx,
+        DateTime::parse_from_rfc3339("2024-06-01T08:00:00+01:00").unwrap(),
+    );
+    assert_eq!(
+        DATA.eval_with_ctx(r#"time_diff(shipped, now, "days")"#, ctx)
+            .unwrap(),
+        JmlValue::int(121)
+    );
+}
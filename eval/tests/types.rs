@@ -0,0 +1,120 @@
+use eval::errors::{EvalError, RuntimeErrorKind, TypeErrorKind};
+
+mod common;
+
+use common::Fixture;
+
+const DATA: Fixture = Fixture::new(
+    r#"values = [null, true, 1, 1.5, "a", [1], { a: 1 }, \x. x]
+---
+"#,
+);
+
+fn assert_type_error(body: &str) {
+    let Err(EvalError::TypeError(error)) = DATA.eval(body) else {
+        panic!("expected a type error for {}", body);
+    };
+    assert!(
+        matches!(error.kind, TypeErrorKind::MismatchedTypes { .. }),
+        "{}",
+        body
+    );
+}
+
+fn assert_invalid_argument(body: &str) {
+    let Err(EvalError::RuntimeError(error)) = DATA.eval(body) else {
+        panic!("expected a runtime error for {}", body);
+    };
+    assert!(
+        matches!(error.kind, RuntimeErrorKind::InvalidArgument { .. }),
+        "{}",
+        body
+    );
+}
+
+#[test]
+fn test_type_of() {
+    DATA.assert_evals_same(
+        "map(values, type_of)",
+        r#"["Null", "Bool", "Int", "Float", "String", "List", "Object", "Lambda"]"#,
+    );
+}
+
+#[test]
+fn test_type_predicates() {
+    DATA.assert_evals_same(
+        "map([is_null, is_bool, is_int, is_float, is_number, is_string, is_list, is_object, is_lambda], \\is. len(filter(values, is)))",
+        "[1, 1, 1, 1, 2, 1, 1, 1, 1]",
+    );
+    DATA.assert_evals_same("filter(values, is_number)", "[1, 1.5]");
+}
+
+#[test]
+fn test_to_bool() {
+    DATA.assert_evals_same(
+        r#"[to_bool(null), to_bool(false), to_bool(0), to_bool(-2), to_bool(0.0), to_bool(0.1), to_bool("true"), to_bool("false")]"#,
+        "[false, false, false, true, false, true, true, false]",
+    );
+    assert_invalid_argument(r#"to_bool("yes")"#);
+    assert_type_error("to_bool([])");
+}
+
+#[test]
+fn test_to_number() {
+    DATA.assert_evals_same(
+        r#"[to_number(2), to_number(2.5), to_number(true), to_number(false), to_number("7"), to_number("7.0")]"#,
+        "[2, 2.5, 1, 0, 7, 7.0]",
+    );
+    assert_invalid_argument(r#"to_number("7 apples")"#);
+    assert_type_error("to_number(null)");
+}
+
+#[test]
+fn test_to_string() {
+    DATA.assert_evals_same(
+        "map(take(values, 7), to_string)",
+        r#"["null", "true", "1", "1.5", "a", "[1]", "{" ++ to_json("a") ++ ":1}"]"#,
+    );
+    assert_type_error(r#"to_string(\x. x)"#);
+}
+
+#[test]
+fn test_json_round_trip() {
+    DATA.assert_evals_same(
+        "map(take(values, 7), \\v. parse_json(to_json(v)))",
+        "take(values, 7)",
+    );
+    DATA.assert_evals_same(
+        r#"[to_json("a"), parse_json(to_json("a")), parse_json("[1, 2.0, {}]")]"#,
+        r#"[parse_json(to_json(to_json("a"))), "a", [1, 2.0, {}]]"#,
+    );
+    DATA.assert_evals_same(
+        r#"keys(parse_json(to_json({ b: 1, a: 2 })))"#,
+        r#"["b", "a"]"#,
+    );
+
+    assert_invalid_argument(r#"parse_json("{ a: 1 }")"#);
+    for (body, place) in [
+        (r#"to_json(\x. x)"#, "the value is one"),
+        (r#"to_json([1, \x. x])"#, "found one at [1]"),
+        (r#"to_json({ a: [{ f: \x. x }] })"#, "found one at .a[0].f"),
+    ] {
+        let Err(EvalError::RuntimeError(error)) = DATA.eval(body) else {
+            panic!("expected a runtime error for {}", body);
+        };
+        let RuntimeErrorKind::InvalidArgument { reason, .. } = error.kind else {
+            panic!("expected an invalid argument error for {}", body);
+        };
+        assert_eq!(
+            reason,
+            format!("lambdas can't be serialized to JSON, {}", place),
+            "{}",
+            body
+        );
+    }
+
+    let Err(EvalError::RuntimeError(error)) = DATA.eval("to_json([1e308 * 10])") else {
+        panic!("expected a runtime error");
+    };
+    assert!(matches!(error.kind, RuntimeErrorKind::NotFinite { .. }));
+}
@@ -23,3 +23,37 @@ fn test_logical_not() {
 
     assert_eq!(result, JmlValue::bool(false));
 }
+
+#[test]
+fn test_negative_literal_extremes() {
+    let source = "[-9223372036854775808, -0x8000000000000000, - 1_000, -1.5e3]";
+
+    let jml = parser::parse(source).expect("should successfully parse");
+
+    let result = eval::eval_with_source(jml, source).expect("should successfully eval");
+
+    assert_eq!(
+        result,
+        JmlValue::list(vec![
+            JmlValue::int(i64::MIN),
+            JmlValue::int(i64::MIN),
+            JmlValue::int(-1000),
+            JmlValue::float(-1500.0),
+        ])
+    );
+}
+
+#[test]
+fn test_negating_min_int_overflows() {
+    let source = "-(-9223372036854775808)";
+
+    let jml = parser::parse(source).expect("should successfully parse");
+
+    assert!(eval::eval_with_source(jml, source).is_err());
+}
+
+#[test]
+fn test_positive_literal_out_of_range() {
+    assert!(parser::parse("9223372036854775808").is_err());
+    assert!(parser::parse("1 - 9223372036854775808").is_err());
+}
@@ -0,0 +1,114 @@
+/// Layout document in the style of Wadler's "prettier printer".
+///
+/// A `Group` is printed on a single line if it fits in the remaining width,
+/// otherwise every `Line` and `SoftLine` directly inside it becomes a newline.
+#[derive(Debug, Clone)]
+pub(crate) enum Doc {
+    Nil,
+    Text(String),
+    /// A space when flat, a newline when broken.
+    Line,
+    /// Nothing when flat, a newline when broken.
+    SoftLine,
+    /// Always a newline; forces every enclosing group to break.
+    HardLine,
+    /// Prints nothing but forces every enclosing group to break.
+    BreakParent,
+    Nest(Box<Doc>),
+    Group(Box<Doc>),
+    Concat(Vec<Doc>),
+}
+
+pub(crate) fn text(s: impl Into<String>) -> Doc {
+    Doc::Text(s.into())
+}
+
+pub(crate) fn concat(docs: impl IntoIterator<Item = Doc>) -> Doc {
+    Doc::Concat(docs.into_iter().collect())
+}
+
+pub(crate) fn nest(doc: Doc) -> Doc {
+    Doc::Nest(Box::new(doc))
+}
+
+pub(crate) fn group(doc: Doc) -> Doc {
+    Doc::Group(Box::new(doc))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+pub(crate) fn render(doc: &Doc, width: usize, indent_width: usize) -> String {
+    let mut out = String::new();
+    let mut column = 0;
+    let mut stack = vec![(0, Mode::Break, doc)];
+
+    while let Some((indent, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Nil | Doc::BreakParent => {}
+            Doc::Text(s) => {
+                out.push_str(s);
+                column += s.chars().count();
+            }
+            Doc::Line if mode == Mode::Flat => {
+                out.push(' ');
+                column += 1;
+            }
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                out.truncate(out.trim_end_matches(' ').len());
+                out.push('\n');
+                out.push_str(&" ".repeat(indent));
+                column = indent;
+            }
+            Doc::Nest(doc) => stack.push((indent + indent_width, mode, doc)),
+            Doc::Group(doc) => {
+                let remaining = width as isize - column as isize;
+                let mode = if mode == Mode::Flat || fits(remaining, (indent, doc), &stack) {
+                    Mode::Flat
+                } else {
+                    Mode::Break
+                };
+                stack.push((indent, mode, doc));
+            }
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+        }
+    }
+
+    out
+}
+
+/// Checks whether `doc` printed flat, followed by the rest of the current
+/// line, fits into `remaining` columns.
+fn fits(mut remaining: isize, doc: (usize, &Doc), rest: &[(usize, Mode, &Doc)]) -> bool {
+    let mut stack = vec![(doc.0, Mode::Flat, doc.1)];
+    let mut rest = rest.iter().rev();
+
+    loop {
+        if remaining < 0 {
+            return false;
+        }
+
+        let (indent, mode, doc) = match stack.pop() {
+            Some(item) => item,
+            None => match rest.next() {
+                Some(item) => *item,
+                None => return true,
+            },
+        };
+
+        match doc {
+            Doc::Nil => {}
+            Doc::Text(s) => remaining -= s.chars().count() as isize,
+            Doc::Line | Doc::SoftLine if mode == Mode::Break => return true,
+            Doc::Line => remaining -= 1,
+            Doc::SoftLine => {}
+            Doc::HardLine | Doc::BreakParent => return mode == Mode::Break,
+            Doc::Nest(doc) | Doc::Group(doc) => stack.push((indent, mode, doc)),
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+        }
+    }
+}
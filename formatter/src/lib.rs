@@ -0,0 +1,593 @@
+use doc::{concat, group, nest, render, text, Doc};
+use lexer::{token::Token, Lexeme, Lexer};
+use parser::{
+    ast::{
+        BinaryOp, ComprehensionClause, Expression, ExpressionKind, Jml, Key, ListElement,
+        ObjectMember, StatementKind, UnaryOp,
+    },
+    errors::{ParseErrors, SyntaxError},
+};
+
+mod doc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Maximum line width objects, lists and calls are laid out against.
+    pub line_width: usize,
+    /// Number of spaces per indentation level.
+    pub indent_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            line_width: 80,
+            indent_width: 4,
+        }
+    }
+}
+
+/// Formats a JML script into its canonical layout, preserving comments.
+pub fn format(source: &str, options: FormatOptions) -> Result<String, ParseErrors> {
+    let jml = parser::parse(source)?;
+
+    let mut comments = vec![];
+    let mut header_position = None;
+    for lexeme in Lexer::with_trivia(source) {
+        let lexeme =
+            lexeme.map_err(|error| ParseErrors::new(source, vec![SyntaxError::Lexing(error)]))?;
+        match lexeme {
+            (start, Lexeme::Comment(comment), end) => comments.push(Comment {
+                start,
+                end,
+                text: comment.trim_end(),
+                own_line: source[..start]
+                    .rsplit('\n')
+                    .next()
+                    .is_some_and(|line| line.trim().is_empty()),
+            }),
+            (start, Lexeme::Token(Token::Header), _) => header_position = Some(start),
+            _ => {}
+        }
+    }
+
+    let mut printer = Printer {
+        source,
+        comments,
+        next_comment: 0,
+        last_end: 0,
+    };
+    let doc = printer.jml(&jml, header_position.unwrap_or(jml.body.l));
+
+    let mut formatted = render(&doc, options.line_width, options.indent_width);
+    formatted.push('\n');
+    Ok(formatted)
+}
+
+/// Checks whether `source` is already in canonical layout.
+pub fn is_formatted(source: &str, options: FormatOptions) -> Result<bool, ParseErrors> {
+    Ok(format(source, options)? == source)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Comment<'source> {
+    start: usize,
+    end: usize,
+    text: &'source str,
+    /// Whether the comment is the first thing on its line.
+    own_line: bool,
+}
+
+struct Printer<'source> {
+    source: &'source str,
+    comments: Vec<Comment<'source>>,
+    next_comment: usize,
+    /// End of the last statement or comment, used to detect blank lines.
+    last_end: usize,
+}
+
+// Binding strength of each syntactic form, mirroring the `ExpressionPrecedence`
+// levels of the grammar. A child printed in a slot that only accepts a lower
+// level has to be parenthesised.
+const PRIMARY: u8 = 0;
+const POSTFIX: u8 = 2;
+const UNARY: u8 = 3;
+const IF: u8 = 12;
+const ANY: u8 = 13;
+
+fn level(expr: &ExpressionKind) -> u8 {
+    match expr {
+        // The parser folds `-1` into the literal, which still prints as a unary minus.
+        ExpressionKind::Int(value) if *value < 0 => UNARY,
+        ExpressionKind::Float(value) if *value < 0.0 => UNARY,
+        ExpressionKind::Null
+        | ExpressionKind::Float(_)
+        | ExpressionKind::Bool(_)
+        | ExpressionKind::Int(_)
+        | ExpressionKind::String(_)
+        | ExpressionKind::Object(_)
+        | ExpressionKind::List(_)
+        | ExpressionKind::ListComprehension { .. }
+        | ExpressionKind::ObjectComprehension { .. } => PRIMARY,
+        ExpressionKind::Variable(_) | ExpressionKind::Apply { .. } => 1,
+        ExpressionKind::IndexAccess { .. }
+        | ExpressionKind::Slice { .. }
+        | ExpressionKind::Selector { .. } => POSTFIX,
+        ExpressionKind::UnaryOp { .. } => UNARY,
+        ExpressionKind::BinaryOp { op, .. } => binary_level(*op),
+        ExpressionKind::IfExpr { .. } => IF,
+        ExpressionKind::Lambda { .. } => ANY,
+    }
+}
+
+fn binary_level(op: BinaryOp) -> u8 {
+    match op {
+        BinaryOp::Pow => 4,
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => 5,
+        BinaryOp::Concat => 6,
+        BinaryOp::Sum | BinaryOp::Sub => 7,
+        BinaryOp::Coalesce => 8,
+        BinaryOp::EQ | BinaryOp::NE | BinaryOp::GT | BinaryOp::LT | BinaryOp::GE | BinaryOp::LE => {
+            9
+        }
+        BinaryOp::And => 10,
+        BinaryOp::Or => 11,
+    }
+}
+
+fn binary_symbol(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::EQ => "==",
+        BinaryOp::NE => "!=",
+        BinaryOp::GT => ">",
+        BinaryOp::LT => "<",
+        BinaryOp::GE => ">=",
+        BinaryOp::LE => "<=",
+        BinaryOp::Sum => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "/",
+        BinaryOp::Pow => "^",
+        BinaryOp::Mod => "%",
+        BinaryOp::And => "&&",
+        BinaryOp::Or => "||",
+        BinaryOp::Concat => "++",
+        BinaryOp::Coalesce => "??",
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "null", "fn", "if", "then", "else", "for", "in", "true", "false", "String", "Float", "Bool",
+    "Int", "Array", "Object", "Null",
+];
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !KEYWORDS.contains(&s)
+}
+
+impl<'source> Printer<'source> {
+    fn jml(&mut self, jml: &Jml<'source>, header_position: usize) -> Doc {
+        let mut docs = vec![];
+
+        for (i, stmt) in jml.header.iter().enumerate() {
+            if i > 0 {
+                docs.push(Doc::HardLine);
+            }
+            docs.push(self.leading(stmt.l, i > 0));
+            docs.push(self.blank_line_before(stmt.l, i > 0));
+
+            let StatementKind::Bind {
+                identifier,
+                expression,
+            } = &stmt.node;
+            docs.push(text(format!("{} = ", identifier.node)));
+            docs.push(self.expr(expression, ANY));
+
+            self.last_end = stmt.r;
+            docs.push(self.trailing(jml.header.get(i + 1).map_or(header_position, |s| s.l)));
+        }
+
+        if !jml.header.is_empty() {
+            docs.push(Doc::HardLine);
+            docs.push(self.leading(header_position, false));
+            docs.push(text("---"));
+            self.last_end = header_position + 3;
+            docs.push(self.trailing(jml.body.l));
+            docs.push(Doc::HardLine);
+        }
+
+        docs.push(self.leading(jml.body.l, false));
+        docs.push(self.expr(&jml.body, ANY));
+        self.last_end = jml.body.r;
+        docs.push(self.trailing(self.source.len()));
+        docs.push(self.tail(self.source.len(), true));
+
+        concat(docs)
+    }
+
+    fn blank_line_before(&self, position: usize, allowed: bool) -> Doc {
+        let gap = self.source.get(self.last_end..position).unwrap_or("");
+        if allowed && gap.trim().is_empty() && gap.matches('\n').count() > 1 {
+            Doc::HardLine
+        } else {
+            Doc::Nil
+        }
+    }
+
+    /// Takes a comment that trails the previous code on the same line.
+    fn trailing(&mut self, before: usize) -> Doc {
+        match self.comments.get(self.next_comment) {
+            Some(comment) if comment.start < before && !comment.own_line => {
+                self.next_comment += 1;
+                self.last_end = comment.end;
+                concat([text(" "), text(comment.text), Doc::BreakParent])
+            }
+            _ => Doc::Nil,
+        }
+    }
+
+    /// Takes the comments before `before`, each printed on its own line
+    /// followed by a newline.
+    fn leading(&mut self, before: usize, keep_blank_lines: bool) -> Doc {
+        let mut docs = vec![];
+
+        while let Some(comment) = self.comments.get(self.next_comment).copied() {
+            if comment.start >= before {
+                break;
+            }
+            docs.push(self.blank_line_before(comment.start, keep_blank_lines));
+            docs.push(text(comment.text));
+            docs.push(Doc::HardLine);
+            self.next_comment += 1;
+            self.last_end = comment.end;
+        }
+
+        concat(docs)
+    }
+
+    /// Takes the comments before `before`, each printed on a new line.
+    fn tail(&mut self, before: usize, keep_blank_lines: bool) -> Doc {
+        let mut docs = vec![];
+
+        while let Some(comment) = self.comments.get(self.next_comment).copied() {
+            if comment.start >= before {
+                break;
+            }
+            docs.push(Doc::HardLine);
+            docs.push(self.blank_line_before(comment.start, keep_blank_lines));
+            docs.push(text(comment.text));
+            self.next_comment += 1;
+            self.last_end = comment.end;
+        }
+
+        concat(docs)
+    }
+
+    fn expr(&mut self, expr: &Expression<'source>, max_level: u8) -> Doc {
+        let doc = self.expr_inner(expr);
+        if level(&expr.node) > max_level {
+            concat([text("("), doc, text(")")])
+        } else {
+            doc
+        }
+    }
+
+    fn expr_inner(&mut self, expr: &Expression<'source>) -> Doc {
+        match &expr.node {
+            ExpressionKind::Null => text("null"),
+            ExpressionKind::Bool(b) => text(b.to_string()),
+            ExpressionKind::Int(_) | ExpressionKind::Float(_) => {
+                let literal = self.source[expr.l..expr.r].trim();
+                match literal.strip_prefix('-') {
+                    Some(digits) => text(format!("-{}", digits.trim_start())),
+                    None => text(literal),
+                }
+            }
+            ExpressionKind::String(s) => text(format!("\"{}\"", s)),
+            ExpressionKind::Variable(name) => text(*name),
+            ExpressionKind::Object(entries) => self.sequence(
+                "{",
+                "}",
+                true,
+                entries,
+                expr.r,
+                |member| match member {
+                    ObjectMember::Entry {
+                        key: Key::Ident(ident),
+                        ..
+                    } => ident.l,
+                    ObjectMember::Entry {
+                        key: Key::Expression(key),
+                        ..
+                    } => key.l,
+                    ObjectMember::Spread(object) => object.l,
+                },
+                |printer, member| match member {
+                    ObjectMember::Entry {
+                        key,
+                        value,
+                        condition,
+                    } => {
+                        let key = match key {
+                            Key::Ident(ident) => text(ident.node),
+                            Key::Expression(key) => printer.expr(key, PRIMARY),
+                        };
+                        let value = printer.expr(value, ANY);
+                        let condition = match condition {
+                            Some(condition) => concat([text(" if "), printer.expr(condition, ANY)]),
+                            None => Doc::Nil,
+                        };
+                        concat([key, text(": "), value, condition])
+                    }
+                    ObjectMember::Spread(object) => {
+                        concat([text("..."), printer.expr(object, ANY)])
+                    }
+                },
+            ),
+            ExpressionKind::List(elems) => self.sequence(
+                "[",
+                "]",
+                false,
+                elems,
+                expr.r,
+                |elem| match elem {
+                    ListElement::Item(item) => item.l,
+                    ListElement::Spread(list) => list.l,
+                },
+                |printer, elem| match elem {
+                    ListElement::Item(item) => printer.expr(item, ANY),
+                    ListElement::Spread(list) => concat([text("..."), printer.expr(list, ANY)]),
+                },
+            ),
+            ExpressionKind::ListComprehension { element, clauses } => {
+                let element = self.expr(element, ANY);
+                self.comprehension("[", "]", Doc::SoftLine, element, clauses)
+            }
+            ExpressionKind::ObjectComprehension {
+                key,
+                value,
+                clauses,
+            } => {
+                let key = match &**key {
+                    Key::Ident(ident) => text(ident.node),
+                    // A bare name would read back as a literal key.
+                    Key::Expression(key) if matches!(key.node, ExpressionKind::Variable(_)) => {
+                        self.expr(key, PRIMARY)
+                    }
+                    Key::Expression(key) => self.expr(key, POSTFIX),
+                };
+                let entry = concat([key, text(": "), self.expr(value, ANY)]);
+                self.comprehension("{", "}", Doc::Line, entry, clauses)
+            }
+            ExpressionKind::IndexAccess {
+                target,
+                index,
+                optional,
+            } => concat([
+                self.expr(target, POSTFIX),
+                text(if *optional { "?[" } else { "[" }),
+                self.expr(index, ANY),
+                text("]"),
+            ]),
+            ExpressionKind::Slice {
+                target,
+                start,
+                end,
+                step,
+                optional,
+            } => {
+                let target = self.expr(target, POSTFIX);
+                let mut bound = |bound: &Option<Box<Expression<'source>>>| match bound {
+                    Some(bound) => self.expr(bound, ANY),
+                    None => text(""),
+                };
+                let start = bound(start);
+                let end = bound(end);
+                let step = step.is_some().then(|| concat([text(":"), bound(step)]));
+
+                concat([
+                    target,
+                    text(if *optional { "?[" } else { "[" }),
+                    start,
+                    text(":"),
+                    end,
+                    step.unwrap_or_else(|| text("")),
+                    text("]"),
+                ])
+            }
+            ExpressionKind::Selector {
+                target,
+                key,
+                optional,
+            } => {
+                let target = self.expr(target, POSTFIX);
+                let key = if is_identifier(key) {
+                    text(*key)
+                } else {
+                    text(format!("\"{}\"", key))
+                };
+                concat([target, text(if *optional { "?." } else { "." }), key])
+            }
+            ExpressionKind::UnaryOp { op, expr: operand } => {
+                let symbol = match op {
+                    UnaryOp::Minus => "-",
+                    UnaryOp::Not => "!",
+                };
+                // `- -x` must not collapse into `--x`, which starts a `---` header, and
+                // `-(-1)` must not become `--1`.
+                let max_level = match (op, &operand.node) {
+                    (
+                        UnaryOp::Minus,
+                        ExpressionKind::UnaryOp {
+                            op: UnaryOp::Minus, ..
+                        },
+                    ) => PRIMARY,
+                    (UnaryOp::Minus, ExpressionKind::Int(_) | ExpressionKind::Float(_))
+                        if level(&operand.node) == UNARY =>
+                    {
+                        PRIMARY
+                    }
+                    _ => UNARY,
+                };
+                concat([text(symbol), self.expr(operand, max_level)])
+            }
+            ExpressionKind::BinaryOp { op, lhs, rhs } => {
+                let level = binary_level(*op);
+                let (lhs_level, rhs_level) = match op {
+                    BinaryOp::Pow => (UNARY, level),
+                    _ => (level, level - 1),
+                };
+                concat([
+                    self.expr(lhs, lhs_level),
+                    text(format!(" {} ", binary_symbol(*op))),
+                    self.expr(rhs, rhs_level),
+                ])
+            }
+            ExpressionKind::IfExpr {
+                condition,
+                then_branch,
+                else_branch,
+            } => group(self.if_chain(condition, then_branch, else_branch)),
+            ExpressionKind::Lambda { params, body } => {
+                let params: Vec<&str> = params.iter().map(|p| p.node).collect();
+                let head = if params.is_empty() {
+                    "\\. ".to_owned()
+                } else {
+                    format!("\\{}. ", params.join(" "))
+                };
+                concat([text(head), self.expr(body, IF)])
+            }
+            ExpressionKind::Apply { lambda, args } => {
+                let function = self.expr(lambda, 1);
+                let args = self.sequence(
+                    "(",
+                    ")",
+                    false,
+                    args,
+                    expr.r,
+                    |arg| arg.l,
+                    |printer, arg| printer.expr(arg, ANY),
+                );
+                concat([function, args])
+            }
+        }
+    }
+
+    /// Prints an `if` and its `else if` continuations as one group, so the
+    /// whole chain is either on one line or has one branch per line.
+    fn if_chain(
+        &mut self,
+        condition: &Expression<'source>,
+        then_branch: &Expression<'source>,
+        else_branch: &Expression<'source>,
+    ) -> Doc {
+        let condition = self.expr(condition, IF);
+        let then_branch = self.expr(then_branch, IF);
+        let else_branch = match &else_branch.node {
+            ExpressionKind::IfExpr {
+                condition,
+                then_branch,
+                else_branch,
+            } => concat([
+                text(" "),
+                self.if_chain(condition, then_branch, else_branch),
+            ]),
+            _ => nest(concat([Doc::Line, self.expr(else_branch, IF)])),
+        };
+
+        concat([
+            text("if "),
+            condition,
+            text(" then"),
+            nest(concat([Doc::Line, then_branch])),
+            Doc::Line,
+            text("else"),
+            else_branch,
+        ])
+    }
+
+    /// Prints a comprehension on one line, or with one clause per line.
+    fn comprehension(
+        &mut self,
+        open: &str,
+        close: &str,
+        edge: Doc,
+        produced: Doc,
+        clauses: &[ComprehensionClause<'source>],
+    ) -> Doc {
+        let mut inner = vec![edge.clone(), produced];
+        for clause in clauses {
+            inner.push(Doc::Line);
+            inner.push(match clause {
+                ComprehensionClause::For { binding, iterable } => concat([
+                    text(format!("for {} in ", binding.node)),
+                    self.expr(iterable, ANY),
+                ]),
+                ComprehensionClause::If(condition) => {
+                    concat([text("if "), self.expr(condition, ANY)])
+                }
+            });
+        }
+
+        group(concat([text(open), nest(concat(inner)), edge, text(close)]))
+    }
+
+    /// Prints a bracketed, comma separated sequence, keeping the comments
+    /// found between its elements.
+    #[allow(clippy::too_many_arguments)]
+    fn sequence<T>(
+        &mut self,
+        open: &str,
+        close: &str,
+        padded: bool,
+        items: &[T],
+        end: usize,
+        start_of: impl Fn(&T) -> usize,
+        mut item: impl FnMut(&mut Self, &T) -> Doc,
+    ) -> Doc {
+        let edge = if padded { Doc::Line } else { Doc::SoftLine };
+        let mut inner = vec![];
+
+        let head = self.trailing(items.first().map_or(end, &start_of));
+
+        for (i, elem) in items.iter().enumerate() {
+            if i > 0 {
+                inner.push(text(","));
+                inner.push(self.trailing(start_of(elem)));
+                inner.push(Doc::Line);
+            } else {
+                inner.push(edge.clone());
+            }
+            inner.push(self.leading(start_of(elem), false));
+            inner.push(item(self, elem));
+        }
+
+        inner.push(self.trailing(end));
+        let tail = self.tail(end, false);
+        let has_tail = !matches!(&tail, Doc::Concat(docs) if docs.is_empty());
+        inner.push(tail);
+
+        if items.is_empty() && !has_tail && matches!(head, Doc::Nil) {
+            return text(format!("{}{}", open, close));
+        }
+
+        let closing = if items.is_empty() {
+            Doc::SoftLine
+        } else {
+            edge
+        };
+
+        group(concat([
+            text(open),
+            head,
+            nest(concat(inner)),
+            closing,
+            text(close),
+        ]))
+    }
+}
@@ -0,0 +1,159 @@
+use formatter::{format, is_formatted, FormatOptions};
+
+fn fmt(source: &str) -> String {
+    format(source, FormatOptions::default()).expect("should successfully format")
+}
+
+#[test]
+fn test_examples_are_formatted() {
+    for entry in std::fs::read_dir("../examples").unwrap() {
+        let path = entry.unwrap().path();
+        let source = std::fs::read_to_string(&path).unwrap();
+
+        assert!(
+            is_formatted(&source, FormatOptions::default()).unwrap(),
+            "{} is not formatted",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn test_format_header_and_body() {
+    let source = "a=1\n\n\n\nb =   [1,2 ,3]\n---\n{key:a,\"other\": b}";
+
+    assert_eq!(
+        fmt(source),
+        "a = 1\n\nb = [1, 2, 3]\n---\n{ key: a, \"other\": b }\n"
+    );
+}
+
+#[test]
+fn test_format_breaks_long_collections() {
+    let source = r#"{ "first": "aaaaaaaaaaaaaaaaaaaa", "second": ["bbbbbbbbbbbbbbbbbbbb", "cccccccccccccccccccc"] }"#;
+
+    assert_eq!(
+        fmt(source),
+        r#"{
+    "first": "aaaaaaaaaaaaaaaaaaaa",
+    "second": ["bbbbbbbbbbbbbbbbbbbb", "cccccccccccccccccccc"]
+}
+"#
+    );
+}
+
+#[test]
+fn test_format_if_chain() {
+    let source = "if condition_number_one then \"first result\" else if condition_number_two then \"second\" else \"third\"";
+
+    assert_eq!(
+        fmt(source),
+        r#"if condition_number_one then
+    "first result"
+else if condition_number_two then
+    "second"
+else
+    "third"
+"#
+    );
+}
+
+#[test]
+fn test_format_preserves_comments() {
+    let source = r#"
+# leading
+x = { // brace
+  a: 1, // one
+  // inside
+  b: 2
+}
+--- // header
+x # end
+"#;
+
+    assert_eq!(
+        fmt(source),
+        r#"# leading
+x = { // brace
+    a: 1, // one
+    // inside
+    b: 2
+}
+--- // header
+x # end
+"#
+    );
+}
+
+#[test]
+fn test_format_keeps_required_parentheses() {
+    let source = "(1 + 2) * 3 - (4 - 5) ++ (\\x. x)(a.b) ++ (f.g)(1) ++ (2 ^ 3) ^ 4 ++ - (- 1)";
+    let formatted = fmt(source);
+
+    assert_eq!(
+        formatted,
+        "(1 + 2) * 3 - (4 - 5) ++ (\\x. x)(a.b) ++ (f.g)(1) ++ (2 ^ 3) ^ 4 ++ -(-1)\n"
+    );
+    assert_eq!(fmt(&formatted), formatted);
+}
+
+#[test]
+fn test_format_negative_literals() {
+    let source = "[- 1, -2.5, (-3)[0], - (-4), -0x10, 1_000]";
+    let formatted = fmt(source);
+
+    assert_eq!(formatted, "[-1, -2.5, (-3)[0], -(-4), -0x10, 1_000]\n");
+    assert_eq!(fmt(&formatted), formatted);
+}
+
+#[test]
+fn test_format_optional_chaining() {
+    let source = "(a ?? b)?.c ?[ 0 ]??d == (e ?? f) ?? g";
+    let formatted = fmt(source);
+
+    assert_eq!(formatted, "(a ?? b)?.c?[0] ?? d == e ?? f ?? g\n");
+    assert_eq!(fmt(&formatted), formatted);
+}
+
+#[test]
+fn test_format_slices() {
+    let source = "[xs[1 : 3], xs[ : -1], xs[::2], s?[n:], xs[:], (a + b)[1:2:-1]]";
+    let formatted = fmt(source);
+
+    assert_eq!(
+        formatted,
+        "[xs[1:3], xs[:-1], xs[::2], s?[n:], xs[:], (a + b)[1:2:-1]]\n"
+    );
+    assert_eq!(fmt(&formatted), formatted);
+}
+
+#[test]
+fn test_format_spreads_and_conditional_members() {
+    let source = "x = {...base,  debug : y if  verbose, (\"k\" ++ n): 1}\n---\n[... a, 1,...b]";
+    let formatted = fmt(source);
+
+    assert_eq!(
+        formatted,
+        "x = { ...base, debug: y if verbose, (\"k\" ++ n): 1 }\n---\n[...a, 1, ...b]\n"
+    );
+    assert_eq!(fmt(&formatted), formatted);
+}
+
+#[test]
+fn test_format_comprehensions() {
+    let source =
+        "xs = []\n---\n[ {u.id:u for u in xs}, [ x for x in xs if x>1 ], {(k): 1 for k in xs}]";
+    let formatted = fmt(source);
+
+    assert_eq!(
+        formatted,
+        "xs = []\n---\n[{ u.id: u for u in xs }, [x for x in xs if x > 1], { (k): 1 for k in xs }]\n"
+    );
+    assert_eq!(fmt(&formatted), formatted);
+
+    let long = fmt("data = []\n---\n[item.some_long_name ++ item.another_long_name for item in data if item.is_enabled]");
+    assert_eq!(
+        long,
+        "data = []\n---\n[\n    item.some_long_name ++ item.another_long_name\n    for item in data\n    if item.is_enabled\n]\n"
+    );
+}
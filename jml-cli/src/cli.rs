@@ -1,6 +1,9 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, FixedOffset, Local};
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::format::Format;
 
 #[derive(Parser)]
 #[command(
@@ -20,8 +23,9 @@ pub struct JmlCli {
     #[command(subcommand)]
     pub command: JmlCommand,
 
-    /// Enable or disable logging
-    #[arg(short, long, help = "Turn logging on or off")]
+    /// Also log the CLI's own progress. What scripts log with `log` is
+    /// always written to stderr.
+    #[arg(short, long, help = "Log the CLI's progress to stderr")]
     pub log: bool,
 }
 
@@ -33,20 +37,133 @@ pub enum JmlCommand {
         #[arg(short, long, help = "Input JML file to process.")]
         file: PathBuf,
 
-        /// Optional path to write the output as JSON.
+        /// Optional path to write the output to.
         ///
         /// If provided, the result of evaluating the JML file will be written
-        /// to this output file in JSON format.
-        #[arg(short, long, help = "Output file for JSON result.")]
+        /// to this output file, in the format given by `--output-format` or
+        /// detected from its extension.
+        #[arg(short, long, help = "Output file for the result.")]
         output: Option<PathBuf>,
 
-        /// Provide multiple variables and their corresponding JSON paths (URL or file).
+        /// Provide multiple variables and their corresponding data paths (URL or file).
         ///
         /// You can specify multiple variables by repeating the `--variable` flag,
-        /// each specifying a variable name and its corresponding JSON value path.
-        #[arg(short, long, value_parser = parse_variable, help = "Variable names and JSON files or URLs")]
+        /// each specifying a variable name and its corresponding value path.
+        /// The format of each file is detected from its extension.
+        #[arg(short, long, value_parser = parse_variable, help = "Variable names and data files or URLs")]
+        variables: Vec<(String, String)>,
+
+        /// Format of every variable file, overriding extension detection.
+        #[arg(long, value_enum, help = "Input format for variables.")]
+        input_format: Option<Format>,
+
+        /// Format of the result. Defaults to the output file extension, or JSON.
+        #[arg(
+            long,
+            value_enum,
+            conflicts_with = "stream",
+            help = "Output format for the result."
+        )]
+        output_format: Option<Format>,
+
+        /// Treat the input as newline-delimited JSON.
+        ///
+        /// The script is parsed once and its body is evaluated for every input
+        /// line, with the parsed line bound to `--line-var`. One JSON line is
+        /// written per input line.
+        #[arg(
+            long,
+            visible_alias = "ndjson",
+            help = "Evaluate the body once per NDJSON input line."
+        )]
+        stream: bool,
+
+        /// Path to the NDJSON input file. Reads from standard input if omitted.
+        #[arg(
+            short,
+            long,
+            requires = "stream",
+            help = "NDJSON input file for --stream."
+        )]
+        input: Option<PathBuf>,
+
+        /// Name of the variable each input line is bound to in stream mode.
+        #[arg(
+            long,
+            default_value = "line",
+            requires = "stream",
+            help = "Variable bound to the current NDJSON line."
+        )]
+        line_var: String,
+
+        /// What to do when a line fails to parse or evaluate in stream mode.
+        #[arg(long, value_enum, default_value_t = OnError::Abort, requires = "stream", help = "Skip or abort on per-line errors.")]
+        on_error: OnError,
+
+        /// Fail on missing object keys and out-of-range indices instead of
+        /// yielding `null`. `?.` and `?[...]` stay lenient.
+        #[arg(long, help = "Make missing keys and out-of-range indices errors.")]
+        strict: bool,
+
+        /// Bind `now` to the given RFC 3339 time, or to the current time when
+        /// no time is given. Without the flag `now` is unbound, so scripts
+        /// only depend on the clock when asked to.
+        #[arg(long, value_name = "TIME", num_args = 0..=1, default_missing_value = CURRENT_TIME, value_parser = parse_now, help = "Bind `now` to TIME, or to the current time.")]
+        now: Option<DateTime<FixedOffset>>,
+    },
+
+    /// Start an interactive session that keeps bindings between entries.
+    Repl {
+        /// Provide multiple variables and their corresponding data paths (URL or file).
+        #[arg(short, long, value_parser = parse_variable, help = "Variable names and data files or URLs")]
         variables: Vec<(String, String)>,
+
+        /// Format of every variable file, overriding extension detection.
+        #[arg(long, value_enum, help = "Input format for variables.")]
+        input_format: Option<Format>,
+
+        /// Fail on missing object keys and out-of-range indices instead of
+        /// yielding `null`.
+        #[arg(long, help = "Make missing keys and out-of-range indices errors.")]
+        strict: bool,
+
+        /// Bind `now` to the given RFC 3339 time, or to the current time when
+        /// no time is given, as `run --now` does.
+        #[arg(long, value_name = "TIME", num_args = 0..=1, default_missing_value = CURRENT_TIME, value_parser = parse_now, help = "Bind `now` to TIME, or to the current time.")]
+        now: Option<DateTime<FixedOffset>>,
     },
+
+    /// Format JML source files into their canonical layout.
+    ///
+    /// Files are rewritten in place. Without files, the source is read from
+    /// standard input and the formatted result is written to standard output.
+    Fmt {
+        /// JML source files to format.
+        files: Vec<PathBuf>,
+
+        /// Only check the files and fail if any of them isn't formatted.
+        #[arg(long, help = "Fail instead of rewriting if a file isn't formatted.")]
+        check: bool,
+
+        /// Maximum line width before objects, lists and calls are broken up.
+        #[arg(long, default_value_t = 80, help = "Maximum line width.")]
+        line_width: usize,
+    },
+
+    /// Start a language server speaking LSP over standard input and output.
+    ///
+    /// Provides diagnostics, hover, go-to-definition, completion and document
+    /// symbols for `.jml` files.
+    Lsp,
+}
+
+/// Strategy for handling a failing line in stream mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OnError {
+    /// Stop at the first failing line and report it.
+    Abort,
+    /// Report the failing line on stderr and continue with the next one.
+    Skip,
 }
 
 fn parse_variable(s: &str) -> Result<(String, String), String> {
@@ -60,3 +177,15 @@ fn parse_variable(s: &str) -> Result<(String, String), String> {
         ))
     }
 }
+
+/// What a bare `--now` stands for.
+const CURRENT_TIME: &str = "current";
+
+fn parse_now(s: &str) -> Result<DateTime<FixedOffset>, String> {
+    if s == CURRENT_TIME {
+        return Ok(Local::now().fixed_offset());
+    }
+
+    DateTime::parse_from_rfc3339(s)
+        .map_err(|e| format!("Invalid time: '{}' ({}). Expected an RFC 3339 time.", s, e))
+}
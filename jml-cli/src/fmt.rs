@@ -0,0 +1,67 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+use formatter::FormatOptions;
+use miette::{miette, IntoDiagnostic, WrapErr};
+
+/// Formats `files` in place, or standard input to standard output if no
+/// files are given. With `check`, nothing is written and an error is
+/// returned if any input isn't formatted.
+pub fn run(files: Vec<PathBuf>, check: bool, options: FormatOptions) -> miette::Result<()> {
+    if files.is_empty() {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source).into_diagnostic()?;
+
+        let formatted = format_source(&source, options).wrap_err("failed to format <stdin>")?;
+
+        if check {
+            if formatted != source {
+                return Err(miette!("<stdin> is not formatted"));
+            }
+        } else {
+            print!("{}", formatted);
+        }
+        return Ok(());
+    }
+
+    let mut unformatted = 0;
+
+    for path in &files {
+        let source = fs::read_to_string(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+
+        let formatted = format_source(&source, options)
+            .wrap_err_with(|| format!("failed to format {}", path.display()))?;
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            eprintln!("Would reformat: {}", path.display());
+            unformatted += 1;
+        } else {
+            tracing::info!("Formatting {:?}", path);
+            fs::write(path, formatted).into_diagnostic()?;
+        }
+    }
+
+    if unformatted > 0 {
+        return Err(miette!(
+            help = "Run `jml-cli fmt` without --check to format them.",
+            "{} of {} files are not formatted",
+            unformatted,
+            files.len()
+        ));
+    }
+
+    Ok(())
+}
+
+fn format_source(source: &str, options: FormatOptions) -> miette::Result<String> {
+    Ok(formatter::format(source, options)?)
+}
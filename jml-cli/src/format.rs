@@ -0,0 +1,362 @@
+use std::{fmt, path::Path};
+
+use clap::ValueEnum;
+use eval::{jml_type::JmlType, value::JmlValue};
+use indexmap::IndexMap;
+use miette::{Diagnostic, IntoDiagnostic, WrapErr};
+use thiserror::Error;
+
+/// Data formats the CLI can read variables from and write results to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+    Csv,
+}
+
+impl Format {
+    /// Detects the format from a file name or URL extension.
+    pub fn from_path(path: impl AsRef<str>) -> Option<Format> {
+        let path = path.as_ref();
+        let path = path.split(['?', '#']).next().unwrap_or(path);
+        let extension = Path::new(path).extension()?.to_str()?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(Format::Json),
+            "yaml" | "yml" => Some(Format::Yaml),
+            "toml" => Some(Format::Toml),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Format::Json => "JSON",
+            Format::Yaml => "YAML",
+            Format::Toml => "TOML",
+            Format::Csv => "CSV",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Error, Diagnostic, Debug)]
+pub enum FormatError {
+    #[error("{found} at `{path}` cannot be represented in {format}")]
+    #[diagnostic(code(format::unrepresentable), help("{help}"))]
+    Unrepresentable {
+        format: Format,
+        path: String,
+        found: JmlType,
+        help: String,
+    },
+}
+
+impl FormatError {
+    fn unrepresentable(
+        format: Format,
+        path: &ValuePath,
+        found: JmlType,
+        help: impl Into<String>,
+    ) -> Self {
+        FormatError::Unrepresentable {
+            format,
+            path: path.to_string(),
+            found,
+            help: help.into(),
+        }
+    }
+}
+
+/// Parses `data` in the given format into a value that can be bound to a variable.
+pub fn parse(data: &str, format: Format) -> miette::Result<JmlValue<'static>> {
+    match format {
+        Format::Json => {
+            let json: serde_json::Value = serde_json::from_str(data).into_diagnostic()?;
+            Ok(json.into())
+        }
+        Format::Yaml => {
+            let json: serde_json::Value = serde_yaml::from_str(data).into_diagnostic()?;
+            Ok(json.into())
+        }
+        Format::Toml => {
+            let table: toml::Table = toml::from_str(data).into_diagnostic()?;
+            Ok(from_toml(toml::Value::Table(table)))
+        }
+        Format::Csv => from_csv(data),
+    }
+}
+
+/// Renders `value` in the given format.
+pub fn render(value: &JmlValue, format: Format) -> miette::Result<String> {
+    match format {
+        Format::Json => {
+            check_representable(value, format, &mut ValuePath::default())?;
+            serde_json::to_string_pretty(value).into_diagnostic()
+        }
+        Format::Yaml => {
+            check_representable(value, format, &mut ValuePath::default())?;
+            serde_yaml::to_string(value).into_diagnostic()
+        }
+        Format::Toml => {
+            let toml = to_toml(value, &mut ValuePath::default())?;
+            match toml {
+                toml::Value::Table(table) => toml::to_string_pretty(&table).into_diagnostic(),
+                _ => Err(FormatError::unrepresentable(
+                    format,
+                    &ValuePath::default(),
+                    value.type_of(),
+                    "A TOML document must be an object at the top level.",
+                ))?,
+            }
+        }
+        Format::Csv => to_csv(value),
+    }
+}
+
+/// Location of a nested value, rendered as `$.key[0]` in diagnostics.
+#[derive(Debug, Default, Clone)]
+struct ValuePath(Vec<PathSegment>);
+
+#[derive(Debug, Clone)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl ValuePath {
+    fn with<T>(&mut self, segment: PathSegment, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.0.push(segment);
+        let result = f(self);
+        self.0.pop();
+        result
+    }
+}
+
+impl fmt::Display for ValuePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "$")?;
+        for segment in &self.0 {
+            match segment {
+                PathSegment::Key(key) => write!(f, ".{}", key)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_representable(
+    value: &JmlValue,
+    format: Format,
+    path: &mut ValuePath,
+) -> Result<(), FormatError> {
+    match value {
+        JmlValue::Lambda(_) => Err(FormatError::unrepresentable(
+            format,
+            path,
+            value.type_of(),
+            "Functions only exist during evaluation; apply them before returning the result.",
+        )),
+        JmlValue::List(list) => list.iter().enumerate().try_for_each(|(i, elem)| {
+            path.with(PathSegment::Index(i), |path| {
+                check_representable(elem, format, path)
+            })
+        }),
+        JmlValue::Object(object) => object.iter().try_for_each(|(key, elem)| {
+            path.with(PathSegment::Key(key.clone()), |path| {
+                check_representable(elem, format, path)
+            })
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn from_toml(value: toml::Value) -> JmlValue<'static> {
+    match value {
+        toml::Value::String(s) => JmlValue::string(s),
+        toml::Value::Integer(i) => JmlValue::int(i),
+        toml::Value::Float(f) => JmlValue::float(f),
+        toml::Value::Boolean(b) => JmlValue::bool(b),
+        toml::Value::Datetime(dt) => JmlValue::string(dt.to_string()),
+        toml::Value::Array(arr) => {
+            JmlValue::list(arr.into_iter().map(from_toml).collect::<Vec<_>>())
+        }
+        toml::Value::Table(table) => JmlValue::object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, from_toml(v)))
+                .collect::<IndexMap<_, _>>(),
+        ),
+    }
+}
+
+fn to_toml(value: &JmlValue, path: &mut ValuePath) -> Result<toml::Value, FormatError> {
+    match value {
+        JmlValue::Null => Err(FormatError::unrepresentable(
+            Format::Toml,
+            path,
+            value.type_of(),
+            "TOML has no null; drop the key or replace null with a default value.",
+        )),
+        JmlValue::Bool(b) => Ok(toml::Value::Boolean(b.is_truthy())),
+        JmlValue::Int(i) => Ok(toml::Value::Integer(i.value())),
+        JmlValue::Float(f) => Ok(toml::Value::Float(f.value())),
+        JmlValue::String(s) => Ok(toml::Value::String(s.as_str().to_owned())),
+        JmlValue::List(list) => list
+            .iter()
+            .enumerate()
+            .map(|(i, elem)| path.with(PathSegment::Index(i), |path| to_toml(elem, path)))
+            .collect::<Result<Vec<_>, _>>()
+            .map(toml::Value::Array),
+        JmlValue::Object(object) => object
+            .iter()
+            .map(|(key, elem)| {
+                path.with(PathSegment::Key(key.clone()), |path| {
+                    to_toml(elem, path).map(|v| (key.clone(), v))
+                })
+            })
+            .collect::<Result<toml::Table, _>>()
+            .map(toml::Value::Table),
+        JmlValue::Lambda(_) => Err(FormatError::unrepresentable(
+            Format::Toml,
+            path,
+            value.type_of(),
+            "Functions only exist during evaluation; apply them before returning the result.",
+        )),
+    }
+}
+
+/// Reads a CSV document with a header row into a list of objects.
+///
+/// Empty fields become `null`; fields that parse as `Int`, `Float` or `Bool`
+/// take that type, everything else stays a `String`.
+fn from_csv(data: &str) -> miette::Result<JmlValue<'static>> {
+    let mut reader = csv::Reader::from_reader(data.as_bytes());
+    let headers = reader.headers().into_diagnostic()?.clone();
+
+    let mut rows = vec![];
+    for (i, record) in reader.records().enumerate() {
+        let record = record
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to read CSV row {}", i + 1))?;
+
+        let row: IndexMap<String, JmlValue> = headers
+            .iter()
+            .zip(record.iter())
+            .map(|(header, field)| (header.to_owned(), csv_field(field)))
+            .collect();
+        rows.push(JmlValue::object(row));
+    }
+
+    Ok(JmlValue::list(rows))
+}
+
+fn csv_field(field: &str) -> JmlValue<'static> {
+    if field.is_empty() {
+        JmlValue::null()
+    } else if let Ok(i) = field.parse::<i64>() {
+        JmlValue::int(i)
+    } else if let Some(f) = csv_float(field) {
+        JmlValue::float(f)
+    } else if let Ok(b) = field.parse::<bool>() {
+        JmlValue::bool(b)
+    } else {
+        JmlValue::string(field)
+    }
+}
+
+/// A float written with digits, a sign, a point and an exponent only, so that
+/// text like `nan` or `Infinity` stays a string.
+fn csv_float(field: &str) -> Option<f64> {
+    if !field
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E'))
+    {
+        return None;
+    }
+
+    field.parse::<f64>().ok().filter(|f| f.is_finite())
+}
+
+/// Writes a list of flat objects as CSV; the header is the union of all keys
+/// in order of first appearance.
+fn to_csv(value: &JmlValue) -> miette::Result<String> {
+    let mut path = ValuePath::default();
+
+    let JmlValue::List(rows) = value else {
+        Err(FormatError::unrepresentable(
+            Format::Csv,
+            &path,
+            value.type_of(),
+            "CSV output must be a list of objects, one object per row.",
+        ))?
+    };
+
+    let mut objects = vec![];
+    for (i, row) in rows.iter().enumerate() {
+        match row {
+            JmlValue::Object(object) => objects.push(object),
+            _ => path.with(PathSegment::Index(i), |path| {
+                Err(FormatError::unrepresentable(
+                    Format::Csv,
+                    path,
+                    row.type_of(),
+                    "Every CSV row must be an object.",
+                ))
+            })?,
+        }
+    }
+
+    let mut headers: IndexMap<&str, ()> = IndexMap::new();
+    for object in &objects {
+        for key in object.keys() {
+            headers.insert(key, ());
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(headers.keys()).into_diagnostic()?;
+
+    for (i, object) in objects.iter().enumerate() {
+        let record = path.with(PathSegment::Index(i), |path| {
+            headers
+                .keys()
+                .map(|header| {
+                    let field = object.get(header).unwrap_or(&JmlValue::Null);
+                    path.with(PathSegment::Key(header.to_string()), |path| {
+                        csv_cell(field, path)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+        writer.write_record(record).into_diagnostic()?;
+    }
+
+    let bytes = writer.into_inner().into_diagnostic()?;
+    String::from_utf8(bytes).into_diagnostic()
+}
+
+fn csv_cell(value: &JmlValue, path: &ValuePath) -> Result<String, FormatError> {
+    match value {
+        JmlValue::Null => Ok(String::new()),
+        JmlValue::String(s) => Ok(s.as_str().to_owned()),
+        JmlValue::Bool(_) | JmlValue::Int(_) => Ok(value.to_string()),
+        // JSON has no NaN or infinity, so write those as text instead of `null`.
+        JmlValue::Float(float) if !float.value().is_finite() => Ok(value.to_string()),
+        // Keep the fraction of whole floats so that they read back as floats.
+        JmlValue::Float(_) => Ok(serde_json::to_string(value).expect("floats serialize to JSON")),
+        JmlValue::List(_) | JmlValue::Object(_) | JmlValue::Lambda(_) => {
+            Err(FormatError::unrepresentable(
+                Format::Csv,
+                path,
+                value.type_of(),
+                "CSV cells must be scalars; flatten nested values or encode them as strings.",
+            ))
+        }
+    }
+}
@@ -1,3 +1,7 @@
 pub mod cli;
+pub mod fmt;
+pub mod format;
 pub mod log;
+pub mod repl;
+pub mod stream;
 pub mod util;
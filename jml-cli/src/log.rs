@@ -1,8 +1,21 @@
+use std::io::{self, IsTerminal};
+
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-pub fn setup_logging() {
+/// Writes log lines to stderr, so they never mix with a result printed to
+/// stdout. Scripts' own `log` calls are always shown, and `verbose` adds the
+/// CLI's progress messages. `RUST_LOG` overrides both, for example
+/// `RUST_LOG=jml::script=trace` to see `debug` and `trace` calls too.
+pub fn setup_logging(verbose: bool) {
+    let default = if verbose { "info" } else { "jml::script=info" };
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default));
+
     tracing_subscriber::registry()
-        .with(fmt::layer())
-        .with(EnvFilter::new("info"))
+        .with(
+            fmt::layer()
+                .with_writer(io::stderr)
+                .with_ansi(io::stderr().is_terminal()),
+        )
+        .with(filter)
         .init()
 }
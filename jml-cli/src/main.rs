@@ -1,19 +1,26 @@
 use std::fs::{self};
 
+use jml_cli::format::{self, Format};
+
 use clap::Parser;
-use eval::context::Context;
+use eval::{context::Context, stdlib::time::bind_now};
+use formatter::FormatOptions;
 use jml_cli::{
     cli::{self, JmlCli},
+    fmt,
     log::setup_logging,
-    util::{load_json, write_output_to_json},
+    repl::{self, Repl},
+    stream::run_stream,
+    util::{load_value, open_input, open_output, write_output},
 };
-use miette::IntoDiagnostic;
+use miette::{miette, IntoDiagnostic};
 
 fn main() -> miette::Result<()> {
     let cli = JmlCli::parse();
 
-    if cli.log {
-        setup_logging();
+    // The language server reports problems itself and has no use for script logs.
+    if !matches!(cli.command, cli::JmlCommand::Lsp) {
+        setup_logging(cli.log);
     }
 
     match cli.command {
@@ -21,34 +28,115 @@ fn main() -> miette::Result<()> {
             file,
             output,
             variables,
+            input_format,
+            output_format,
+            stream,
+            input,
+            line_var,
+            on_error,
+            strict,
+            now,
         } => {
             let source = fs::read_to_string(&file).into_diagnostic()?.leak();
 
             tracing::info!("Processing file: {:?}\n", file);
 
             let mut ctx = Context::new();
+            ctx.set_strict(strict);
+            ctx.set_file_name(file.display().to_string());
+            if let Some(now) = now {
+                bind_now(&mut ctx, now);
+            }
 
             for (var_name, var_path) in variables {
                 tracing::info!("Loading variable '{}' from '{}'\n", var_name, var_path);
 
-                let json_data = load_json(&var_path)?;
-                ctx.bind_with_value(var_name.clone(), json_data.clone());
+                let data = load_value(&var_path, input_format)?;
+                ctx.bind_with_value(var_name.clone(), data);
 
-                tracing::info!("Loaded JSON data for '{}'\n", var_name);
+                tracing::info!("Loaded data for '{}'\n", var_name);
+            }
+
+            let ast = parser::parse(source)?;
+
+            if stream {
+                tracing::info!(
+                    "Streaming NDJSON input, binding each line to '{}'\n",
+                    line_var
+                );
+
+                let stats = run_stream(
+                    ast,
+                    source,
+                    &mut ctx,
+                    &line_var,
+                    open_input(input)?,
+                    open_output(output)?,
+                    on_error,
+                )?;
+
+                tracing::info!(
+                    "Processed {} lines, skipped {}\n",
+                    stats.processed,
+                    stats.skipped
+                );
+                return Ok(());
             }
 
-            let ast = parser::parse(source).into_diagnostic()?;
             let res = eval::eval_with_ctx_source(ast, source, &mut ctx)?;
 
+            let output_format = output_format
+                .or_else(|| {
+                    output
+                        .as_ref()
+                        .and_then(|path| Format::from_path(path.to_string_lossy()))
+                })
+                .unwrap_or(Format::Json);
+
             if let Some(output_path) = output {
                 tracing::info!("Output will be written to: {:?}", output_path);
-                write_output_to_json(output_path, &res)?;
+                write_output(output_path, &res, output_format)?;
             } else {
                 tracing::info!("No output file specified. Printing to console. \n");
-                let json = serde_json::to_string_pretty(&res).into_diagnostic()?;
-                println!("{}", json);
+                let rendered = format::render(&res, output_format)?;
+                println!("{}", rendered.trim_end());
             }
         }
+        cli::JmlCommand::Repl {
+            variables,
+            input_format,
+            strict,
+            now,
+        } => {
+            let mut repl = Repl::new();
+            repl.set_strict(strict);
+            if let Some(now) = now {
+                repl.bind_now(now);
+            }
+
+            for (var_name, var_path) in variables {
+                tracing::info!("Loading variable '{}' from '{}'\n", var_name, var_path);
+
+                let data = load_value(&var_path, input_format)?;
+                repl.bind_value(var_name, data);
+            }
+
+            repl::run(repl)?;
+        }
+        cli::JmlCommand::Fmt {
+            files,
+            check,
+            line_width,
+        } => {
+            let options = FormatOptions {
+                line_width,
+                ..FormatOptions::default()
+            };
+            fmt::run(files, check, options)?;
+        }
+        cli::JmlCommand::Lsp => {
+            lsp::run().map_err(|e| miette!("{:#}", e))?;
+        }
     }
 
     Ok(())
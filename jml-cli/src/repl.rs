@@ -0,0 +1,328 @@
+use std::{env, fs, path::PathBuf, rc::Rc};
+
+use chrono::{DateTime, FixedOffset};
+use eval::{
+    context::{Binding, Context},
+    errors::EvalError,
+    stdlib::{
+        define_std_lib,
+        time::{bind_now, NOW},
+    },
+    value::JmlValue,
+};
+use lexer::{token::Token, Lexer};
+use miette::{
+    miette, IntoDiagnostic, MietteError, MietteSpanContents, SourceCode, SourceSpan, SpanContents,
+    WrapErr,
+};
+use parser::ast::{Expression, ExpressionKind, Statement, StatementKind};
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+const HELP: &str = "\
+Enter a binding (`x = 1 + 2`) or an expression to evaluate it.
+
+Commands:
+  :type <expr>   Show the type of an expression
+  :load <file>   Bind the header of a .jml file and evaluate its body
+  :vars          List bound variables and their types
+  :help          Show this message
+  :quit          Exit the REPL";
+
+/// Interactive session keeping one `Context` alive across entries.
+pub struct Repl {
+    ctx: Context<'static>,
+    sources: Sources,
+    names: Vec<String>,
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        let mut ctx = Context::new();
+        define_std_lib(&mut ctx);
+
+        Repl {
+            ctx,
+            sources: Sources::default(),
+            names: vec![],
+        }
+    }
+
+    /// Turns strict key and index access on or off, as `run --strict` does.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.ctx.set_strict(strict);
+    }
+
+    /// Binds an already loaded value, as `run -v` does.
+    pub fn bind_value(&mut self, name: impl Into<String>, value: JmlValue<'static>) {
+        let name = name.into();
+        self.ctx.bind_with_value(name.clone(), value);
+        self.remember(name);
+    }
+
+    /// Binds `now` to the given time, as `run --now` does.
+    pub fn bind_now(&mut self, now: DateTime<FixedOffset>) {
+        bind_now(&mut self.ctx, now);
+        self.remember(NOW.to_owned());
+    }
+
+    /// Handles a single line of input and returns the text to print, if any.
+    pub fn eval_line(&mut self, line: &str) -> miette::Result<Option<String>> {
+        let line = line.trim();
+
+        if line.is_empty() {
+            return Ok(None);
+        }
+
+        if let Some(command) = line.strip_prefix(':') {
+            return self.eval_command(command);
+        }
+
+        let (offset, source) = self.keep(line.to_owned());
+
+        if is_binding(source) {
+            let stmt = parser::parse_statement_at(source, offset)?;
+            self.bind_stmt(stmt)?;
+            Ok(None)
+        } else {
+            let value = self.eval_source(source, offset)?;
+            Ok(Some(show(&value)))
+        }
+    }
+
+    fn eval_command(&mut self, command: &str) -> miette::Result<Option<String>> {
+        let (name, arg) = command
+            .split_once(char::is_whitespace)
+            .map(|(name, arg)| (name, arg.trim()))
+            .unwrap_or((command, ""));
+
+        match name {
+            "type" | "t" => {
+                let (offset, source) = self.keep(arg.to_owned());
+                let value = self.eval_source(source, offset)?;
+                Ok(Some(value.type_of().to_string()))
+            }
+            "load" | "l" => self.load(arg),
+            "vars" | "v" => Ok(Some(self.vars())),
+            "help" | "h" | "?" => Ok(Some(HELP.to_owned())),
+            _ => Err(miette!(
+                help = "Type :help to list the available commands.",
+                "Unknown command ':{}'",
+                name
+            )),
+        }
+    }
+
+    /// Keeps the text of an entry for the rest of the session and returns the
+    /// offset its spans start at.
+    fn keep(&mut self, source: String) -> (usize, &'static str) {
+        let (offset, source) = self.sources.keep(source);
+        self.ctx.add_source(offset, source);
+        (offset, source)
+    }
+
+    fn eval_source(
+        &mut self,
+        source: &'static str,
+        offset: usize,
+    ) -> miette::Result<JmlValue<'static>> {
+        let expr = parser::parse_expression_at(source, offset)?;
+        self.eval_expr(expr)
+    }
+
+    fn eval_expr(&mut self, expr: Expression<'static>) -> miette::Result<JmlValue<'static>> {
+        eval::expr::eval_expr(expr, &mut self.ctx).map_err(|e| self.report(e))
+    }
+
+    /// The error shown against every entry, since a lambda from an earlier
+    /// entry can fail in a later one.
+    fn report(&self, error: EvalError) -> miette::Report {
+        miette::Report::from(error).with_source_code(self.sources.clone())
+    }
+
+    /// Evaluates the right-hand side once and binds its value, so that later
+    /// entries can't change it and `x = x + 1` refers to the previous `x`.
+    fn bind_stmt(&mut self, stmt: Statement<'static>) -> miette::Result<()> {
+        let StatementKind::Bind {
+            identifier,
+            expression,
+        } = stmt.node;
+
+        let value = self.eval_expr(expression)?;
+        self.bind_value(identifier.node, value);
+
+        Ok(())
+    }
+
+    fn load(&mut self, path: &str) -> miette::Result<Option<String>> {
+        if path.is_empty() {
+            return Err(miette!("Usage: :load <file.jml>"));
+        }
+
+        let source = fs::read_to_string(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to read '{}'", path))?;
+        let (offset, source) = self.keep(source);
+
+        let jml = parser::parse_at(source, offset)?;
+
+        // The header is bound lazily in its own scope, as `run` does, so its
+        // bindings may refer to each other in any order. Their values are
+        // then bound in the session.
+        let mut header = Context::new_with_parent(Rc::new(self.ctx.clone()));
+        let mut names = vec![];
+        for stmt in jml.header {
+            let StatementKind::Bind {
+                identifier,
+                expression,
+            } = stmt.node;
+            header.bind_with_expr(identifier.node, expression);
+            names.push(identifier);
+        }
+
+        for name in names {
+            let value = eval::expr::eval_expr(
+                Expression {
+                    l: name.l,
+                    r: name.r,
+                    node: ExpressionKind::Variable(name.node),
+                },
+                &mut header,
+            )
+            .map_err(|e| self.report(e))?;
+            self.bind_value(name.node, value);
+        }
+
+        let value = self.eval_expr(jml.body)?;
+        Ok(Some(show(&value)))
+    }
+
+    fn vars(&self) -> String {
+        let mut lines = vec![];
+
+        for name in &self.names {
+            let ty = match self.ctx.lookup_variable(name) {
+                Ok(Binding::Value(value)) => value.type_of().to_string(),
+                _ => "<error>".to_owned(),
+            };
+            lines.push(format!("{} : {}", name, ty));
+        }
+
+        lines.join("\n")
+    }
+
+    fn remember(&mut self, name: String) {
+        self.names.retain(|n| *n != name);
+        self.names.push(name);
+    }
+}
+
+/// The text of every entry, placed one after another as if the session were
+/// a single source, so that a span tells which entry it points into.
+#[derive(Debug, Default, Clone)]
+struct Sources(Vec<(usize, &'static str)>);
+
+impl Sources {
+    /// Entries are leaked, as bindings may borrow from them until the session
+    /// ends.
+    fn keep(&mut self, source: String) -> (usize, &'static str) {
+        // Leave a gap, so that no span can end in one entry and start the next.
+        let offset = self
+            .0
+            .last()
+            .map_or(0, |(offset, text)| offset + text.len() + 1);
+        let source: &'static str = source.leak();
+        self.0.push((offset, source));
+        (offset, source)
+    }
+}
+
+impl SourceCode for Sources {
+    fn read_span<'a>(
+        &'a self,
+        span: &SourceSpan,
+        context_lines_before: usize,
+        context_lines_after: usize,
+    ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+        let (offset, source) = self
+            .0
+            .iter()
+            .rev()
+            .find(|(offset, _)| *offset <= span.offset())
+            .ok_or(MietteError::OutOfBounds)?;
+
+        let local = SourceSpan::new((span.offset() - offset).into(), span.len());
+        let contents = source.read_span(&local, context_lines_before, context_lines_after)?;
+        let found = contents.span();
+        Ok(Box::new(MietteSpanContents::new(
+            contents.data(),
+            SourceSpan::new((found.offset() + offset).into(), found.len()),
+            contents.line(),
+            contents.column(),
+            contents.line_count(),
+        )))
+    }
+}
+
+/// Runs the interactive loop until `:quit` or end of input.
+pub fn run(mut repl: Repl) -> miette::Result<()> {
+    let mut editor = DefaultEditor::new().into_diagnostic()?;
+    let history = history_path();
+
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+
+    println!("JML REPL. Type :help for commands, :quit to exit.");
+
+    loop {
+        match editor.readline("jml> ") {
+            Ok(line) => {
+                let _ = editor.add_history_entry(line.as_str());
+
+                if matches!(line.trim(), ":quit" | ":q") {
+                    break;
+                }
+
+                match repl.eval_line(&line) {
+                    Ok(Some(output)) => println!("{}", output),
+                    Ok(None) => {}
+                    Err(report) => eprintln!("{:?}", report),
+                }
+            }
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e).into_diagnostic(),
+        }
+    }
+
+    if let Some(history) = &history {
+        let _ = editor.save_history(history);
+    }
+
+    Ok(())
+}
+
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".jml_history"))
+}
+
+fn is_binding(source: &str) -> bool {
+    let mut tokens = Lexer::new(source);
+    matches!(
+        (tokens.next(), tokens.next()),
+        (
+            Some(Ok((_, Token::Identifier(_), _))),
+            Some(Ok((_, Token::Assign, _)))
+        )
+    )
+}
+
+fn show(value: &JmlValue) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
@@ -0,0 +1,90 @@
+use std::io::{BufRead, Write};
+
+use eval::{context::Context, value::JmlValue};
+use miette::{GraphicalReportHandler, IntoDiagnostic, WrapErr};
+use parser::ast::{Expression, Jml};
+use serde_json::Value;
+
+use crate::cli::OnError;
+
+/// Summary of a finished stream run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StreamStats {
+    pub processed: usize,
+    pub skipped: usize,
+}
+
+/// Evaluates the body of `jml` once for every non-blank line of `input`.
+///
+/// The header is bound into `ctx` a single time and reused for every line;
+/// each line is parsed as JSON and bound to `line_var` before evaluation.
+/// Results are written to `output` as compact JSON, one per line.
+pub fn run_stream<'source>(
+    jml: Jml<'source>,
+    source: &'static str,
+    ctx: &mut Context<'source>,
+    line_var: &str,
+    input: impl BufRead,
+    mut output: impl Write,
+    on_error: OnError,
+) -> miette::Result<StreamStats> {
+    eval::eval_header_with_ctx(jml.header, ctx).map_err(|e| e.with_source_code(source))?;
+
+    let mut stats = StreamStats::default();
+
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to read input line {}", line_number))?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match eval_line(&jml.body, source, ctx, line_var, &line, line_number) {
+            Ok(value) => {
+                serde_json::to_writer(&mut output, &value).into_diagnostic()?;
+                writeln!(output).into_diagnostic()?;
+                stats.processed += 1;
+            }
+            Err(report) => match on_error {
+                OnError::Abort => return Err(report),
+                OnError::Skip => {
+                    eprint!("{}", render_report(&report));
+                    stats.skipped += 1;
+                }
+            },
+        }
+    }
+
+    output.flush().into_diagnostic()?;
+
+    Ok(stats)
+}
+
+/// Renders a skipped line's error the way miette reports a fatal one.
+fn render_report(report: &miette::Report) -> String {
+    let mut rendered = String::new();
+    GraphicalReportHandler::new()
+        .render_report(&mut rendered, report.as_ref())
+        .map_or_else(|_| format!("{}\n", report), |()| rendered)
+}
+
+fn eval_line<'source>(
+    body: &Expression<'source>,
+    source: &'static str,
+    ctx: &mut Context<'source>,
+    line_var: &str,
+    line: &str,
+    line_number: usize,
+) -> miette::Result<JmlValue<'source>> {
+    let value: Value = serde_json::from_str(line)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("input line {} is not valid JSON", line_number))?;
+
+    ctx.bind_with_value(line_var, value);
+
+    eval::eval_expr_with_ctx_source(body.clone(), source, ctx)
+        .wrap_err_with(|| format!("failed to evaluate input line {}", line_number))
+}
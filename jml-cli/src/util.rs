@@ -1,33 +1,66 @@
 use std::{
     fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
     path::Path,
 };
 
-use miette::IntoDiagnostic;
+use eval::value::JmlValue;
+use miette::{IntoDiagnostic, WrapErr};
 use reqwest::blocking::get;
-use serde::Serialize;
-use serde_json::Value;
 
-pub fn write_output_to_json<T: Serialize>(
+use crate::format::{self, Format};
+
+pub fn write_output(
     output_path: impl AsRef<Path>,
-    value: &T,
+    value: &JmlValue,
+    format: Format,
 ) -> miette::Result<()> {
-    let file = File::create(output_path).into_diagnostic()?;
+    let rendered = format::render(value, format)?;
 
-    serde_json::to_writer_pretty(file, &value).into_diagnostic()?;
+    fs::write(output_path, rendered).into_diagnostic()?;
 
     Ok(())
 }
 
-pub fn load_json(path: impl AsRef<str>) -> miette::Result<Value> {
+/// Loads a variable from a file path or URL.
+///
+/// The format is taken from `format` if given, otherwise it is detected from
+/// the extension, falling back to JSON.
+pub fn load_value(
+    path: impl AsRef<str>,
+    format: Option<Format>,
+) -> miette::Result<JmlValue<'static>> {
     let path_ref = path.as_ref();
-    if path_ref.starts_with("http://") || path_ref.starts_with("https://") {
-        let response = get(path_ref).into_diagnostic()?.text().into_diagnostic()?;
-        let json: Value = serde_json::from_str(&response).into_diagnostic()?;
-        Ok(json)
+    let format = format
+        .or_else(|| Format::from_path(path_ref))
+        .unwrap_or(Format::Json);
+
+    let data = if path_ref.starts_with("http://") || path_ref.starts_with("https://") {
+        get(path_ref).into_diagnostic()?.text().into_diagnostic()?
     } else {
-        let data = fs::read_to_string(path_ref).into_diagnostic()?;
-        let json: Value = serde_json::from_str(&data).into_diagnostic()?;
-        Ok(json)
+        fs::read_to_string(path_ref).into_diagnostic()?
+    };
+
+    format::parse(&data, format)
+        .wrap_err_with(|| format!("failed to parse '{}' as {}", path_ref, format))
+}
+
+pub fn open_input(path: Option<impl AsRef<Path>>) -> miette::Result<Box<dyn BufRead>> {
+    match path {
+        Some(path) => {
+            let file = File::open(path).into_diagnostic()?;
+            Ok(Box::new(BufReader::new(file)))
+        }
+        None => Ok(Box::new(io::stdin().lock())),
+    }
+}
+
+pub fn open_output(path: Option<impl AsRef<Path>>) -> miette::Result<Box<dyn Write>> {
+    match path {
+        Some(path) => {
+            let file = File::create(path).into_diagnostic()?;
+            Ok(Box::new(BufWriter::new(file)))
+        }
+        None => Ok(Box::new(BufWriter::new(io::stdout().lock()))),
     }
 }
@@ -0,0 +1,35 @@
+use chrono::DateTime;
+use clap::Parser;
+use jml_cli::cli::{JmlCli, JmlCommand};
+
+fn now_of(args: &[&str]) -> Option<DateTime<chrono::FixedOffset>> {
+    let cli = JmlCli::try_parse_from(args).expect("should successfully parse the arguments");
+    match cli.command {
+        JmlCommand::Run { now, .. } | JmlCommand::Repl { now, .. } => now,
+        _ => panic!("expected `run` or `repl`"),
+    }
+}
+
+#[test]
+fn test_now_is_unbound_by_default() {
+    assert_eq!(now_of(&["jml", "run", "-f", "script.jml"]), None);
+    assert_eq!(now_of(&["jml", "repl"]), None);
+}
+
+#[test]
+fn test_now_flag() {
+    let fixed = DateTime::parse_from_rfc3339("2024-06-01T08:00:00+01:00").unwrap();
+    assert_eq!(
+        now_of(&[
+            "jml",
+            "run",
+            "-f",
+            "script.jml",
+            "--now",
+            "2024-06-01T08:00:00+01:00"
+        ]),
+        Some(fixed)
+    );
+    assert!(now_of(&["jml", "repl", "--now"]).is_some());
+    assert!(JmlCli::try_parse_from(["jml", "repl", "--now", "tomorrow"]).is_err());
+}
@@ -0,0 +1,115 @@
+use eval::value::JmlValue;
+use indexmap::indexmap;
+use jml_cli::format::{self, Format};
+
+#[test]
+fn test_format_from_path() {
+    assert_eq!(Format::from_path("app.yaml"), Some(Format::Yaml));
+    assert_eq!(Format::from_path("app.YML"), Some(Format::Yaml));
+    assert_eq!(Format::from_path("Cargo.toml"), Some(Format::Toml));
+    assert_eq!(Format::from_path("report.csv"), Some(Format::Csv));
+    assert_eq!(
+        Format::from_path("https://example.com/data.json?page=2"),
+        Some(Format::Json)
+    );
+    assert_eq!(Format::from_path("data"), None);
+}
+
+#[test]
+fn test_parse_csv_infers_scalars() {
+    let data = "id,name,score,active,note\n1,Ann,3.5,true,\n";
+
+    let value = format::parse(data, Format::Csv).expect("should successfully parse");
+
+    let expected = JmlValue::list(vec![JmlValue::object(indexmap! {
+        "id".to_string() => JmlValue::int(1),
+        "name".to_string() => JmlValue::string("Ann"),
+        "score".to_string() => JmlValue::float(3.5),
+        "active".to_string() => JmlValue::bool(true),
+        "note".to_string() => JmlValue::null(),
+    })]);
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn test_parse_csv_keeps_non_numeric_text() {
+    let data = "a,b,c,d\nnan,inf,Infinity,1e999\n";
+
+    let value = format::parse(data, Format::Csv).expect("should successfully parse");
+
+    let expected = JmlValue::list(vec![JmlValue::object(indexmap! {
+        "a".to_string() => JmlValue::string("nan"),
+        "b".to_string() => JmlValue::string("inf"),
+        "c".to_string() => JmlValue::string("Infinity"),
+        "d".to_string() => JmlValue::string("1e999"),
+    })]);
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn test_render_csv_writes_non_finite_floats_as_text() {
+    let value = JmlValue::list(vec![JmlValue::object(indexmap! {
+        "nan".to_string() => JmlValue::float(f64::NAN),
+        "inf".to_string() => JmlValue::float(f64::NEG_INFINITY),
+    })]);
+
+    let csv = format::render(&value, Format::Csv).expect("should successfully render");
+
+    assert_eq!(csv, "nan,inf\nNaN,-inf\n");
+}
+
+#[test]
+fn test_csv_round_trip_keeps_whole_floats() {
+    let value = JmlValue::list(vec![JmlValue::object(indexmap! {
+        "count".to_string() => JmlValue::int(1),
+        "ratio".to_string() => JmlValue::float(1.0),
+    })]);
+
+    let csv = format::render(&value, Format::Csv).expect("should successfully render");
+    let back = format::parse(&csv, Format::Csv).expect("should successfully parse");
+
+    assert_eq!(csv, "count,ratio\n1,1.0\n");
+    assert_eq!(value, back);
+}
+
+#[test]
+fn test_yaml_to_toml_round_trip() {
+    let data = "name: app\nports: [80, 443]\ndb:\n  host: localhost\n";
+
+    let value = format::parse(data, Format::Yaml).expect("should successfully parse");
+    let toml = format::render(&value, Format::Toml).expect("should successfully render");
+    let back = format::parse(&toml, Format::Toml).expect("should successfully parse");
+
+    assert_eq!(value, back);
+}
+
+#[test]
+fn test_render_toml_rejects_null() {
+    let value = JmlValue::object(indexmap! {
+        "db".to_string() => JmlValue::object(indexmap! {
+            "password".to_string() => JmlValue::null(),
+        }),
+    });
+
+    let error = format::render(&value, Format::Toml).expect_err("null is not valid TOML");
+
+    assert_eq!(
+        error.to_string(),
+        "Null at `$.db.password` cannot be represented in TOML"
+    );
+}
+
+#[test]
+fn test_render_csv_rejects_nested_values() {
+    let value = JmlValue::list(vec![JmlValue::object(indexmap! {
+        "id".to_string() => JmlValue::int(1),
+        "tags".to_string() => JmlValue::list(vec![JmlValue::string("a")]),
+    })]);
+
+    let error = format::render(&value, Format::Csv).expect_err("lists are not valid CSV cells");
+
+    assert_eq!(
+        error.to_string(),
+        "List at `$[0].tags` cannot be represented in CSV"
+    );
+}
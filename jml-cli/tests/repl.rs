@@ -0,0 +1,92 @@
+use eval::value::JmlValue;
+use jml_cli::repl::Repl;
+use miette::{GraphicalReportHandler, GraphicalTheme};
+
+#[test]
+fn test_repl_keeps_bindings_between_entries() {
+    let mut repl = Repl::new();
+
+    assert_eq!(repl.eval_line("x = 20").unwrap(), None);
+    assert_eq!(repl.eval_line("double = \\n. n * 2").unwrap(), None);
+    assert_eq!(
+        repl.eval_line("double(x) + 2").unwrap(),
+        Some("42".to_string())
+    );
+}
+
+#[test]
+fn test_repl_commands() {
+    let mut repl = Repl::new();
+    repl.bind_value("payload", JmlValue::list(vec![JmlValue::int(1)]));
+    repl.eval_line("name = \"jml\"").unwrap();
+
+    assert_eq!(
+        repl.eval_line(":type payload[0]").unwrap(),
+        Some("Int".to_string())
+    );
+    assert_eq!(
+        repl.eval_line(":vars").unwrap(),
+        Some("payload : List\nname : String".to_string())
+    );
+    assert!(repl.eval_line(":unknown").is_err());
+}
+
+#[test]
+fn test_repl_reports_errors_and_recovers() {
+    let mut repl = Repl::new();
+
+    assert!(repl.eval_line("missing + 1").is_err());
+    assert!(repl.eval_line("1 +").is_err());
+    assert_eq!(repl.eval_line("1 + 1").unwrap(), Some("2".to_string()));
+}
+
+#[test]
+fn test_repl_binds_values_once() {
+    let mut repl = Repl::new();
+
+    repl.eval_line("x = 1").unwrap();
+    repl.eval_line("x = x + 1").unwrap();
+    repl.eval_line("y = x * 10").unwrap();
+    repl.eval_line("x = 5").unwrap();
+
+    assert_eq!(
+        repl.eval_line("[x, y]").unwrap(),
+        Some("[\n  5,\n  20\n]".to_string())
+    );
+}
+
+#[test]
+fn test_repl_reports_errors_in_earlier_entries() {
+    let mut repl = Repl::new();
+    repl.eval_line("f = \\x. x + \"a\"").unwrap();
+
+    for line in ["f(1)", "[1, 2, 3, 4, f(1)]"] {
+        let report = repl.eval_line(line).unwrap_err();
+
+        let mut rendered = String::new();
+        GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+            .render_report(&mut rendered, report.as_ref())
+            .unwrap();
+        assert!(rendered.contains("f = \\x. x + \"a\""), "{}", rendered);
+        assert!(!rendered.contains("f(1)"), "{}", rendered);
+    }
+}
+
+#[test]
+fn test_repl_tells_lambdas_of_different_entries_apart() {
+    let mut repl = Repl::new();
+    repl.eval_line("f = \\x. x").unwrap();
+    repl.eval_line("g = \\x. x").unwrap();
+
+    assert_eq!(
+        repl.eval_line("[f == f, f == g]").unwrap(),
+        Some("[\n  true,\n  false\n]".to_string())
+    );
+}
+
+#[test]
+fn test_repl_leaves_now_unbound() {
+    let mut repl = Repl::new();
+
+    assert!(repl.eval_line("now").is_err());
+}
@@ -0,0 +1,55 @@
+use eval::context::Context;
+use jml_cli::{cli::OnError, stream::run_stream};
+
+fn stream(source: &'static str, input: &str, on_error: OnError) -> miette::Result<String> {
+    let jml = parser::parse(source).expect("should successfully parse");
+    let mut ctx = Context::new();
+    let mut output = vec![];
+
+    run_stream(
+        jml,
+        source,
+        &mut ctx,
+        "line",
+        input.as_bytes(),
+        &mut output,
+        on_error,
+    )?;
+
+    Ok(String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn test_stream_evaluates_each_line() {
+    let source = r#"
+        factor = 10
+        ---
+        line.value * factor
+    "#
+    .trim();
+    let input = "{\"value\": 1}\n\n{\"value\": 2}\n";
+
+    let output = stream(source, input, OnError::Abort).expect("should successfully stream");
+
+    assert_eq!(output, "10\n20\n");
+}
+
+#[test]
+fn test_stream_skips_failing_lines() {
+    let source = "line.value + 1";
+    let input = "{\"value\": 1}\nnot json\n{\"value\": \"a\"}\n{\"value\": 3}\n";
+
+    let output = stream(source, input, OnError::Skip).expect("should successfully stream");
+
+    assert_eq!(output, "2\n4\n");
+}
+
+#[test]
+fn test_stream_aborts_with_line_number() {
+    let source = "line.value + 1";
+    let input = "{\"value\": 1}\nnot json\n{\"value\": 3}\n";
+
+    let error = stream(source, input, OnError::Abort).expect_err("should abort on line 2");
+
+    assert_eq!(error.to_string(), "input line 2 is not valid JSON");
+}
@@ -1,27 +1,169 @@
-use std::num::{ParseFloatError, ParseIntError};
-
 use miette::{Diagnostic, SourceSpan};
 use thiserror::Error;
 
 #[derive(Error, Diagnostic, Debug, Default, PartialEq, Clone)]
-#[error("evaluation error")]
 pub enum LexingError {
-    InvalidFloat {
-        #[source_code]
-        source: String,
-        #[label("Error occurred here")]
+    #[error("unexpected character `{found}`")]
+    #[diagnostic(code(lexer::unexpected_character))]
+    UnexpectedCharacter {
+        found: char,
+        #[label("this character does not start any token")]
+        span: SourceSpan,
+    },
+
+    #[error("unterminated string")]
+    #[diagnostic(
+        code(lexer::unterminated_string),
+        help("add a closing `\"` to end the string")
+    )]
+    UnterminatedString {
+        #[label("string starts here")]
+        span: SourceSpan,
+    },
+
+    #[error("invalid escape sequence `{sequence}`")]
+    #[diagnostic(
+        code(lexer::invalid_escape),
+        help("valid escapes are \\\", \\\\, \\/, \\b, \\f, \\n, \\r, \\t and \\uXXXX")
+    )]
+    InvalidEscape {
+        sequence: String,
+        #[label("unknown escape")]
+        span: SourceSpan,
+    },
+
+    #[error("integer literal is out of range")]
+    #[diagnostic(
+        code(lexer::integer_out_of_range),
+        help("integers must be between -9223372036854775808 and 9223372036854775807; use a float for larger numbers")
+    )]
+    IntegerOutOfRange {
+        #[label("does not fit in a 64-bit integer")]
+        span: SourceSpan,
+    },
+
+    #[error("float literal is out of range")]
+    #[diagnostic(
+        code(lexer::float_out_of_range),
+        help("floats must be finite 64-bit numbers")
+    )]
+    FloatOutOfRange {
+        #[label("too large to represent")]
         span: SourceSpan,
-        #[source]
-        e: ParseFloatError,
     },
-    InvalidInteger {
-        #[source_code]
-        source: String,
-        #[label("Error occurred here")]
+
+    #[error("unexpected `_` in number literal")]
+    #[diagnostic(
+        code(lexer::stray_underscore),
+        help("`_` can only separate two digits, as in `1_000_000`")
+    )]
+    StrayUnderscore {
+        #[label("not between two digits")]
         span: SourceSpan,
-        #[source]
-        e: ParseIntError,
     },
+
+    #[error("missing digits after `{prefix}`")]
+    #[diagnostic(code(lexer::missing_digits))]
+    MissingDigits {
+        prefix: String,
+        #[label("expected digits here")]
+        span: SourceSpan,
+    },
+
+    #[error("invalid digit `{digit}` in base {radix} literal")]
+    #[diagnostic(code(lexer::invalid_digit))]
+    InvalidDigit {
+        digit: char,
+        radix: u32,
+        #[label("not a base {radix} digit")]
+        span: SourceSpan,
+    },
+
+    #[error("missing digits after the decimal point")]
+    #[diagnostic(
+        code(lexer::missing_fraction_digits),
+        help("add a digit after the `.`, as in `1.0`")
+    )]
+    MissingFractionDigits {
+        #[label("expected a digit after this")]
+        span: SourceSpan,
+    },
+
+    /// What logos reports when no token matches. [`crate::Lexer`] replaces it
+    /// with one of the errors above, so it only comes from `Token::lexer`.
     #[default]
+    #[error("undefined token")]
+    #[diagnostic(code(lexer::undefined_token))]
     UndefinedToken,
 }
+
+impl LexingError {
+    pub fn span(&self) -> Option<SourceSpan> {
+        match self {
+            LexingError::UnexpectedCharacter { span, .. }
+            | LexingError::UnterminatedString { span }
+            | LexingError::InvalidEscape { span, .. }
+            | LexingError::IntegerOutOfRange { span }
+            | LexingError::FloatOutOfRange { span }
+            | LexingError::StrayUnderscore { span }
+            | LexingError::MissingDigits { span, .. }
+            | LexingError::InvalidDigit { span, .. }
+            | LexingError::MissingFractionDigits { span } => Some(*span),
+            LexingError::UndefinedToken => None,
+        }
+    }
+
+    /// Works out why nothing matched at `start`.
+    pub(crate) fn unmatched(source: &str, start: usize) -> Self {
+        let rest = &source[start..];
+
+        if !rest.starts_with('"') {
+            let found = rest.chars().next().unwrap_or_default();
+            return LexingError::UnexpectedCharacter {
+                found,
+                span: (start, found.len_utf8()).into(),
+            };
+        }
+
+        let mut chars = rest.char_indices().skip(1);
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    return LexingError::UnexpectedCharacter {
+                        found: '"',
+                        span: (start, 1).into(),
+                    }
+                }
+                '\\' => {
+                    let escape = match chars.next() {
+                        Some((_, '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't')) => continue,
+                        Some((_, 'u')) => {
+                            let digits: String = rest[offset + 2..]
+                                .chars()
+                                .take(4)
+                                .take_while(char::is_ascii_hexdigit)
+                                .collect();
+                            if digits.len() == 4 {
+                                chars.nth(3);
+                                continue;
+                            }
+                            format!("\\u{}", digits)
+                        }
+                        Some((_, other)) => format!("\\{}", other),
+                        None => "\\".to_owned(),
+                    };
+
+                    return LexingError::InvalidEscape {
+                        span: (start + offset, escape.len()).into(),
+                        sequence: escape,
+                    };
+                }
+                _ => {}
+            }
+        }
+
+        LexingError::UnterminatedString {
+            span: (start, 1).into(),
+        }
+    }
+}
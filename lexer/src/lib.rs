@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use errors::LexingError;
 use logos::{Logos, SpannedIter};
 use token::Token;
@@ -15,6 +17,17 @@ impl<'source> Lexer<'source> {
             token_stream: Token::lexer(source).spanned(),
         }
     }
+
+    /// Creates a lexer that also yields the comments the regular lexer skips.
+    pub fn with_trivia(source: &'source str) -> TriviaLexer<'source> {
+        TriviaLexer {
+            source,
+            lexer: Lexer::new(source),
+            position: 0,
+            pending: VecDeque::new(),
+            finished: false,
+        }
+    }
 }
 
 impl<'source> From<&'source str> for Lexer<'source> {
@@ -27,8 +40,92 @@ impl<'source> Iterator for Lexer<'source> {
     type Item = Result<(usize, Token<'source>, usize), LexingError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.token_stream
-            .next()
-            .map(|(token, span)| token.map(|token| (span.start, token, span.end)))
+        let (token, span) = self.token_stream.next()?;
+
+        Some(match token {
+            Ok(token) => Ok((span.start, token, span.end)),
+            Err(LexingError::UndefinedToken) => Err(LexingError::unmatched(
+                self.token_stream.source(),
+                span.start,
+            )),
+            Err(error) => Err(error),
+        })
+    }
+}
+
+/// Either a token or a piece of trivia preserved by [`Lexer::with_trivia`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum Lexeme<'source> {
+    Token(Token<'source>),
+    /// A `//` or `#` comment, without the trailing newline.
+    Comment(&'source str),
+}
+
+pub struct TriviaLexer<'source> {
+    source: &'source str,
+    lexer: Lexer<'source>,
+    position: usize,
+    pending: VecDeque<Result<(usize, Lexeme<'source>, usize), LexingError>>,
+    finished: bool,
+}
+
+impl<'source> TriviaLexer<'source> {
+    /// Queues the comments found between the previous token and `end`.
+    ///
+    /// Everything between two tokens is whitespace or comments, so the gap can
+    /// be scanned without re-lexing the tokens themselves.
+    fn scan_gap(&mut self, end: usize) {
+        let gap = &self.source[self.position..end];
+        let mut offset = 0;
+
+        while offset < gap.len() {
+            let rest = &gap[offset..];
+            if rest.starts_with("//") || rest.starts_with('#') {
+                let len = rest.find('\n').unwrap_or(rest.len());
+                let start = self.position + offset;
+                self.pending
+                    .push_back(Ok((start, Lexeme::Comment(&rest[..len]), start + len)));
+                offset += len;
+            } else {
+                offset += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+
+        self.position = end;
+    }
+}
+
+impl<'source> Iterator for TriviaLexer<'source> {
+    type Item = Result<(usize, Lexeme<'source>, usize), LexingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.pending.pop_front() {
+            return Some(item);
+        }
+
+        if self.finished {
+            return None;
+        }
+
+        match self.lexer.next() {
+            Some(Ok((start, token, end))) => {
+                self.scan_gap(start);
+                self.pending
+                    .push_back(Ok((start, Lexeme::Token(token), end)));
+                self.position = end;
+            }
+            Some(Err(e)) => {
+                let span = self.lexer.token_stream.span();
+                self.scan_gap(span.start);
+                self.pending.push_back(Err(e));
+                self.position = span.end;
+            }
+            None => {
+                self.scan_gap(self.source.len());
+                self.finished = true;
+            }
+        }
+
+        self.pending.pop_front()
     }
 }
@@ -1,6 +1,6 @@
 use crate::errors::LexingError;
 use derive_more::derive::Display;
-use literal::{float, ident, int, string};
+use literal::{float, ident, int, missing_fraction, radix_int, string};
 use logos::Logos;
 mod literal;
 
@@ -27,6 +27,12 @@ pub enum Token<'source> {
 
     #[token("else")]
     Else,
+
+    #[token("for")]
+    For,
+
+    #[token("in")]
+    In,
     // Types
     #[token("String")]
     StringType,
@@ -53,13 +59,17 @@ pub enum Token<'source> {
     #[regex(r"[a-zA-Z_][a-zA-Z0-9_]*", callback=ident)]
     Identifier(&'source str),
 
-    #[regex(r#""([^"\\]|\\["\\bnfrt]|u[a-fA-F0-9]{4})*""#, callback=string)]
+    #[regex(r#""([^"\\]|\\["\\/bnfrt]|\\u[a-fA-F0-9]{4})*""#, callback=string)]
     StringLiteral(&'source str),
 
-    #[regex(r"[0-9][_0-9]*", callback=int, priority=2)]
-    IntLiteral(i64),
+    /// Unsigned, as a leading `-` is a separate token; the parser folds it
+    /// back in and checks the value fits in an `i64`.
+    #[regex(r"[0-9][_0-9]*", callback=int, priority=3)]
+    #[regex(r"0[xXbBoO][0-9a-zA-Z_]*", callback=radix_int)]
+    IntLiteral(u64),
 
-    #[regex(r"(0|[1-9][0-9]*)(\.[0-9]+)?([Ee][\-+]?[0-9]+)?", callback=float, priority=1)]
+    #[regex(r"[0-9][_0-9]*(\.[0-9][_0-9]*)?([Ee][\-+]?[0-9][_0-9]*)?", callback=float, priority=2)]
+    #[regex(r"[0-9][_0-9]*\.", callback=missing_fraction)]
     FloatLiteral(f64),
 
     #[token("false", |_| false)]
@@ -125,6 +135,9 @@ pub enum Token<'source> {
     #[token(".")]
     Dot,
 
+    #[token("...")]
+    Spread,
+
     #[token(",")]
     Comma,
 
@@ -155,9 +168,22 @@ pub enum Token<'source> {
     #[token("=>")]
     Arrow,
 
+    #[token("?.")]
+    SafeDot,
+
+    #[token("?[")]
+    SafeLBracket,
+
+    #[token("??")]
+    Coalesce,
+
     // Comments
     #[regex(r"//[^\n]*", logos::skip)]
     LineComment,
+
+    /// Never lexed. A parser can put it where a lexing error was, so that
+    /// the error is reported and parsing carries on after it.
+    Error,
 }
 
 #[cfg(test)]
@@ -167,11 +193,13 @@ mod tests {
 
     #[test]
     fn test_keywords() {
-        let mut lexer = Token::lexer("null if else");
+        let mut lexer = Token::lexer("null if else for in");
 
         assert_eq!(lexer.next(), Some(Ok(Token::Null)));
         assert_eq!(lexer.next(), Some(Ok(Token::If)));
         assert_eq!(lexer.next(), Some(Ok(Token::Else)));
+        assert_eq!(lexer.next(), Some(Ok(Token::For)));
+        assert_eq!(lexer.next(), Some(Ok(Token::In)));
         assert_eq!(lexer.next(), None); // No more tokens
     }
 
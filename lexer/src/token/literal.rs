@@ -1,25 +1,75 @@
 use logos::Lexer;
+use miette::SourceSpan;
 
 use crate::errors::LexingError;
 
 use super::Token;
 
 pub fn float<'source>(lex: &mut Lexer<'source, Token<'source>>) -> Result<f64, LexingError> {
-    lex.slice().parse().map_err(|e| LexingError::InvalidFloat {
-        source: lex.source().into(),
-        span: (lex.span().start, lex.span().end - lex.span().start).into(),
-        e,
+    let span = lex.span();
+    let digits = without_separators(lex.slice(), span.start, |c| c.is_ascii_digit())?;
+
+    // The regex only matches valid floats, but large exponents overflow.
+    match digits.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        _ => Err(LexingError::FloatOutOfRange {
+            span: (span.start, span.len()).into(),
+        }),
+    }
+}
+
+/// `1.` is rejected rather than read as `1.0`, as it is easy to mistake for
+/// the start of a selector.
+pub fn missing_fraction<'source>(
+    lex: &mut Lexer<'source, Token<'source>>,
+) -> Result<f64, LexingError> {
+    Err(LexingError::MissingFractionDigits {
+        span: (lex.span().end - 1, 1).into(),
     })
 }
 
-pub fn int<'source>(lex: &mut Lexer<'source, Token<'source>>) -> Result<i64, LexingError> {
-    lex.slice()
-        .parse()
-        .map_err(|e| LexingError::InvalidInteger {
-            source: lex.source().into(),
-            span: (lex.span().start, lex.span().end - lex.span().start).into(),
-            e,
-        })
+pub fn int<'source>(lex: &mut Lexer<'source, Token<'source>>) -> Result<u64, LexingError> {
+    let span = lex.span();
+    let digits = without_separators(lex.slice(), span.start, |c| c.is_ascii_digit())?;
+
+    digits.parse().map_err(|_| LexingError::IntegerOutOfRange {
+        span: (span.start, span.len()).into(),
+    })
+}
+
+/// Hexadecimal, binary and octal literals, such as `0xff`, `0b1010` and `0o755`.
+pub fn radix_int<'source>(lex: &mut Lexer<'source, Token<'source>>) -> Result<u64, LexingError> {
+    let span = lex.span();
+    let (prefix, rest) = lex.slice().split_at(2);
+    let radix = match prefix.as_bytes()[1] {
+        b'x' | b'X' => 16,
+        b'b' | b'B' => 2,
+        _ => 8,
+    };
+
+    if rest.is_empty() {
+        return Err(LexingError::MissingDigits {
+            prefix: prefix.to_owned(),
+            span: (span.start, span.len()).into(),
+        });
+    }
+
+    let digits = without_separators(rest, span.start + 2, |c| c.is_ascii_alphanumeric())?;
+
+    if let Some((offset, digit)) = rest
+        .char_indices()
+        .find(|(_, c)| *c != '_' && !c.is_digit(radix))
+    {
+        return Err(LexingError::InvalidDigit {
+            digit,
+            radix,
+            span: (span.start + 2 + offset, 1).into(),
+        });
+    }
+
+    u64::from_str_radix(&digits, radix).map_err(|_| LexingError::IntegerOutOfRange {
+        span: (span.start, span.len()).into(),
+    })
 }
 
 pub fn string<'source>(lex: &mut Lexer<'source, Token<'source>>) -> &'source str {
@@ -29,3 +79,24 @@ pub fn string<'source>(lex: &mut Lexer<'source, Token<'source>>) -> &'source str
 pub fn ident<'source>(lex: &mut Lexer<'source, Token<'source>>) -> &'source str {
     lex.slice()
 }
+
+/// Strips `_` digit separators, which are only allowed between two digits.
+fn without_separators(
+    literal: &str,
+    start: usize,
+    is_digit: impl Fn(char) -> bool,
+) -> Result<String, LexingError> {
+    let bytes = literal.as_bytes();
+
+    for (offset, _) in literal.match_indices('_') {
+        let before = offset.checked_sub(1).map(|i| bytes[i] as char);
+        let after = bytes.get(offset + 1).map(|&b| b as char);
+
+        if !before.is_some_and(&is_digit) || !after.is_some_and(&is_digit) {
+            let span: SourceSpan = (start + offset, 1).into();
+            return Err(LexingError::StrayUnderscore { span });
+        }
+    }
+
+    Ok(literal.replace('_', ""))
+}
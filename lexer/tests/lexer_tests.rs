@@ -2,15 +2,173 @@
 mod tests {
     use lexer::{errors::LexingError, token::Token, Lexer};
 
+    fn first_error(source: &str) -> LexingError {
+        Lexer::new(source)
+            .find_map(Result::err)
+            .expect("Expected a lexing error")
+    }
+
     #[test]
-    fn test_undefined_token() {
-        let source = "@";
+    fn test_unexpected_character() {
+        assert_eq!(
+            first_error("x = @"),
+            LexingError::UnexpectedCharacter {
+                found: '@',
+                span: (4, 1).into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_unterminated_string() {
+        assert_eq!(
+            first_error(r#"x = "abc"#),
+            LexingError::UnterminatedString {
+                span: (4, 1).into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_escape() {
+        assert_eq!(
+            first_error(r#""a\qb""#),
+            LexingError::InvalidEscape {
+                sequence: "\\q".to_owned(),
+                span: (2, 2).into()
+            }
+        );
+        assert_eq!(
+            first_error(r#""\u12g""#),
+            LexingError::InvalidEscape {
+                sequence: "\\u12".to_owned(),
+                span: (1, 4).into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_valid_escapes() {
+        let source = r#""\" \\ \/ \n \u00e9""#;
         let mut lexer = Lexer::new(source);
 
-        match lexer.next() {
-            Some(Err(LexingError::UndefinedToken)) => {}
-            _ => panic!("Expected UndefinedToken error"),
-        }
+        assert!(matches!(
+            lexer.next(),
+            Some(Ok((_, Token::StringLiteral(_), _)))
+        ));
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn test_integer_out_of_range() {
+        assert_eq!(
+            first_error("1 + 99999999999999999999"),
+            LexingError::IntegerOutOfRange {
+                span: (4, 20).into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_float_out_of_range() {
+        assert_eq!(
+            first_error("1e999"),
+            LexingError::FloatOutOfRange {
+                span: (0, 5).into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_stray_underscore() {
+        assert_eq!(
+            first_error("12__3"),
+            LexingError::StrayUnderscore {
+                span: (2, 1).into()
+            }
+        );
+        assert_eq!(
+            first_error("1_000_"),
+            LexingError::StrayUnderscore {
+                span: (5, 1).into()
+            }
+        );
+        assert_eq!(
+            first_error("0x_ff"),
+            LexingError::StrayUnderscore {
+                span: (2, 1).into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_digit_separators() {
+        let tokens: Vec<_> = Lexer::new("1_000_000 0.000_1 1_0e1_0")
+            .map(|token| token.unwrap().1)
+            .collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::IntLiteral(1_000_000),
+                Token::FloatLiteral(0.000_1),
+                Token::FloatLiteral(1e11),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_radix_int_literals() {
+        let tokens: Vec<_> = Lexer::new("0xff 0XFF_FF 0b1010 0o755 0xFFFFFFFFFFFFFFFF")
+            .map(|token| token.unwrap().1)
+            .collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::IntLiteral(0xff),
+                Token::IntLiteral(0xffff),
+                Token::IntLiteral(0b1010),
+                Token::IntLiteral(0o755),
+                Token::IntLiteral(u64::MAX),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_radix_literals() {
+        assert_eq!(
+            first_error("0b102"),
+            LexingError::InvalidDigit {
+                digit: '2',
+                radix: 2,
+                span: (4, 1).into()
+            }
+        );
+        assert_eq!(
+            first_error("0x"),
+            LexingError::MissingDigits {
+                prefix: "0x".to_owned(),
+                span: (0, 2).into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_fraction_digits() {
+        assert_eq!(
+            first_error("[1.]"),
+            LexingError::MissingFractionDigits {
+                span: (2, 1).into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_leading_dot_is_not_a_float() {
+        let tokens: Vec<_> = Lexer::new(".5").map(|token| token.unwrap().1).collect();
+
+        assert_eq!(tokens, vec![Token::Dot, Token::IntLiteral(5)]);
     }
 
     #[test]
@@ -77,3 +235,41 @@ mod tests {
         assert_eq!(lexer.next(), None);
     }
 }
+
+#[cfg(test)]
+mod trivia_tests {
+    use lexer::{token::Token, Lexeme, Lexer};
+
+    #[test]
+    fn test_trivia_keeps_comments() {
+        let source = "// leading\nx = 1 # trailing\n---\nx";
+        let lexemes: Vec<_> = Lexer::with_trivia(source).map(Result::unwrap).collect();
+
+        assert_eq!(
+            lexemes,
+            vec![
+                (0, Lexeme::Comment("// leading"), 10),
+                (11, Lexeme::Token(Token::Identifier("x")), 12),
+                (13, Lexeme::Token(Token::Assign), 14),
+                (15, Lexeme::Token(Token::IntLiteral(1)), 16),
+                (17, Lexeme::Comment("# trailing"), 27),
+                (28, Lexeme::Token(Token::Header), 31),
+                (32, Lexeme::Token(Token::Identifier("x")), 33),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trivia_comment_at_end_of_input() {
+        let source = "x // done";
+        let lexemes: Vec<_> = Lexer::with_trivia(source).map(Result::unwrap).collect();
+
+        assert_eq!(
+            lexemes,
+            vec![
+                (0, Lexeme::Token(Token::Identifier("x")), 1),
+                (2, Lexeme::Comment("// done"), 9),
+            ]
+        );
+    }
+}
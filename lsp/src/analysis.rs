@@ -0,0 +1,751 @@
+//! Editor features computed from a single document, independent of the
+//! protocol. Every position here is a byte offset into the source.
+
+use std::{
+    ops::Range,
+    panic::{self, AssertUnwindSafe},
+};
+
+use eval::{
+    context::Context,
+    errors::{EvalError, RuntimeError, TypeError, TypeErrorKind},
+    expr::eval_expr,
+    jml_type::JmlType,
+    stdlib::{std_lib_signatures, time::NOW},
+};
+use lexer::{token::Token, Lexer};
+use miette::Diagnostic;
+use parser::{
+    ast::{
+        ComprehensionClause, Expression, ExpressionKind, Identifier, Jml, Key, ListElement,
+        ObjectMember, Statement, StatementKind, UnaryOp,
+    },
+    errors::{ParseErrors, SyntaxError},
+};
+
+/// How many expression nodes the constant check may evaluate per document,
+/// so that a large document doesn't slow down every keystroke.
+const CONSTANT_CHECK_BUDGET: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Information,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub span: Range<usize>,
+    pub severity: Severity,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Function,
+    Variable,
+    Parameter,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Variable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub detail: Option<String>,
+    pub span: Range<usize>,
+    pub name_span: Range<usize>,
+}
+
+/// What a variable refers to at the place it is used.
+enum Definition<'a, 'source> {
+    Parameter(&'a Identifier<'source>),
+    Header(&'a Statement<'source>),
+    StdLib(&'a (String, Vec<String>)),
+    Unbound,
+}
+
+/// A parsed document together with the natives every script can call.
+pub struct Analysis<'source> {
+    source: &'source str,
+    parsed: Result<Jml<'source>, ParseErrors>,
+    std_lib: Vec<(String, Vec<String>)>,
+}
+
+impl<'source> Analysis<'source> {
+    pub fn new(source: &'source str) -> Self {
+        Analysis {
+            source,
+            parsed: parser::parse(source),
+            std_lib: std_lib_signatures(),
+        }
+    }
+
+    pub fn diagnostics(&self) -> Vec<Problem> {
+        let jml = match &self.parsed {
+            Ok(jml) => jml,
+            Err(errors) => {
+                return errors
+                    .errors
+                    .iter()
+                    .map(|error| syntax_problem(self.source, error))
+                    .collect()
+            }
+        };
+
+        let mut problems = vec![];
+        let mut budget = CONSTANT_CHECK_BUDGET;
+
+        for expression in expressions(jml) {
+            check_constants(expression, &mut problems, &mut budget);
+        }
+
+        self.walk(jml, &mut |expression, scope| match &expression.node {
+            ExpressionKind::Variable(name) => {
+                // `now` is bound by the host rather than passed with `-v`.
+                if *name == NOW {
+                    return;
+                }
+                if let Definition::Unbound = self.resolve(jml, name, scope) {
+                    problems.push(Problem {
+                        span: expression.l..expression.r,
+                        severity: Severity::Information,
+                        code: Some("analysis::unbound_variable".to_owned()),
+                        message: format!(
+                            "`{}` is not bound in this script; pass it with `-v {}=<file>`",
+                            name, name
+                        ),
+                    });
+                }
+            }
+            ExpressionKind::Apply { lambda, args } => {
+                let ExpressionKind::Variable(name) = &lambda.node else {
+                    return;
+                };
+                let expected_count = match self.resolve(jml, name, scope) {
+                    Definition::Header(statement) => match &bound(statement).node {
+                        ExpressionKind::Lambda { params, .. } => params.len(),
+                        _ => return,
+                    },
+                    Definition::StdLib((_, params)) => params.len(),
+                    _ => return,
+                };
+
+                if expected_count != args.len() {
+                    problems.push(type_problem(
+                        expression.l..expression.r,
+                        TypeErrorKind::ArgumentCountMismatch {
+                            expected_count,
+                            actual_count: args.len(),
+                        },
+                    ));
+                }
+            }
+            _ => {}
+        });
+
+        problems.sort_by_key(|problem| problem.span.start);
+        problems
+    }
+
+    /// Span of the binding the name under `offset` refers to.
+    pub fn definition(&self, offset: usize) -> Option<Range<usize>> {
+        let jml = self.parsed.as_ref().ok()?;
+
+        if let Some(statement) = header_name_at(jml, offset) {
+            let StatementKind::Bind { identifier, .. } = &statement.node;
+            return Some(identifier.l..identifier.r);
+        }
+
+        let mut found = None;
+        self.walk(jml, &mut |expression, scope| {
+            if let ExpressionKind::Lambda { params, .. } = &expression.node {
+                if let Some(param) = params.iter().find(|p| contains(p.l..p.r, offset)) {
+                    found = Some(param.l..param.r);
+                }
+            }
+
+            if let ExpressionKind::Variable(name) = &expression.node {
+                if contains(expression.l..expression.r, offset) {
+                    found = match self.resolve(jml, name, scope) {
+                        Definition::Parameter(param) => Some(param.l..param.r),
+                        Definition::Header(statement) => {
+                            let StatementKind::Bind { identifier, .. } = &statement.node;
+                            Some(identifier.l..identifier.r)
+                        }
+                        Definition::StdLib(_) | Definition::Unbound => None,
+                    };
+                }
+            }
+        });
+
+        found
+    }
+
+    /// Markdown describing the name under `offset`.
+    pub fn hover(&self, offset: usize) -> Option<String> {
+        let jml = self.parsed.as_ref().ok()?;
+
+        if let Some(statement) = header_name_at(jml, offset) {
+            return Some(self.describe_header(jml, statement));
+        }
+
+        let mut found = None;
+        self.walk(jml, &mut |expression, scope| {
+            if let ExpressionKind::Lambda { params, .. } = &expression.node {
+                if let Some(param) = params.iter().find(|p| contains(p.l..p.r, offset)) {
+                    found = Some(code_block(format!("(parameter) {}", param.node)));
+                }
+            }
+
+            if let ExpressionKind::Variable(name) = &expression.node {
+                if contains(expression.l..expression.r, offset) {
+                    found = match self.resolve(jml, name, scope) {
+                        Definition::Parameter(param) => {
+                            Some(code_block(format!("(parameter) {}", param.node)))
+                        }
+                        Definition::Header(statement) => Some(self.describe_header(jml, statement)),
+                        Definition::StdLib((name, params)) => Some(format!(
+                            "{}\n\nBuilt-in function",
+                            code_block(format!("{}({})", name, params.join(", ")))
+                        )),
+                        Definition::Unbound => Some(format!(
+                            "{}\n\nNot bound in this script, expected from `-v {}=<file>`",
+                            code_block(name.to_string()),
+                            name
+                        )),
+                    };
+                }
+            }
+        });
+
+        found
+    }
+
+    /// Names that can be typed at `offset`.
+    pub fn completions(&self, offset: usize) -> Vec<Completion> {
+        let mut completions: Vec<Completion> = self
+            .std_lib
+            .iter()
+            .map(|(name, params)| Completion {
+                label: name.clone(),
+                kind: CompletionKind::Function,
+                detail: Some(format!("{}({})", name, params.join(", "))),
+            })
+            .collect();
+
+        let Ok(jml) = &self.parsed else {
+            completions.extend(
+                bindings_by_scan(self.source)
+                    .into_iter()
+                    .map(|name| Completion {
+                        label: name.to_owned(),
+                        kind: CompletionKind::Variable,
+                        detail: None,
+                    }),
+            );
+            return dedup(completions);
+        };
+
+        for statement in &jml.header {
+            let StatementKind::Bind {
+                identifier,
+                expression,
+            } = &statement.node;
+            let kind = match expression.node {
+                ExpressionKind::Lambda { .. } => CompletionKind::Function,
+                _ => CompletionKind::Variable,
+            };
+            completions.push(Completion {
+                label: identifier.node.to_owned(),
+                kind,
+                detail: self.type_of(jml, expression).map(|t| t.to_string()),
+            });
+        }
+
+        self.walk(jml, &mut |expression, _| {
+            if let ExpressionKind::Lambda { params, body } = &expression.node {
+                if contains(body.l..body.r, offset) {
+                    completions.extend(params.iter().map(|param| Completion {
+                        label: param.node.to_owned(),
+                        kind: CompletionKind::Parameter,
+                        detail: None,
+                    }));
+                }
+            }
+        });
+
+        dedup(completions)
+    }
+
+    /// One symbol per header binding.
+    pub fn symbols(&self) -> Vec<Symbol> {
+        let Ok(jml) = &self.parsed else {
+            return vec![];
+        };
+
+        jml.header
+            .iter()
+            .map(|statement| {
+                let StatementKind::Bind {
+                    identifier,
+                    expression,
+                } = &statement.node;
+                let kind = match expression.node {
+                    ExpressionKind::Lambda { .. } => SymbolKind::Function,
+                    _ => SymbolKind::Variable,
+                };
+
+                Symbol {
+                    name: identifier.node.to_owned(),
+                    kind,
+                    detail: self.type_of(jml, expression).map(|t| t.to_string()),
+                    span: statement.l..statement.r,
+                    name_span: identifier.l..identifier.r,
+                }
+            })
+            .collect()
+    }
+
+    fn describe_header(&self, jml: &Jml<'source>, statement: &Statement<'source>) -> String {
+        let StatementKind::Bind {
+            identifier,
+            expression,
+        } = &statement.node;
+
+        let signature = match &expression.node {
+            ExpressionKind::Lambda { params, .. } => format!(
+                "{}({})",
+                identifier.node,
+                params.iter().map(|p| p.node).collect::<Vec<_>>().join(", ")
+            ),
+            _ => match self.type_of(jml, expression) {
+                Some(ty) => format!("{}: {}", identifier.node, ty),
+                None => identifier.node.to_owned(),
+            },
+        };
+
+        code_block(signature)
+    }
+
+    /// Calls `visit` for every expression in the document, along with the
+    /// lambda parameters in scope at that expression, innermost last.
+    fn walk<'a, F>(&self, jml: &'a Jml<'source>, visit: &mut F)
+    where
+        F: FnMut(&'a Expression<'source>, &[&'a Identifier<'source>]),
+    {
+        for expression in expressions(jml) {
+            walk_expression(expression, &mut vec![], visit);
+        }
+    }
+
+    fn resolve<'a>(
+        &'a self,
+        jml: &'a Jml<'source>,
+        name: &str,
+        scope: &[&'a Identifier<'source>],
+    ) -> Definition<'a, 'source> {
+        if let Some(param) = scope.iter().rev().find(|param| param.node == name) {
+            return Definition::Parameter(param);
+        }
+
+        // Header bindings are global and a later binding replaces an earlier one.
+        if let Some(statement) = jml.header.iter().rev().find(|statement| {
+            let StatementKind::Bind { identifier, .. } = &statement.node;
+            identifier.node == name
+        }) {
+            return Definition::Header(statement);
+        }
+
+        match self.std_lib.iter().find(|(native, _)| native == name) {
+            Some(signature) => Definition::StdLib(signature),
+            None => Definition::Unbound,
+        }
+    }
+
+    /// Best-effort static type of an expression, if it can be known without
+    /// evaluating anything.
+    fn type_of(&self, jml: &Jml<'source>, expression: &Expression<'source>) -> Option<JmlType> {
+        self.type_of_depth(jml, expression, 0)
+    }
+
+    fn type_of_depth(
+        &self,
+        jml: &Jml<'source>,
+        expression: &Expression<'source>,
+        depth: usize,
+    ) -> Option<JmlType> {
+        use parser::ast::BinaryOp::*;
+
+        // Bindings may refer to each other in a cycle.
+        if depth > 32 {
+            return None;
+        }
+        let type_of = |expression| self.type_of_depth(jml, expression, depth + 1);
+
+        match &expression.node {
+            ExpressionKind::Null => Some(JmlType::Null),
+            ExpressionKind::Float(_) => Some(JmlType::Float),
+            ExpressionKind::Bool(_) => Some(JmlType::Bool),
+            ExpressionKind::Int(_) => Some(JmlType::Int),
+            ExpressionKind::String(_) => Some(JmlType::String),
+            ExpressionKind::Object(_) | ExpressionKind::ObjectComprehension { .. } => {
+                Some(JmlType::Object)
+            }
+            ExpressionKind::List(_) | ExpressionKind::ListComprehension { .. } => {
+                Some(JmlType::List)
+            }
+            ExpressionKind::Lambda { params, .. } => Some(JmlType::Lambda {
+                arity: params.len(),
+            }),
+            ExpressionKind::Variable(name) => match self.resolve(jml, name, &[]) {
+                Definition::Header(statement) => type_of(bound(statement)),
+                Definition::StdLib((_, params)) => Some(JmlType::Lambda {
+                    arity: params.len(),
+                }),
+                _ => None,
+            },
+            ExpressionKind::UnaryOp { op, expr } => match op {
+                UnaryOp::Not => Some(JmlType::Bool),
+                UnaryOp::Minus => type_of(expr).filter(|t| t.is_number()),
+            },
+            ExpressionKind::BinaryOp { op, lhs, rhs } => match op {
+                EQ | NE | GT | LT | GE | LE | And | Or => Some(JmlType::Bool),
+                Concat => Some(JmlType::String),
+                Coalesce => {
+                    let lhs_type = type_of(lhs)?;
+                    (Some(lhs_type) == type_of(rhs)).then_some(lhs_type)
+                }
+                Sum | Sub | Mul | Div | Pow | Mod => match (type_of(lhs)?, type_of(rhs)?) {
+                    (JmlType::Int, JmlType::Int) => Some(JmlType::Int),
+                    (l, r) if l.is_number() && r.is_number() => Some(JmlType::Float),
+                    (JmlType::String, JmlType::String) if *op == Sum => Some(JmlType::String),
+                    (JmlType::List, JmlType::List) if *op == Sum => Some(JmlType::List),
+                    (JmlType::Object, JmlType::Object) if *op == Sum => Some(JmlType::Object),
+                    _ => None,
+                },
+            },
+            ExpressionKind::IfExpr {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let then_type = type_of(then_branch)?;
+                (Some(then_type) == type_of(else_branch)).then_some(then_type)
+            }
+            ExpressionKind::Slice {
+                target,
+                optional: false,
+                ..
+            } => type_of(target).filter(|t| matches!(t, JmlType::List | JmlType::String)),
+            ExpressionKind::IndexAccess { .. }
+            | ExpressionKind::Slice { .. }
+            | ExpressionKind::Selector { .. }
+            | ExpressionKind::Apply { .. } => None,
+        }
+    }
+}
+
+/// Header expressions followed by the body.
+fn expressions<'a, 'source>(
+    jml: &'a Jml<'source>,
+) -> impl Iterator<Item = &'a Expression<'source>> {
+    jml.header
+        .iter()
+        .map(bound)
+        .chain(std::iter::once(&jml.body))
+}
+
+fn bound<'a, 'source>(statement: &'a Statement<'source>) -> &'a Expression<'source> {
+    let StatementKind::Bind { expression, .. } = &statement.node;
+    expression
+}
+
+fn walk_expression<'a, 'source, F>(
+    expression: &'a Expression<'source>,
+    scope: &mut Vec<&'a Identifier<'source>>,
+    visit: &mut F,
+) where
+    F: FnMut(&'a Expression<'source>, &[&'a Identifier<'source>]),
+{
+    visit(expression, scope);
+
+    if let ExpressionKind::Lambda { params, body } = &expression.node {
+        let depth = scope.len();
+        scope.extend(params);
+        walk_expression(body, scope, visit);
+        scope.truncate(depth);
+        return;
+    }
+
+    let comprehension = match &expression.node {
+        ExpressionKind::ListComprehension { element, clauses } => Some((vec![&**element], clauses)),
+        ExpressionKind::ObjectComprehension {
+            key,
+            value,
+            clauses,
+        } => {
+            let key = match &**key {
+                Key::Ident(_) => None,
+                Key::Expression(key) => Some(key),
+            };
+            Some((key.into_iter().chain([&**value]).collect(), clauses))
+        }
+        _ => None,
+    };
+
+    if let Some((produced, clauses)) = comprehension {
+        // Each `for` binding is visible in the clauses after it and in the
+        // produced element.
+        let depth = scope.len();
+        for clause in clauses {
+            match clause {
+                ComprehensionClause::For { binding, iterable } => {
+                    walk_expression(iterable, scope, visit);
+                    scope.push(binding);
+                }
+                ComprehensionClause::If(condition) => walk_expression(condition, scope, visit),
+            }
+        }
+        for child in produced {
+            walk_expression(child, scope, visit);
+        }
+        scope.truncate(depth);
+        return;
+    }
+
+    for child in children(expression) {
+        walk_expression(child, scope, visit);
+    }
+}
+
+fn children<'a, 'source>(expression: &'a Expression<'source>) -> Vec<&'a Expression<'source>> {
+    match &expression.node {
+        ExpressionKind::Null
+        | ExpressionKind::Float(_)
+        | ExpressionKind::Bool(_)
+        | ExpressionKind::Int(_)
+        | ExpressionKind::String(_)
+        | ExpressionKind::Variable(_) => vec![],
+        ExpressionKind::Object(members) => members
+            .iter()
+            .flat_map(|member| match member {
+                ObjectMember::Entry {
+                    key,
+                    value,
+                    condition,
+                } => {
+                    let key = match key {
+                        Key::Ident(_) => None,
+                        Key::Expression(key) => Some(key),
+                    };
+                    key.into_iter()
+                        .chain([value])
+                        .chain(condition)
+                        .collect::<Vec<_>>()
+                }
+                ObjectMember::Spread(object) => vec![object],
+            })
+            .collect(),
+        ExpressionKind::List(elements) => elements
+            .iter()
+            .map(|element| match element {
+                ListElement::Item(item) => item,
+                ListElement::Spread(list) => list,
+            })
+            .collect(),
+        ExpressionKind::ListComprehension { element, clauses } => std::iter::once(&**element)
+            .chain(clauses.iter().map(clause_expression))
+            .collect(),
+        ExpressionKind::ObjectComprehension {
+            key,
+            value,
+            clauses,
+        } => {
+            let key = match &**key {
+                Key::Ident(_) => None,
+                Key::Expression(key) => Some(key),
+            };
+            key.into_iter()
+                .chain([&**value])
+                .chain(clauses.iter().map(clause_expression))
+                .collect()
+        }
+        ExpressionKind::IndexAccess { target, index, .. } => vec![target, index],
+        ExpressionKind::Slice {
+            target,
+            start,
+            end,
+            step,
+            ..
+        } => std::iter::once(target)
+            .chain([start, end, step].into_iter().flatten())
+            .map(|expression| &**expression)
+            .collect(),
+        ExpressionKind::Selector { target, .. } => vec![target],
+        ExpressionKind::UnaryOp { expr, .. } => vec![expr],
+        ExpressionKind::BinaryOp { lhs, rhs, .. } => vec![lhs, rhs],
+        ExpressionKind::IfExpr {
+            condition,
+            then_branch,
+            else_branch,
+        } => vec![condition, then_branch, else_branch],
+        ExpressionKind::Lambda { body, .. } => vec![body],
+        ExpressionKind::Apply { lambda, args } => std::iter::once(&**lambda).chain(args).collect(),
+    }
+}
+
+fn clause_expression<'a, 'source>(
+    clause: &'a ComprehensionClause<'source>,
+) -> &'a Expression<'source> {
+    match clause {
+        ComprehensionClause::For { iterable, .. } => iterable,
+        ComprehensionClause::If(condition) => condition,
+    }
+}
+
+/// Whether the expression can be evaluated without any bindings, and so in
+/// time proportional to its size. Comprehensions are left out because nested
+/// ones multiply their iterations.
+fn is_constant(expression: &Expression) -> bool {
+    !matches!(
+        expression.node,
+        ExpressionKind::Variable(_)
+            | ExpressionKind::Lambda { .. }
+            | ExpressionKind::Apply { .. }
+            | ExpressionKind::ListComprehension { .. }
+            | ExpressionKind::ObjectComprehension { .. }
+    ) && children(expression).into_iter().all(is_constant)
+}
+
+fn size(expression: &Expression) -> usize {
+    1 + children(expression).into_iter().map(size).sum::<usize>()
+}
+
+/// Evaluates the largest constant subexpressions and reports their errors,
+/// until `budget` nodes have been evaluated.
+fn check_constants(expression: &Expression, problems: &mut Vec<Problem>, budget: &mut usize) {
+    if !is_constant(expression) {
+        for child in children(expression) {
+            check_constants(child, problems, budget);
+        }
+        return;
+    }
+
+    let Some(remaining) = budget.checked_sub(size(expression)) else {
+        return;
+    };
+    *budget = remaining;
+
+    // A bug in the evaluator must not take the server down with it.
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        eval_expr(expression.clone(), &mut Context::new())
+    }));
+    if let Ok(Err(error)) = result {
+        problems.push(eval_problem(error));
+    }
+}
+
+fn eval_problem(error: EvalError) -> Problem {
+    match error {
+        EvalError::TypeError(TypeError { span, kind }) => {
+            type_problem(span.offset()..span.offset() + span.len(), kind)
+        }
+        EvalError::RuntimeError(RuntimeError { span, kind }) => Problem {
+            span: span.offset()..span.offset() + span.len(),
+            severity: Severity::Error,
+            code: kind.code().map(|code| code.to_string()),
+            message: kind.to_string(),
+        },
+    }
+}
+
+fn type_problem(span: Range<usize>, kind: TypeErrorKind) -> Problem {
+    Problem {
+        span,
+        severity: Severity::Error,
+        code: kind.code().map(|code| code.to_string()),
+        message: kind.to_string(),
+    }
+}
+
+fn syntax_problem(source: &str, error: &SyntaxError) -> Problem {
+    let span = error
+        .span()
+        .map_or(0..0, |span| span.offset()..span.offset() + span.len());
+
+    let mut message = error.to_string();
+    if let Some(label) = error.labels().and_then(|mut labels| labels.next()) {
+        if let Some(label) = label.label() {
+            message = format!("{}: {}", message, label);
+        }
+    }
+    if let Some(help) = error.help() {
+        message = format!("{}\n{}", message, help);
+    }
+
+    Problem {
+        span: span.start.min(source.len())..span.end.min(source.len()),
+        severity: Severity::Error,
+        code: error.code().map(|code| code.to_string()),
+        message,
+    }
+}
+
+fn header_name_at<'a, 'source>(
+    jml: &'a Jml<'source>,
+    offset: usize,
+) -> Option<&'a Statement<'source>> {
+    jml.header.iter().find(|statement| {
+        let StatementKind::Bind { identifier, .. } = &statement.node;
+        contains(identifier.l..identifier.r, offset)
+    })
+}
+
+/// Header names found by scanning tokens, for documents that don't parse.
+fn bindings_by_scan(source: &str) -> Vec<&str> {
+    let tokens: Vec<_> = Lexer::new(source).filter_map(Result::ok).collect();
+
+    tokens
+        .windows(2)
+        .filter_map(|pair| match pair {
+            [(_, Token::Identifier(name), _), (_, Token::Assign, _)] => Some(*name),
+            _ => None,
+        })
+        .collect()
+}
+
+fn dedup(completions: Vec<Completion>) -> Vec<Completion> {
+    let mut unique: Vec<Completion> = vec![];
+
+    // Later entries are closer in scope, so they replace earlier ones.
+    for completion in completions {
+        unique.retain(|c| c.label != completion.label);
+        unique.push(completion);
+    }
+
+    unique
+}
+
+/// Cursor positions touching either end of a name still count as on it.
+fn contains(span: Range<usize>, offset: usize) -> bool {
+    span.start <= offset && offset <= span.end
+}
+
+fn code_block(code: String) -> String {
+    format!("```jml\n{}\n```", code)
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+
+use analysis::{Analysis, CompletionKind, Severity, SymbolKind};
+use line_index::LineIndex;
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as NotificationTrait, PublishDiagnostics,
+    },
+    request::{Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as _},
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    Diagnostic, DiagnosticSeverity, DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, Location, MarkupContent, MarkupKind, NumberOrString, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    Url,
+};
+
+pub mod analysis;
+mod line_index;
+
+/// Serves the language server protocol over stdin and stdout until the
+/// client asks it to shut down.
+pub fn run() -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    Server::default().main_loop(&connection)?;
+
+    // The writer thread only finishes once every sender is gone.
+    drop(connection);
+    io_threads.join()?;
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Url, String>,
+}
+
+impl Server {
+    fn main_loop(&mut self, connection: &Connection) -> anyhow::Result<()> {
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.handle_request(request);
+                    connection.sender.send(Message::Response(response))?;
+                }
+                Message::Notification(notification) => {
+                    if let Some(uri) = self.handle_notification(notification)? {
+                        self.publish_diagnostics(connection, uri)?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => {
+                self.dispatch(request, |server, params: HoverParams| server.hover(params))
+            }
+            GotoDefinition::METHOD => self
+                .dispatch(request, |server, params: GotoDefinitionParams| {
+                    server.definition(params)
+                }),
+            Completion::METHOD => self.dispatch(request, |server, params: CompletionParams| {
+                server.completion(params)
+            }),
+            DocumentSymbolRequest::METHOD => self
+                .dispatch(request, |server, params: DocumentSymbolParams| {
+                    server.symbols(params)
+                }),
+            _ => {
+                return Response::new_err(
+                    id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("unsupported request '{}'", request.method),
+                )
+            }
+        };
+
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err(e) => invalid_params(id, e),
+        }
+    }
+
+    fn dispatch<P, R>(
+        &self,
+        request: Request,
+        handler: impl FnOnce(&Self, P) -> Option<R>,
+    ) -> Result<serde_json::Value, serde_json::Error>
+    where
+        P: serde::de::DeserializeOwned,
+        R: serde::Serialize,
+    {
+        let params = serde_json::from_value(request.params)?;
+        serde_json::to_value(handler(self, params))
+    }
+
+    /// Updates the stored documents, returning the one whose diagnostics
+    /// need to be published again.
+    fn handle_notification(&mut self, notification: Notification) -> anyhow::Result<Option<Url>> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents
+                    .insert(uri.clone(), params.text_document.text);
+                Ok(Some(uri))
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                // Only full sync is advertised, so the last change is the whole text.
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.documents.insert(uri.clone(), change.text);
+                }
+                Ok(Some(uri))
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                Ok(Some(uri))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn publish_diagnostics(&self, connection: &Connection, uri: Url) -> anyhow::Result<()> {
+        let diagnostics = match self.documents.get(&uri) {
+            Some(source) => {
+                let index = LineIndex::new(source);
+                Analysis::new(source)
+                    .diagnostics()
+                    .into_iter()
+                    .map(|problem| Diagnostic {
+                        range: index.range(problem.span),
+                        severity: Some(match problem.severity {
+                            Severity::Error => DiagnosticSeverity::ERROR,
+                            Severity::Warning => DiagnosticSeverity::WARNING,
+                            Severity::Information => DiagnosticSeverity::INFORMATION,
+                        }),
+                        code: problem.code.map(NumberOrString::String),
+                        source: Some("jml".to_owned()),
+                        message: problem.message,
+                        ..Default::default()
+                    })
+                    .collect()
+            }
+            // Closed documents have their diagnostics cleared.
+            None => vec![],
+        };
+
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        };
+        connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                PublishDiagnostics::METHOD.to_owned(),
+                params,
+            )))?;
+
+        Ok(())
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let source = self.documents.get(&position.text_document.uri)?;
+        let offset = LineIndex::new(source).offset(position.position);
+
+        let markdown = Analysis::new(source).hover(offset)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: markdown,
+            }),
+            range: None,
+        })
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let position = params.text_document_position_params;
+        let uri = position.text_document.uri;
+        let source = self.documents.get(&uri)?;
+        let index = LineIndex::new(source);
+
+        let span = Analysis::new(source).definition(index.offset(position.position))?;
+
+        Some(GotoDefinitionResponse::Scalar(Location {
+            uri,
+            range: index.range(span),
+        }))
+    }
+
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let position = params.text_document_position;
+        let source = self.documents.get(&position.text_document.uri)?;
+        let offset = LineIndex::new(source).offset(position.position);
+
+        let items = Analysis::new(source)
+            .completions(offset)
+            .into_iter()
+            .map(|completion| CompletionItem {
+                label: completion.label,
+                kind: Some(match completion.kind {
+                    CompletionKind::Function => CompletionItemKind::FUNCTION,
+                    CompletionKind::Variable => CompletionItemKind::VARIABLE,
+                    CompletionKind::Parameter => CompletionItemKind::VARIABLE,
+                }),
+                detail: completion.detail,
+                ..Default::default()
+            })
+            .collect();
+
+        Some(CompletionResponse::Array(items))
+    }
+
+    fn symbols(&self, params: DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+        let source = self.documents.get(&params.text_document.uri)?;
+        let index = LineIndex::new(source);
+
+        #[allow(deprecated)]
+        let symbols = Analysis::new(source)
+            .symbols()
+            .into_iter()
+            .map(|symbol| DocumentSymbol {
+                name: symbol.name,
+                detail: symbol.detail,
+                kind: match symbol.kind {
+                    SymbolKind::Function => lsp_types::SymbolKind::FUNCTION,
+                    SymbolKind::Variable => lsp_types::SymbolKind::VARIABLE,
+                },
+                tags: None,
+                deprecated: None,
+                range: index.range(symbol.span),
+                selection_range: index.range(symbol.name_span),
+                children: None,
+            })
+            .collect();
+
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+}
+
+fn invalid_params(id: RequestId, error: serde_json::Error) -> Response {
+    Response::new_err(
+        id,
+        lsp_server::ErrorCode::InvalidParams as i32,
+        error.to_string(),
+    )
+}
@@ -0,0 +1,50 @@
+use lsp_types::{Position, Range};
+
+/// Converts between byte offsets and LSP positions, whose columns are
+/// counted in UTF-16 code units.
+pub(crate) struct LineIndex<'source> {
+    source: &'source str,
+    line_starts: Vec<usize>,
+}
+
+impl<'source> LineIndex<'source> {
+    pub(crate) fn new(source: &'source str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        LineIndex {
+            source,
+            line_starts,
+        }
+    }
+
+    pub(crate) fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.source.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let character = self.source[line_start..offset].encode_utf16().count();
+
+        Position::new(line as u32, character as u32)
+    }
+
+    pub(crate) fn range(&self, span: std::ops::Range<usize>) -> Range {
+        Range::new(self.position(span.start), self.position(span.end))
+    }
+
+    pub(crate) fn offset(&self, position: Position) -> usize {
+        let Some(&line_start) = self.line_starts.get(position.line as usize) else {
+            return self.source.len();
+        };
+
+        let mut units = 0;
+        for (i, c) in self.source[line_start..].char_indices() {
+            if units >= position.character as usize || c == '\n' {
+                return line_start + i;
+            }
+            units += c.len_utf16();
+        }
+
+        self.source.len()
+    }
+}
@@ -0,0 +1,188 @@
+use lsp::analysis::{Analysis, CompletionKind, Severity, SymbolKind};
+
+fn offset_of(source: &str, needle: &str) -> usize {
+    source.find(needle).expect("needle not in source")
+}
+
+#[test]
+fn test_parse_error_is_reported() {
+    let source = "x = 1 +\n---\nx";
+    let problems = Analysis::new(source).diagnostics();
+
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].severity, Severity::Error);
+    assert_eq!(problems[0].span, 8..11);
+}
+
+#[test]
+fn test_constant_type_error_is_reported() {
+    let source = "x = 1 + true\n---\nx";
+    let problems = Analysis::new(source).diagnostics();
+
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].span, 4..12);
+    assert_eq!(
+        problems[0].code.as_deref(),
+        Some("type_error::invalid_binary_operator")
+    );
+}
+
+#[test]
+fn test_division_by_zero_is_reported() {
+    let source = "total = payload.total + 10 / (2 - 2)\n---\ntotal";
+    let problems = Analysis::new(source).diagnostics();
+
+    let division = problems
+        .iter()
+        .find(|p| p.code.as_deref() == Some("eval::division_by_zero"));
+    assert!(division.is_some(), "{:?}", problems);
+}
+
+#[test]
+fn test_integer_overflow_is_reported() {
+    let source = "big = 9223372036854775807 + 1\n---\n[big, -9223372036854775808 / -1]";
+    let problems = Analysis::new(source).diagnostics();
+
+    let overflows = problems
+        .iter()
+        .filter(|p| p.code.as_deref() == Some("eval::overflow"))
+        .count();
+    assert_eq!(overflows, 2, "{:?}", problems);
+}
+
+#[test]
+fn test_argument_count_mismatch_is_reported() {
+    let source = "add = \\a b. a + b\n---\n[add(1), map([1], \\x. x, 2)]";
+    let problems = Analysis::new(source).diagnostics();
+
+    let mismatches: Vec<_> = problems
+        .iter()
+        .filter(|p| p.code.as_deref() == Some("type_error::argument_count_mismatch"))
+        .map(|p| &source[p.span.clone()])
+        .collect();
+    assert_eq!(mismatches, vec!["add(1)", "map([1], \\x. x, 2)"]);
+}
+
+#[test]
+fn test_unbound_variable_is_information() {
+    let source = "double = \\x. x * 2\n---\ndouble(payload)";
+    let problems = Analysis::new(source).diagnostics();
+
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].severity, Severity::Information);
+    assert_eq!(&source[problems[0].span.clone()], "payload");
+}
+
+#[test]
+fn test_now_is_bound_by_the_host() {
+    let problems = Analysis::new("x = 1\n---\nnow").diagnostics();
+    assert!(problems.is_empty());
+}
+
+#[test]
+fn test_definition_of_header_binding() {
+    let source = "total = 10\n---\ntotal * 2";
+    let analysis = Analysis::new(source);
+
+    let use_site = source.rfind("total").unwrap() + 1;
+    assert_eq!(analysis.definition(use_site), Some(0..5));
+}
+
+#[test]
+fn test_definition_of_lambda_param_prefers_innermost_scope() {
+    let source = "x = 1\n---\n\\x. x + 1";
+    let analysis = Analysis::new(source);
+
+    let use_site = source.rfind("x +").unwrap();
+    let param = offset_of(source, "\\x") + 1;
+    assert_eq!(analysis.definition(use_site), Some(param..param + 1));
+}
+
+#[test]
+fn test_comprehension_binding_is_in_scope() {
+    let source = "users = []\n---\n[u.name for u in users if u.active]";
+    let analysis = Analysis::new(source);
+
+    assert!(analysis.diagnostics().is_empty());
+    let binding = offset_of(source, "u in");
+    assert_eq!(
+        analysis.definition(offset_of(source, "u.active")),
+        Some(binding..binding + 1)
+    );
+    assert_eq!(
+        analysis.definition(offset_of(source, "u.name")),
+        Some(binding..binding + 1)
+    );
+}
+
+#[test]
+fn test_definition_of_later_binding_wins() {
+    let source = "x = 1\nx = 2\n---\nx";
+    let analysis = Analysis::new(source);
+
+    assert_eq!(analysis.definition(source.len() - 1), Some(6..7));
+}
+
+#[test]
+fn test_hover_shows_inferred_type() {
+    let source = "greeting = \"hi\" ++ \" there\"\n---\ngreeting";
+    let hover = Analysis::new(source).hover(source.len() - 1).unwrap();
+
+    assert!(hover.contains("greeting: String"), "{}", hover);
+}
+
+#[test]
+fn test_hover_shows_std_lib_signature() {
+    let source = "map([1, 2], \\x. x)";
+    let hover = Analysis::new(source).hover(1).unwrap();
+
+    assert!(hover.contains("map(list, lambda)"), "{}", hover);
+}
+
+#[test]
+fn test_completion_includes_std_lib_header_and_params() {
+    let source = "limit = 3\n---\n\\item. item";
+    let completions = Analysis::new(source).completions(source.len());
+
+    let find = |label: &str| completions.iter().find(|c| c.label == label);
+    assert_eq!(find("filter").unwrap().kind, CompletionKind::Function);
+    assert_eq!(find("limit").unwrap().kind, CompletionKind::Variable);
+    assert_eq!(find("item").unwrap().kind, CompletionKind::Parameter);
+}
+
+#[test]
+fn test_completion_survives_parse_errors() {
+    let source = "limit = 3\n---\nlimit +";
+    let completions = Analysis::new(source).completions(source.len());
+
+    assert!(completions.iter().any(|c| c.label == "limit"));
+    assert!(completions.iter().any(|c| c.label == "reduce"));
+}
+
+#[test]
+fn test_symbols_for_header_statements() {
+    let source = "base = 2\nsquare = \\x. x * x\n---\nsquare(base)";
+    let symbols = Analysis::new(source).symbols();
+
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0].name, "base");
+    assert_eq!(symbols[0].kind, SymbolKind::Variable);
+    assert_eq!(symbols[0].detail.as_deref(), Some("Int"));
+    assert_eq!(symbols[1].name, "square");
+    assert_eq!(symbols[1].kind, SymbolKind::Function);
+    assert_eq!(symbols[1].name_span, 9..15);
+}
+
+#[test]
+fn test_every_syntax_error_is_reported() {
+    let source = "x = [1, , 2]\ny = if x = 1 then 2 else 3\n---\nx";
+    let problems = Analysis::new(source).diagnostics();
+
+    let spans: Vec<_> = problems.iter().map(|p| &source[p.span.clone()]).collect();
+    assert_eq!(spans, vec![",", "="]);
+    assert!(
+        problems[1].message.contains("`==`"),
+        "{}",
+        problems[1].message
+    );
+}
@@ -30,6 +30,37 @@ pub enum Key<'source> {
     Expression(Expression<'source>),
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjectMember<'source> {
+    /// `key: value`, or `key: value if condition` to only add the entry when
+    /// the condition holds.
+    Entry {
+        key: Key<'source>,
+        value: Expression<'source>,
+        condition: Option<Expression<'source>>,
+    },
+    /// `...object`, which adds every entry of another object.
+    Spread(Expression<'source>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListElement<'source> {
+    Item(Expression<'source>),
+    /// `...list`, which adds every element of another list.
+    Spread(Expression<'source>),
+}
+
+/// A clause of a comprehension. Each `for` loops over the rest of the
+/// comprehension, and each `if` skips the element when it doesn't hold.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ComprehensionClause<'source> {
+    For {
+        binding: Identifier<'source>,
+        iterable: Expression<'source>,
+    },
+    If(Expression<'source>),
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum ExpressionKind<'source> {
     Null,
@@ -37,16 +68,39 @@ pub enum ExpressionKind<'source> {
     Bool(bool),
     Int(i64),
     String(&'source str),
-    Object(Vec<(Key<'source>, Expression<'source>)>),
-    List(Vec<Expression<'source>>),
+    Object(Vec<ObjectMember<'source>>),
+    List(Vec<ListElement<'source>>),
+    /// `[element for x in xs if condition]`, starting with a `for` clause.
+    ListComprehension {
+        element: Box<Expression<'source>>,
+        clauses: Vec<ComprehensionClause<'source>>,
+    },
+    /// `{ key: value for x in xs if condition }`, starting with a `for` clause.
+    ObjectComprehension {
+        key: Box<Key<'source>>,
+        value: Box<Expression<'source>>,
+        clauses: Vec<ComprehensionClause<'source>>,
+    },
     Variable(&'source str),
     IndexAccess {
         target: Box<Expression<'source>>,
         index: Box<Expression<'source>>,
+        /// `?[...]`, which yields `null` instead of failing on a `null` target.
+        optional: bool,
+    },
+    /// `target[start:end:step]`, where every bound may be left out.
+    Slice {
+        target: Box<Expression<'source>>,
+        start: Option<Box<Expression<'source>>>,
+        end: Option<Box<Expression<'source>>>,
+        step: Option<Box<Expression<'source>>>,
+        optional: bool,
     },
     Selector {
         target: Box<Expression<'source>>,
         key: &'source str,
+        /// `?.`, which yields `null` instead of failing on a `null` target.
+        optional: bool,
     },
 
     UnaryOp {
@@ -94,6 +148,7 @@ pub enum BinaryOp {
     And,
     Or,
     Concat,
+    Coalesce,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -0,0 +1,291 @@
+use lalrpop_util::ParseError;
+use lexer::{errors::LexingError, token::Token, Lexer};
+use miette::{Diagnostic, SourceSpan};
+use thiserror::Error;
+
+/// Every syntax error found in a source, reported together.
+#[derive(Error, Diagnostic, Debug, Clone, PartialEq)]
+#[error(
+    "found {} syntax error{}",
+    errors.len(),
+    if errors.len() == 1 { "" } else { "s" }
+)]
+pub struct ParseErrors {
+    #[source_code]
+    pub src: String,
+
+    #[related]
+    pub errors: Vec<SyntaxError>,
+}
+
+impl ParseErrors {
+    pub fn new(source: &str, mut errors: Vec<SyntaxError>) -> Self {
+        errors.sort_by_key(|error| error.span().map(|span| span.offset()));
+
+        ParseErrors {
+            src: source.to_owned(),
+            errors,
+        }
+    }
+}
+
+#[derive(Error, Diagnostic, Debug, Clone, PartialEq)]
+pub enum SyntaxError {
+    #[error("unexpected {found}")]
+    #[diagnostic(code(parser::unexpected_token))]
+    UnexpectedToken {
+        found: String,
+        #[label("{expected}")]
+        span: SourceSpan,
+        expected: String,
+        #[help]
+        help: Option<String>,
+    },
+
+    #[error("unexpected end of input")]
+    #[diagnostic(code(parser::unexpected_eof))]
+    UnexpectedEof {
+        #[label("{expected}")]
+        span: SourceSpan,
+        expected: String,
+        #[help]
+        help: Option<String>,
+    },
+
+    #[error("invalid token")]
+    #[diagnostic(code(parser::invalid_token))]
+    InvalidToken {
+        #[label("this is not valid here")]
+        span: SourceSpan,
+    },
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Lexing(LexingError),
+}
+
+impl SyntaxError {
+    pub fn span(&self) -> Option<SourceSpan> {
+        match self {
+            SyntaxError::UnexpectedToken { span, .. }
+            | SyntaxError::UnexpectedEof { span, .. }
+            | SyntaxError::InvalidToken { span } => Some(*span),
+            SyntaxError::Lexing(error) => error.span(),
+        }
+    }
+
+    pub(crate) fn new(source: &str, error: ParseError<usize, Token, LexingError>) -> Self {
+        match error {
+            ParseError::InvalidToken { location } => SyntaxError::InvalidToken {
+                span: (location, 0).into(),
+            },
+            ParseError::UnrecognizedEof { location, expected } => SyntaxError::UnexpectedEof {
+                span: (location, 0).into(),
+                help: hint(source, None, None, &expected),
+                expected: describe_expected(&expected),
+            },
+            ParseError::UnrecognizedToken {
+                token: (l, token, r),
+                expected,
+            } => SyntaxError::UnexpectedToken {
+                found: format!("`{}`", &source[l..r]),
+                span: (l, r - l).into(),
+                help: leading_dot_hint(source, &token, r)
+                    .or_else(|| hint(source, previous_token(source, l), Some(&token), &expected)),
+                expected: describe_expected(&expected),
+            },
+            ParseError::ExtraToken { token: (l, _, r) } => SyntaxError::UnexpectedToken {
+                found: format!("`{}`", &source[l..r]),
+                span: (l, r - l).into(),
+                expected: "expected end of input".to_owned(),
+                help: None,
+            },
+            ParseError::User { error } => SyntaxError::Lexing(error),
+        }
+    }
+}
+
+/// Drops errors that are most likely a consequence of recovering from the
+/// previous one: those with nothing expected, or with at most one token
+/// between them and the previous error.
+pub(crate) fn without_cascades<'source>(
+    source: &'source str,
+    mut errors: Vec<ParseError<usize, Token<'source>, LexingError>>,
+) -> Vec<ParseError<usize, Token<'source>, LexingError>> {
+    let token_starts: Vec<usize> = Lexer::new(source)
+        .filter_map(Result::ok)
+        .map(|(start, _, _)| start)
+        .collect();
+
+    errors.sort_by_key(|error| location(error).map(|(start, _)| start));
+
+    let mut previous_end = None;
+    errors.retain(|error| {
+        let Some((start, end)) = location(error) else {
+            return true;
+        };
+        let nothing_expected = match error {
+            ParseError::UnrecognizedEof { expected, .. }
+            | ParseError::UnrecognizedToken { expected, .. } => expected.is_empty(),
+            _ => false,
+        };
+
+        let cascade = previous_end.is_some_and(|previous_end| {
+            let between = token_starts
+                .iter()
+                .filter(|&&token| previous_end <= token && token < start)
+                .count();
+            nothing_expected || between <= 1
+        });
+
+        previous_end = Some(end);
+        !cascade
+    });
+
+    errors
+}
+
+/// Whether the parser stumbled on the stand-in for a lexing error.
+pub(crate) fn at_error_token(error: &ParseError<usize, Token, LexingError>) -> bool {
+    matches!(
+        error,
+        ParseError::UnrecognizedToken {
+            token: (_, Token::Error, _),
+            ..
+        } | ParseError::ExtraToken {
+            token: (_, Token::Error, _)
+        }
+    )
+}
+
+fn location(error: &ParseError<usize, Token, LexingError>) -> Option<(usize, usize)> {
+    match error {
+        ParseError::InvalidToken { location } | ParseError::UnrecognizedEof { location, .. } => {
+            Some((*location, *location))
+        }
+        ParseError::UnrecognizedToken {
+            token: (l, _, r), ..
+        }
+        | ParseError::ExtraToken { token: (l, _, r) } => Some((*l, *r)),
+        ParseError::User { .. } => None,
+    }
+}
+
+const OPERATORS: [&str; 16] = [
+    "%", "^", "++", "+", "-", "*", "/", "==", "!=", "<", ">", "<=", ">=", "&&", "||", "??",
+];
+
+/// Renders LALRPOP's terminal names the way they are written in a script.
+fn describe_expected(expected: &[String]) -> String {
+    let terminals: Vec<&str> = expected
+        .iter()
+        .map(|terminal| terminal.trim_matches('"'))
+        .collect();
+
+    let operators = terminals
+        .iter()
+        .filter(|terminal| OPERATORS.contains(terminal))
+        .count();
+
+    let mut names: Vec<String> = vec![];
+    for terminal in &terminals {
+        let name = match *terminal {
+            // A long run of operators is more noise than help.
+            operator if OPERATORS.contains(&operator) && operators > 3 => "an operator".to_owned(),
+            "HEADER" => "`---`".to_owned(),
+            "IDENTIFIER" => "a name".to_owned(),
+            "STRING_LITERAL" => "a string".to_owned(),
+            "INT_LITERAL" => "an integer".to_owned(),
+            "FLOAT_LITERAL" => "a float".to_owned(),
+            "BOOL_LITERAL" => "`true` or `false`".to_owned(),
+            "NULL" => "`null`".to_owned(),
+            "IF" => "`if`".to_owned(),
+            "THEN" => "`then`".to_owned(),
+            "ELSE" => "`else`".to_owned(),
+            "FOR" => "`for`".to_owned(),
+            "IN" => "`in`".to_owned(),
+            "\\\\" => "`\\`".to_owned(),
+            terminal => format!("`{}`", terminal),
+        };
+
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    match names.as_slice() {
+        [] => "unexpected here".to_owned(),
+        [only] => format!("expected {}", only),
+        [first, second] => format!("expected {} or {}", first, second),
+        [init @ .., last] => format!("expected one of {} or {}", init.join(", "), last),
+    }
+}
+
+/// `.5` is lexed as a selector dot followed by an integer.
+fn leading_dot_hint(source: &str, token: &Token, end: usize) -> Option<String> {
+    let digits: String = source[end..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '_')
+        .collect();
+
+    (*token == Token::Dot && !digits.is_empty()).then(|| {
+        format!(
+            "numbers need a digit before the decimal point, as in `0.{}`",
+            digits
+        )
+    })
+}
+
+/// The last token that ends at or before `offset`.
+fn previous_token(source: &str, offset: usize) -> Option<Token<'_>> {
+    Lexer::new(source)
+        .filter_map(Result::ok)
+        .take_while(|(_, _, end)| *end <= offset)
+        .last()
+        .map(|(_, token, _)| token)
+}
+
+/// Suggestions for the mistakes people make most often.
+fn hint(
+    source: &str,
+    previous: Option<Token>,
+    found: Option<&Token>,
+    expected: &[String],
+) -> Option<String> {
+    let expects = |terminal: &str| expected.iter().any(|e| e == terminal);
+
+    match found {
+        // After a complete header binding a new one may start, so `=` is fine there.
+        Some(Token::Assign) if expects("\"==\"") && !expects("IDENTIFIER") => {
+            return Some(
+                "use `==` to compare values; `=` only binds names in the header".to_owned(),
+            )
+        }
+        Some(Token::Comma) if expects("\".\"") && !expects("\"+\"") => {
+            return Some("lambda parameters are separated by spaces: `\\a b. a + b`".to_owned());
+        }
+        Some(Token::Comma)
+            if matches!(
+                previous,
+                Some(Token::Comma | Token::LBracket | Token::LParen | Token::LBrace)
+            ) =>
+        {
+            return Some(
+                "remove the extra comma; elements, entries and arguments are separated by a single comma"
+                    .to_owned(),
+            )
+        }
+        Some(Token::Comma) => return None,
+        Some(Token::Arrow) => {
+            return Some("lambdas are written as `\\x y. body`".to_owned());
+        }
+        _ => {}
+    }
+
+    let has_header = Lexer::new(source).any(|token| matches!(token, Ok((_, Token::Header, _))));
+    if !has_header && (expects("HEADER") || expects("\"=\"")) {
+        return Some("separate the header bindings from the body with a `---` line".to_owned());
+    }
+
+    None
+}
@@ -1,22 +1,137 @@
-use lalrpop_util::{lalrpop_mod, ParseError};
+use errors::{ParseErrors, SyntaxError};
+use lalrpop_util::{lalrpop_mod, ErrorRecovery, ParseError};
 use lexer::{errors::LexingError, token::Token, Lexer};
 
 pub mod ast;
+pub mod errors;
+mod literal;
 lalrpop_mod!(
     #[allow(clippy::all, unused_variables, unused_imports)]
     #[rustfmt::skip]
     pub jml);
 
-pub fn parse(source: &str) -> Result<ast::Jml<'_>, ParseError<usize, Token<'_>, LexingError>> {
-    let lexer = Lexer::new(source);
-    jml::JmlParser::new().parse(source, lexer)
+pub fn parse(source: &str) -> Result<ast::Jml<'_>, ParseErrors> {
+    parse_at(source, 0)
+}
+
+pub fn parse_statement(source: &str) -> Result<ast::Statement<'_>, ParseErrors> {
+    parse_statement_at(source, 0)
+}
+
+pub fn parse_expression(source: &str) -> Result<ast::Expression<'_>, ParseErrors> {
+    parse_expression_at(source, 0)
+}
+
+/// Parses `source` as if it started `offset` bytes into a larger text, so
+/// that the spans of the tree point into that text. Errors still point into
+/// `source`.
+pub fn parse_at(source: &str, offset: usize) -> Result<ast::Jml<'_>, ParseErrors> {
+    let mut errors = vec![];
+    let mut lexing_errors = vec![];
+    let result = jml::JmlParser::new().parse(
+        source,
+        &mut errors,
+        tokens(source, offset, &mut lexing_errors),
+    );
+    collect_errors(source, offset, result, errors, lexing_errors)
+}
+
+/// [`parse_statement`] for a text starting at `offset`, as [`parse_at`].
+pub fn parse_statement_at(source: &str, offset: usize) -> Result<ast::Statement<'_>, ParseErrors> {
+    let mut errors = vec![];
+    let mut lexing_errors = vec![];
+    let result = jml::StatementParser::new().parse(
+        source,
+        &mut errors,
+        tokens(source, offset, &mut lexing_errors),
+    );
+    collect_errors(source, offset, result, errors, lexing_errors)
+}
+
+/// [`parse_expression`] for a text starting at `offset`, as [`parse_at`].
+pub fn parse_expression_at(
+    source: &str,
+    offset: usize,
+) -> Result<ast::Expression<'_>, ParseErrors> {
+    let mut errors = vec![];
+    let mut lexing_errors = vec![];
+    let result = jml::ExpressionParser::new().parse(
+        source,
+        &mut errors,
+        tokens(source, offset, &mut lexing_errors),
+    );
+    collect_errors(source, offset, result, errors, lexing_errors)
+}
+
+/// The tokens of `source`, placed `offset` bytes further, with every lexing
+/// error set aside in `errors` and replaced by [`Token::Error`], which the
+/// parser recovers from like any other unexpected token.
+fn tokens<'source, 'errors>(
+    source: &'source str,
+    offset: usize,
+    errors: &'errors mut Vec<LexingError>,
+) -> impl Iterator<Item = Result<(usize, Token<'source>, usize), LexingError>> + 'errors
+where
+    'source: 'errors,
+{
+    Lexer::new(source).map(move |token| {
+        let (l, token, r) = token.unwrap_or_else(|error| {
+            let span = error.span().unwrap_or_else(|| (source.len(), 0).into());
+            errors.push(error);
+            (span.offset(), Token::Error, span.offset() + span.len())
+        });
+        Ok((l + offset, token, r + offset))
+    })
+}
+
+/// Fails if the lexer or the parser had to recover from any error, even if
+/// the parser then produced a tree. The errors are moved back by `offset`, to
+/// point into `source`.
+fn collect_errors<'source, T>(
+    source: &'source str,
+    offset: usize,
+    result: Result<T, ParseError<usize, Token<'source>, LexingError>>,
+    recovered: Vec<ErrorRecovery<usize, Token<'source>, LexingError>>,
+    lexing_errors: Vec<LexingError>,
+) -> Result<T, ParseErrors> {
+    let mut errors: Vec<_> = recovered
+        .into_iter()
+        .map(|recovery| recovery.error)
+        .collect();
+
+    match result {
+        Ok(value) if errors.is_empty() && lexing_errors.is_empty() => return Ok(value),
+        Ok(_) => {}
+        Err(error) => errors.push(error),
+    }
+
+    let errors = errors
+        .into_iter()
+        .map(|error| error.map_location(|location| location - offset))
+        .collect();
+
+    // The lexing error already explains why its stand-in token was unexpected.
+    let errors = errors::without_cascades(source, errors)
+        .into_iter()
+        .filter(|error| !errors::at_error_token(error))
+        .map(|error| SyntaxError::new(source, error))
+        .chain(lexing_errors.into_iter().map(SyntaxError::Lexing))
+        .collect();
+    Err(ParseErrors::new(source, errors))
 }
 
 #[cfg(test)]
 mod tests {
     use super::jml;
-    use crate::ast::{ExpressionKind, StatementKind};
+    use crate::{
+        ast::{
+            BinaryOp, ComprehensionClause, Expression, ExpressionKind, Key, ListElement, Located,
+            ObjectMember, StatementKind,
+        },
+        errors::SyntaxError,
+    };
     use lexer::Lexer;
+    use miette::Diagnostic;
     #[test]
     fn test_parse_jml() {
         let source = r#"
@@ -27,7 +142,9 @@ mod tests {
             "#;
 
         let lexer = Lexer::new(source);
-        let jml = jml::JmlParser::new().parse(source, lexer).unwrap();
+        let jml = jml::JmlParser::new()
+            .parse(source, &mut vec![], lexer)
+            .unwrap();
         assert_eq!(jml.header.len(), 2);
         match &jml.header[0].node {
             StatementKind::Bind {
@@ -68,7 +185,9 @@ mod tests {
     fn test_parse_statement() {
         let source = "x = 42";
         let lexer = Lexer::new(source);
-        let statement = jml::StatementParser::new().parse(source, lexer).unwrap();
+        let statement = jml::StatementParser::new()
+            .parse(source, &mut vec![], lexer)
+            .unwrap();
         match statement.node {
             StatementKind::Bind {
                 identifier,
@@ -88,7 +207,9 @@ mod tests {
     fn test_parse_expression() {
         let source = "42";
         let lexer = Lexer::new(source);
-        let expression = jml::ExpressionParser::new().parse(source, lexer).unwrap();
+        let expression = jml::ExpressionParser::new()
+            .parse(source, &mut vec![], lexer)
+            .unwrap();
 
         if let ExpressionKind::Int(value) = expression.node {
             assert_eq!(value, 42);
@@ -102,17 +223,35 @@ mod tests {
         let source = r#"{"key1": 42, "key2": "value"}"#;
 
         let lexer = Lexer::new(source);
-        let expression = jml::ExpressionParser::new().parse(source, lexer).unwrap();
+        let expression = jml::ExpressionParser::new()
+            .parse(source, &mut vec![], lexer)
+            .unwrap();
 
         if let ExpressionKind::Object(map) = expression.node {
             assert_eq!(map.len(), 2);
-            if let ExpressionKind::Int(value) = map[0].1.node {
+            if let ObjectMember::Entry {
+                value:
+                    Expression {
+                        node: ExpressionKind::Int(value),
+                        ..
+                    },
+                ..
+            } = map[0]
+            {
                 assert_eq!(value, 42);
             } else {
                 panic!("Expected an Int expression for key1");
             }
 
-            if let ExpressionKind::String(value) = map[1].1.node {
+            if let ObjectMember::Entry {
+                value:
+                    Expression {
+                        node: ExpressionKind::String(value),
+                        ..
+                    },
+                ..
+            } = map[1]
+            {
                 assert_eq!(value, "value");
             } else {
                 panic!("Expected a String expression for key2");
@@ -126,15 +265,237 @@ mod tests {
     fn test_parse_list() {
         let source = "[1, 2, 3]";
         let lexer = Lexer::new(source);
-        let expression = jml::ExpressionParser::new().parse(source, lexer).unwrap();
+        let expression = jml::ExpressionParser::new()
+            .parse(source, &mut vec![], lexer)
+            .unwrap();
 
         if let ExpressionKind::List(vec) = expression.node {
             assert_eq!(vec.len(), 3);
-            assert_eq!(vec[0].node, ExpressionKind::Int(1));
-            assert_eq!(vec[1].node, ExpressionKind::Int(2));
-            assert_eq!(vec[2].node, ExpressionKind::Int(3));
+            let items: Vec<_> = vec
+                .iter()
+                .map(|elem| match elem {
+                    ListElement::Item(item) => &item.node,
+                    ListElement::Spread(_) => panic!("Expected list items"),
+                })
+                .collect();
+            assert_eq!(items[0], &ExpressionKind::Int(1));
+            assert_eq!(items[1], &ExpressionKind::Int(2));
+            assert_eq!(items[2], &ExpressionKind::Int(3));
         } else {
             panic!("Expected a List expression");
         }
     }
+
+    fn syntax_errors(source: &str) -> Vec<SyntaxError> {
+        crate::parse(source).unwrap_err().errors
+    }
+
+    fn help(error: &SyntaxError) -> Option<&str> {
+        match error {
+            SyntaxError::UnexpectedToken { help, .. } | SyntaxError::UnexpectedEof { help, .. } => {
+                help.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_reports_every_syntax_error() {
+        let source = "x = [1, , 2]
+y = if x = 1 then 2 else 3
+---
+{ a: x, b: y }";
+        let errors = syntax_errors(source);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].span().unwrap().offset(), 8);
+        assert_eq!(errors[1].span().unwrap().offset(), 22);
+    }
+
+    #[test]
+    fn test_parse_error_names_expected_tokens() {
+        let errors = syntax_errors(
+            "x = 1
+---
+if x then 1",
+        );
+
+        match &errors[0] {
+            SyntaxError::UnexpectedEof { expected, .. } => {
+                assert_eq!(expected, "expected `else`")
+            }
+            error => panic!("Expected an UnexpectedEof error, got {:?}", error),
+        }
+
+        let errors = syntax_errors("{ a: 1 b: 2 }");
+
+        match &errors[0] {
+            SyntaxError::UnexpectedToken {
+                found, expected, ..
+            } => {
+                assert_eq!(found, "`b`");
+                assert_eq!(expected, "expected one of `if`, `for`, `,` or `}`");
+            }
+            error => panic!("Expected an UnexpectedToken error, got {:?}", error),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_hints_missing_header() {
+        let errors = syntax_errors(
+            "x = 1
+y = 2
+x + y",
+        );
+
+        assert_eq!(errors.len(), 1);
+        assert!(help(&errors[0]).unwrap().contains("`---`"));
+    }
+
+    #[test]
+    fn test_parse_error_hints_comparison() {
+        let errors = syntax_errors("if a = 1 then 2 else 3");
+
+        assert!(help(&errors[0]).unwrap().contains("`==`"));
+    }
+
+    #[test]
+    fn test_parse_error_hints_extra_comma() {
+        let errors = syntax_errors("f(1,, 2)");
+        assert!(help(&errors[0]).unwrap().contains("comma"));
+
+        let errors = syntax_errors("[, 1]");
+        assert!(help(&errors[0]).unwrap().contains("comma"));
+
+        let errors = syntax_errors("{ a: 1 }, 2");
+        assert_eq!(help(&errors[0]), None);
+    }
+
+    #[test]
+    fn test_parse_recovers_after_lexing_errors() {
+        let errors = syntax_errors(
+            "a = 1 @ 2
+b = [1, , 2]
+c = if b = 1 then 2 else 3
+d = (1 +)
+---
+a",
+        );
+
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(matches!(errors[0], SyntaxError::Lexing(_)));
+    }
+
+    #[test]
+    fn test_parse_reports_lexing_errors_with_spans() {
+        let errors = syntax_errors("x = 1\n---\n{ a: x, b: @ }");
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], SyntaxError::Lexing(_)));
+        assert_eq!(errors[0].span(), Some((21, 1).into()));
+        assert_eq!(
+            errors[0].code().map(|code| code.to_string()).as_deref(),
+            Some("lexer::unexpected_character")
+        );
+    }
+
+    #[test]
+    fn test_parse_reports_syntax_errors_after_a_lexing_error() {
+        let errors = syntax_errors("x = 1\n---\n[x @ 1, if x then 2]");
+
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert_eq!(
+            errors[0].code().map(|code| code.to_string()).as_deref(),
+            Some("lexer::unexpected_character")
+        );
+        assert_eq!(
+            errors[1].code().map(|code| code.to_string()).as_deref(),
+            Some("parser::unexpected_token")
+        );
+    }
+
+    #[test]
+    fn test_parse_folds_negative_literals() {
+        let expression = crate::parse_expression("-9223372036854775808").unwrap();
+        assert_eq!(expression.node, ExpressionKind::Int(i64::MIN));
+
+        let expression = crate::parse_expression("-1[0]").unwrap();
+        assert!(matches!(expression.node, ExpressionKind::UnaryOp { .. }));
+
+        let expression = crate::parse_expression("-(1)").unwrap();
+        assert!(matches!(expression.node, ExpressionKind::UnaryOp { .. }));
+
+        let errors = syntax_errors("-(9223372036854775808)");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].code().map(|code| code.to_string()).as_deref(),
+            Some("lexer::integer_out_of_range")
+        );
+    }
+
+    #[test]
+    fn test_parse_error_hints_leading_dot() {
+        let errors = syntax_errors("[.5]");
+
+        assert!(help(&errors[0]).unwrap().contains("`0.5`"));
+    }
+
+    #[test]
+    fn test_parse_statement_and_expression_report_errors() {
+        assert!(crate::parse_statement("x = ").is_err());
+        assert!(crate::parse_expression("[1, 2").is_err());
+    }
+
+    #[test]
+    fn test_parse_coalesce_precedence() {
+        let expression = crate::parse_expression("a?.b ?? 0 > 5").unwrap();
+
+        let ExpressionKind::BinaryOp { op, lhs, .. } = expression.node else {
+            panic!("Expected a BinaryOp expression");
+        };
+        assert_eq!(op, BinaryOp::GT);
+        assert!(matches!(
+            lhs.node,
+            ExpressionKind::BinaryOp {
+                op: BinaryOp::Coalesce,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_comprehension_clauses() {
+        let expression =
+            crate::parse_expression("{ u.id: t for u in users if u.active for t in u.tags }")
+                .unwrap();
+
+        let ExpressionKind::ObjectComprehension { key, clauses, .. } = expression.node else {
+            panic!("Expected an ObjectComprehension expression");
+        };
+        assert!(matches!(*key, Key::Expression(_)));
+        assert!(matches!(
+            clauses.as_slice(),
+            [
+                ComprehensionClause::For {
+                    binding: Located { node: "u", .. },
+                    ..
+                },
+                ComprehensionClause::If(_),
+                ComprehensionClause::For {
+                    binding: Located { node: "t", .. },
+                    ..
+                },
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_parse_at_offset() {
+        let expression = crate::parse_expression_at("f(x)", 100).unwrap();
+        assert_eq!((expression.l, expression.r), (100, 104));
+
+        let errors = crate::parse_expression_at("1 +", 100).unwrap_err();
+        let spans: Vec<_> = errors.errors.iter().filter_map(SyntaxError::span).collect();
+        assert_eq!(spans, vec![(3, 0).into()]);
+    }
 }
@@ -0,0 +1,76 @@
+use lalrpop_util::{ErrorRecovery, ParseError};
+use lexer::{errors::LexingError, token::Token};
+
+use crate::ast::{Expression, ExpressionKind, UnaryOp};
+
+type Errors<'source> = Vec<ErrorRecovery<usize, Token<'source>, LexingError>>;
+
+/// Builds an integer literal, reporting it if it doesn't fit in an `i64`.
+///
+/// `9223372036854775808` is kept as `i64::MIN`, so that [`negate`] can fold
+/// `-9223372036854775808` and withdraw the error again.
+pub(crate) fn int<'source>(
+    errors: &mut Errors<'source>,
+    l: usize,
+    value: u64,
+    r: usize,
+) -> Expression<'source> {
+    if value > i64::MAX as u64 {
+        errors.push(ErrorRecovery {
+            error: ParseError::User {
+                error: LexingError::IntegerOutOfRange {
+                    span: (l, r - l).into(),
+                },
+            },
+            dropped_tokens: vec![],
+        });
+    }
+
+    Expression {
+        l,
+        r,
+        node: ExpressionKind::Int(value as i64),
+    }
+}
+
+/// Folds a minus sign directly in front of a number literal into the literal.
+pub(crate) fn negate<'source>(
+    source: &'source str,
+    errors: &mut Errors<'source>,
+    l: usize,
+    operand: Expression<'source>,
+    r: usize,
+) -> Expression<'source> {
+    // Only fold a literal written right after the minus, not one that is
+    // already negative or wrapped in parentheses.
+    let is_literal = source[l + 1..operand.l].trim().is_empty()
+        && operand.r == r
+        && source[operand.l..].starts_with(|c: char| c.is_ascii_digit());
+
+    let node = match operand.node {
+        ExpressionKind::Int(i64::MIN) if is_literal => {
+            errors.retain(|recovery| {
+                !matches!(
+                    &recovery.error,
+                    ParseError::User {
+                        error: LexingError::IntegerOutOfRange { span },
+                    } if span.offset() == operand.l
+                )
+            });
+            ExpressionKind::Int(i64::MIN)
+        }
+        // `-0` stays an operation so that every negative literal is below zero.
+        ExpressionKind::Int(value) if is_literal && value != 0 => ExpressionKind::Int(-value),
+        ExpressionKind::Float(value) if is_literal && value != 0.0 => ExpressionKind::Float(-value),
+        node => ExpressionKind::UnaryOp {
+            op: UnaryOp::Minus,
+            expr: Box::new(Expression {
+                l: operand.l,
+                r: operand.r,
+                node,
+            }),
+        },
+    };
+
+    Expression { l, r, node }
+}